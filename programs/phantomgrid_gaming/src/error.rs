@@ -142,4 +142,136 @@ pub enum PgError {
     // --- worker profile errors ---
     #[msg("Invalid payout method")]
     InvalidPayoutMethod,
+
+    // --- sealed bid errors ---
+    #[msg("Sealed bid reveal does not match commitment")]
+    InvalidBidCommitment,
+    #[msg("Sealed bid already revealed")]
+    BidAlreadyRevealed,
+
+    // --- order book errors ---
+    #[msg("Order book side is full")]
+    OrderBookFull,
+    #[msg("Order not found")]
+    OrderNotFound,
+    #[msg("Order would trade against own resting order")]
+    SelfTrade,
+    #[msg("No crossed orders to match")]
+    OrderBookNotCrossed,
+    #[msg("Order notional is below the order book minimum")]
+    OrderTooSmall,
+    #[msg("Item mint cannot be traded on an order book")]
+    InvalidOrderBookMint,
+
+    // --- listing update errors ---
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+    #[msg("Listing changed since expected version")]
+    ListingVersionMismatch,
+
+    // --- royalty split errors ---
+    #[msg("Invalid royalty split recipients or weights")]
+    InvalidRoyaltySplit,
+    #[msg("Royalty split account does not match listing")]
+    RoyaltySplitMismatch,
+
+    // --- metadata royalty errors ---
+    #[msg("Invalid or mismatched Metaplex metadata account")]
+    InvalidMetadata,
+    #[msg("Metadata creator shares must sum to 100")]
+    InvalidCreatorShares,
+    #[msg("Metadata account required when royalties are enforced")]
+    MetadataRequired,
+    #[msg("Items resolve to different creator royalties")]
+    MixedItemRoyalties,
+
+    // --- referral errors ---
+    #[msg("Buyer or seller cannot refer their own trade")]
+    SelfReferral,
+
+    // --- sweep errors ---
+    #[msg("Invalid sweep items or remaining accounts")]
+    InvalidSweep,
+    #[msg("Listing price exceeds the sweep item's max price")]
+    SweepPriceExceeded,
+    #[msg("No sweep item could be filled")]
+    SweepNothingFilled,
+
+    // --- private listing errors ---
+    #[msg("Buyer is not allowed on this private listing")]
+    BuyerNotAllowed,
+
+    // --- lazy mint errors ---
+    #[msg("Lazy minting is not enabled for this game")]
+    LazyMintDisabled,
+    #[msg("Invalid lazy mint metadata")]
+    InvalidLazyMetadata,
+    #[msg("Lazy mint metadata or mint does not match the auction leaf")]
+    LazyMintMismatch,
+    #[msg("Lazy item already minted")]
+    LazyItemAlreadyMinted,
+
+    // --- spending limit errors ---
+    #[msg("Rolling daily or weekly spending cap exceeded")]
+    SpendingCapExceeded,
+    #[msg("Guardian approval required for this amount")]
+    GuardianApprovalRequired,
+    #[msg("Player is in a self-exclusion cool-off")]
+    SelfExcluded,
+
+    // --- kyc tier errors ---
+    #[msg("Trade exceeds the max trade size for the player's KYC tier")]
+    KycTradeSizeExceeded,
+    #[msg("Daily volume limit for the player's KYC tier exceeded")]
+    KycDailyVolumeExceeded,
+    #[msg("KYC provider registry is full")]
+    KycProviderRegistryFull,
+
+    // --- jurisdiction errors ---
+    #[msg("Trading is not available in the player's jurisdiction")]
+    JurisdictionBlocked,
+    #[msg("Invalid country code (expected ISO 3166-1 alpha-2)")]
+    InvalidCountryCode,
+
+    // --- fee tier errors ---
+    #[msg("Invalid maker/taker fee tier table")]
+    InvalidFeeTiers,
+
+    // --- staker discount errors ---
+    #[msg("Invalid PDOX stake account")]
+    InvalidStakeAccount,
+
+    // --- PDOX fee payment errors ---
+    #[msg("Paying protocol fees in PDOX is disabled")]
+    PdoxFeesDisabled,
+    #[msg("Invalid PDOX fee payment accounts")]
+    InvalidPdoxPayment,
+    #[msg("Invalid PDOX price source")]
+    InvalidPdoxPriceSource,
+    #[msg("PDOX price pool liquidity too low")]
+    PdoxLiquidityTooLow,
+    #[msg("PDOX pool price deviates too far from the reference price")]
+    PdoxPriceDeviation,
+    #[msg("Invalid PDOX fee sink")]
+    InvalidPdoxFeeSink,
+
+    // --- fee router errors ---
+    #[msg("Fee router splits must sum to 100%")]
+    InvalidFeeRouter,
+    #[msg("Fee router is not configured")]
+    FeeRouterDisabled,
+    #[msg("Protocol fees are routed; use distribute_protocol_fees")]
+    FeeRouterActive,
+    #[msg("Fee route destination mismatch")]
+    FeeRouteMismatch,
+
+    // --- game role errors ---
+    #[msg("Invalid game roles")]
+    InvalidGameRoles,
+    #[msg("Game roles account is full")]
+    GameRolesFull,
+
+    // --- migration errors ---
+    #[msg("Account is not a program account of the expected type")]
+    InvalidAccountLayout,
 }
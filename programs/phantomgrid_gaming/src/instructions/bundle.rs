@@ -0,0 +1,898 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::game_roles::is_owner_or_role;
+use crate::{
+    create_pda_account, enter_execution_game, exit_execution_game, instructions, metadata,
+    BundleCancelled, BundleListing, BundleListingCreated, BundleSold, GameConfig, GameRole,
+    GameRoles, GlobalConfig, ListingStatus, PgError, PlayerLedger, RoyaltyPaid, RoyaltySplit,
+    BPS_DENOM, BUNDLE_LISTING_SEED, CONFIG_SEED, ESCROW_SEED, GAME_ROLES_SEED, GAME_SEED,
+    LEDGER_SEED, MAX_BULK_QTY, MAX_BUNDLE_ITEMS, MAX_LISTING_DURATION_SECS, MAX_ROYALTY_BPS,
+    MIN_ROYALTY_BPS,
+};
+
+// ======================================================================
+// BUNDLE LISTING INSTRUCTIONS
+// ======================================================================
+
+/// Accounts passed per component in `remaining_accounts`:
+/// `[item_mint, escrow_item_ata, party_item_ata]` (party = seller or buyer)
+pub const BUNDLE_ACCOUNTS_PER_ITEM: usize = 3;
+
+/// Escrow PDA for one bundle component
+pub fn bundle_escrow_address(
+    bundle: &Pubkey,
+    item_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ESCROW_SEED, bundle.as_ref(), item_mint.as_ref()],
+        program_id,
+    )
+}
+
+/// One validated bundle component taken from `remaining_accounts`
+pub(crate) struct BundleComponent<'info> {
+    pub item_mint: InterfaceAccount<'info, Mint>,
+    pub escrow_item_ata: &'info AccountInfo<'info>,
+    pub escrow_bump: u8,
+    pub party_item_ata: InterfaceAccount<'info, TokenAccount>,
+}
+
+/// Load component `index` and check the mint program, escrow PDA and party token account.
+///
+/// `bundle` is the account the escrow PDAs are derived from (a bundle or a swap offer).
+pub(crate) fn load_bundle_component<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    index: usize,
+    bundle: &Pubkey,
+    party: &Pubkey,
+    token_program: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<BundleComponent<'info>> {
+    let base = index
+        .checked_mul(BUNDLE_ACCOUNTS_PER_ITEM)
+        .ok_or(PgError::Overflow)?;
+    let item_mint = InterfaceAccount::<Mint>::try_from(&accounts[base])?;
+    require!(
+        item_mint.to_account_info().owner == token_program,
+        PgError::Unauthorized
+    );
+
+    let escrow_item_ata = &accounts[base + 1];
+    let (escrow, escrow_bump) = bundle_escrow_address(bundle, &item_mint.key(), program_id);
+    require!(escrow_item_ata.key() == escrow, PgError::Unauthorized);
+
+    let party_item_ata = InterfaceAccount::<TokenAccount>::try_from(&accounts[base + 2])?;
+    require!(
+        party_item_ata.mint == item_mint.key(),
+        PgError::ItemMintMismatch
+    );
+    require!(party_item_ata.owner == *party, PgError::Unauthorized);
+
+    Ok(BundleComponent {
+        item_mint,
+        escrow_item_ata,
+        escrow_bump,
+        party_item_ata,
+    })
+}
+
+/// Create the escrow PDA of `component` (sized for the mint's required extensions) with the
+/// game as token authority. `payer` funds the rent; a pre-funded escrow address is handled by
+/// `create_pda_account`.
+pub(crate) fn create_component_escrow<'info>(
+    component: &BundleComponent<'info>,
+    bundle: &Pubkey,
+    payer: &AccountInfo<'info>,
+    game: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let space = token_interface::get_account_data_size(
+        CpiContext::new(
+            token_program.to_account_info(),
+            token_interface::GetAccountDataSize {
+                mint: component.item_mint.to_account_info(),
+            },
+        ),
+        &[],
+    )?;
+    let lamports =
+        Rent::get()?.minimum_balance(usize::try_from(space).map_err(|_| PgError::Overflow)?);
+    let mint_key = component.item_mint.key();
+    let escrow_seeds: &[&[u8]] = &[
+        ESCROW_SEED,
+        bundle.as_ref(),
+        mint_key.as_ref(),
+        &[component.escrow_bump],
+    ];
+    create_pda_account(
+        component.escrow_item_ata,
+        payer,
+        system_program,
+        lamports,
+        space,
+        &token_program.key(),
+        escrow_seeds,
+    )?;
+    token_interface::initialize_account3(CpiContext::new(
+        token_program.to_account_info(),
+        token_interface::InitializeAccount3 {
+            account: component.escrow_item_ata.clone(),
+            mint: component.item_mint.to_account_info(),
+            authority: game.clone(),
+        },
+    ))
+}
+
+/// Close a component escrow drained by a delivery and return its rent to `destination`.
+///
+/// The game PDA signs. An escrow still holding tokens (sent to it outside the program) is
+/// left open so the sale or cancel can't be blocked by a dust transfer.
+pub(crate) fn close_component_escrow<'info>(
+    escrow: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    game: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let amount = TokenAccount::try_deserialize(&mut &escrow.try_borrow_data()?[..])?.amount;
+    if amount > 0 {
+        return Ok(());
+    }
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        token_interface::CloseAccount {
+            account: escrow.clone(),
+            destination: destination.clone(),
+            authority: game.clone(),
+        },
+        signer,
+    ))
+}
+
+/// Create a bundle listing and escrow every component.
+///
+/// `remaining_accounts` holds `[item_mint, escrow_item_ata, seller_item_ata]` per component,
+/// in the same order as `quantities`, optionally followed by each component's Metaplex
+/// metadata PDA (required when the game enforces royalties). Escrow PDAs are created here
+/// with the game as authority. Bundles are active immediately and can be bought once
+/// `start_time` has passed.
+///
+/// Creator royalties from the components' metadata override the seller's
+/// `royalty_recipient`/`royalty_bps` as on `create_listing`; every component that carries a
+/// royalty must resolve to the same one.
+///
+/// # Errors
+/// - `PgError::InvalidAmount` if the component count, a quantity or the price is invalid
+/// - `PgError::InvalidTime` if the time window is invalid or exceeds `MAX_LISTING_DURATION_SECS`
+/// - `PgError::InvalidRoyalty` if royalty is outside `MIN_ROYALTY_BPS..=MAX_ROYALTY_BPS`
+/// - `PgError::MixedItemRoyalties` if components resolve to different creator royalties
+/// - `PgError::RoyaltySplitMismatch` if the royalty split doesn't match the resolved royalty
+/// - `PgError::FeeTooHigh` if royalty plus fees exceed 100%
+/// - `PgError::ItemMintMismatch` if a mint is repeated or a token account has the wrong mint
+/// - `PgError::InsufficientQuantity` if the seller doesn't hold a component's quantity
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` if the bundle price exceeds the seller's
+///   KYC tier
+#[allow(clippy::too_many_arguments)] // Required for bundle creation parameters
+#[allow(clippy::too_many_lines)] // Per-component escrow creation
+pub fn create_bundle_listing<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateBundleListing<'info>>,
+    bundle_id: u64,
+    quantities: Vec<u64>,
+    price: u64,
+    start_time: i64,
+    end_time: i64,
+    royalty_recipient: Pubkey,
+    royalty_bps: u16,
+) -> Result<()> {
+    let item_count = quantities.len();
+    require!(
+        (1..=MAX_BUNDLE_ITEMS).contains(&item_count),
+        PgError::InvalidAmount
+    );
+    let component_accounts = item_count * BUNDLE_ACCOUNTS_PER_ITEM;
+    require!(
+        ctx.remaining_accounts.len() == component_accounts
+            || ctx.remaining_accounts.len() == component_accounts + item_count,
+        PgError::InvalidAmount
+    );
+    for quantity in &quantities {
+        require!(
+            (1..=MAX_BULK_QTY).contains(quantity),
+            PgError::InvalidAmount
+        );
+    }
+    require!(price > 0, PgError::InvalidAmount);
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    require!(!cfg.paused_new, PgError::ListingsPaused);
+    require!(!game.paused_new, PgError::ListingsPaused);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(end_time > start_time, PgError::InvalidTime);
+    require!(end_time > now, PgError::InvalidTime);
+    let duration = end_time.checked_sub(start_time).ok_or(PgError::Overflow)?;
+    require!(duration <= MAX_LISTING_DURATION_SECS, PgError::InvalidTime);
+
+    // Same royalty bounds as listings (dust royalties rejected to prevent PDA spam)
+    if royalty_bps > 0 {
+        require!(
+            (MIN_ROYALTY_BPS..=MAX_ROYALTY_BPS).contains(&royalty_bps),
+            PgError::InvalidRoyalty
+        );
+        require!(
+            royalty_recipient != Pubkey::default(),
+            PgError::InvalidRoyalty
+        );
+    }
+
+    let seller = ctx.accounts.seller_signer.key();
+    require!(
+        ctx.accounts.seller_ledger.authority == seller,
+        PgError::Unauthorized
+    );
+    // KYC: the seller's tier must allow a trade of the whole bundle
+    ctx.accounts
+        .seller_ledger
+        .check_kyc_trade(cfg, game.kyc_required, price, now)?;
+
+    // Validate every component up front (distinct mints, seller holds the quantity)
+    let bundle_key = ctx.accounts.bundle.key();
+    let token_program_id = ctx.accounts.token_program.key();
+    let mut components: Vec<BundleComponent<'info>> = Vec::with_capacity(item_count);
+    for (index, quantity) in quantities.iter().enumerate() {
+        let component = load_bundle_component(
+            ctx.remaining_accounts,
+            index,
+            &bundle_key,
+            &seller,
+            &token_program_id,
+            ctx.program_id,
+        )?;
+        require!(
+            components
+                .iter()
+                .all(|c| c.item_mint.key() != component.item_mint.key()),
+            PgError::ItemMintMismatch
+        );
+        require!(
+            component.party_item_ata.amount >= *quantity,
+            PgError::InsufficientQuantity
+        );
+        components.push(component);
+    }
+
+    // Creator royalties from component metadata override seller input (per-game policy)
+    let metadata_accounts = &ctx.remaining_accounts[component_accounts..];
+    let royalty_items: Vec<(Pubkey, Option<&AccountInfo>)> = components
+        .iter()
+        .enumerate()
+        .map(|(index, c)| (c.item_mint.key(), metadata_accounts.get(index)))
+        .collect();
+    let (royalty_recipient, royalty_bps) = metadata::resolve_items_royalty(
+        game.royalty_enforcement,
+        &royalty_items,
+        ctx.accounts.royalty_split.as_deref().map(|split| &**split),
+        royalty_recipient,
+        royalty_bps,
+    )?;
+
+    // CRITICAL: Fee sanity check - total fees must not exceed 100%
+    let total_fees = u64::from(royalty_bps)
+        .checked_add(u64::from(game.fee_bps))
+        .ok_or(PgError::Overflow)?
+        .checked_add(u64::from(cfg.protocol_fee_bps))
+        .ok_or(PgError::Overflow)?;
+    require!(total_fees <= BPS_DENOM, PgError::FeeTooHigh);
+
+    // Multi-recipient royalties: the split's primary recipient is the bundle's royalty_recipient
+    let royalty_split = match &ctx.accounts.royalty_split {
+        Some(split) => {
+            require!(split.game == game.key(), PgError::RoyaltySplitMismatch);
+            require!(royalty_bps > 0, PgError::InvalidRoyalty);
+            require!(
+                split.recipients[0] == royalty_recipient,
+                PgError::RoyaltySplitMismatch
+            );
+            split.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let bundle = &mut ctx.accounts.bundle;
+        bundle.game = game.key();
+        bundle.bundle_id = bundle_id;
+        bundle.seller = seller;
+        bundle.status = ListingStatus::Active;
+        bundle.currency_mint = game.currency_mint;
+        bundle.item_count = u8::try_from(item_count).map_err(|_| PgError::Overflow)?;
+        bundle.price = price;
+        bundle.start_time = start_time;
+        bundle.end_time = end_time;
+        bundle.royalty_recipient = royalty_recipient;
+        bundle.royalty_bps = royalty_bps;
+        bundle.royalty_split = royalty_split;
+        bundle.created_at = now;
+        bundle.updated_at = now;
+        bundle.bump = ctx.bumps.bundle;
+
+        for (index, (component, quantity)) in components.iter().zip(quantities.iter()).enumerate() {
+            create_component_escrow(
+                component,
+                &bundle_key,
+                &ctx.accounts.seller_signer.to_account_info(),
+                &game.to_account_info(),
+                &ctx.accounts.token_program,
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+
+            // Escrow items: seller -> escrow
+            let cpi_accounts = token_interface::TransferChecked {
+                from: component.party_item_ata.to_account_info(),
+                mint: component.item_mint.to_account_info(),
+                to: component.escrow_item_ata.clone(),
+                authority: ctx.accounts.seller_signer.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, *quantity, component.item_mint.decimals)?;
+
+            bundle.item_mints[index] = component.item_mint.key();
+            bundle.item_quantities[index] = *quantity;
+        }
+
+        emit!(BundleListingCreated {
+            bundle: bundle_key,
+            game: game.key(),
+            bundle_id,
+            seller,
+            item_count: bundle.item_count,
+            price,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Buy a whole bundle: all components, fees and royalties settle in one transaction.
+///
+/// `remaining_accounts` holds `[item_mint, escrow_item_ata, buyer_item_ata]` per component,
+/// in bundle order, followed by the other royalty split recipients' ledgers when the bundle
+/// has a split. Fees are the game fee plus the flat `protocol_fee_bps` on the bundle price
+/// (no maker/taker tiers, and the trade doesn't count toward tier volume). Drained escrows
+/// are closed and their rent returned to the seller.
+///
+/// # Errors
+/// - `PgError::InvalidListingStatus` if the bundle is not Active
+/// - `PgError::InvalidTime` if outside the bundle's time window
+/// - `PgError::ItemMintMismatch` if components are missing, reordered or use the wrong mint
+/// - `PgError::InsufficientCredits` if buyer doesn't have enough available credits
+/// - `PgError::SelfExcluded` if the buyer is in a self-exclusion cool-off
+/// - `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired` if the buyer's
+///   spending limits don't allow it
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded`
+///   if the bundle price exceeds the buyer's KYC tier limits
+/// - `PgError::FeeTooHigh` if total fees exceed 50% of the price
+/// - `PgError::InvalidSellerAmount` if the seller would receive nothing after fees
+#[allow(clippy::too_many_lines)] // Settlement logic mirrors buy_fixed
+pub fn buy_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, BuyBundle<'info>>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let bundle = &ctx.accounts.bundle;
+
+    require!(bundle.game == game.key(), PgError::Unauthorized);
+    require!(
+        bundle.currency_mint == game.currency_mint,
+        PgError::CurrencyMintMismatch
+    );
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+    require!(
+        bundle.status == ListingStatus::Active,
+        PgError::InvalidListingStatus
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= bundle.start_time, PgError::InvalidTime);
+    require!(now <= bundle.end_time, PgError::InvalidTime);
+
+    let buyer = ctx.accounts.buyer_signer.key();
+    let buyer_ledger = &ctx.accounts.buyer_ledger;
+    require!(buyer_ledger.authority == buyer, PgError::Unauthorized);
+    buyer_ledger.check_kyc_trade(cfg, game.kyc_required, bundle.price, now)?;
+    require!(
+        ctx.accounts.seller_ledger.authority == bundle.seller,
+        PgError::Unauthorized
+    );
+    require!(
+        buyer_ledger.available >= bundle.price,
+        PgError::InsufficientCredits
+    );
+    require!(!buyer_ledger.limits.is_excluded(now), PgError::SelfExcluded);
+
+    // Multi-recipient royalties: the split must be the one recorded on the bundle
+    if bundle.royalty_split != Pubkey::default() {
+        let split = ctx
+            .accounts
+            .royalty_split
+            .as_ref()
+            .ok_or(PgError::RoyaltySplitMismatch)?;
+        require!(
+            split.key() == bundle.royalty_split,
+            PgError::RoyaltySplitMismatch
+        );
+    }
+
+    let item_count = usize::from(bundle.item_count);
+    let component_accounts = item_count * BUNDLE_ACCOUNTS_PER_ITEM;
+    require!(
+        ctx.remaining_accounts.len() >= component_accounts,
+        PgError::ItemMintMismatch
+    );
+    let bundle_key = bundle.key();
+    let token_program_id = ctx.accounts.token_program.key();
+    let mut components: Vec<BundleComponent<'info>> = Vec::with_capacity(item_count);
+    for index in 0..item_count {
+        let component = load_bundle_component(
+            ctx.remaining_accounts,
+            index,
+            &bundle_key,
+            &buyer,
+            &token_program_id,
+            ctx.program_id,
+        )?;
+        require!(
+            component.item_mint.key() == bundle.item_mints[index],
+            PgError::ItemMintMismatch
+        );
+        components.push(component);
+    }
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let bundle = &mut ctx.accounts.bundle;
+    let buyer_ledger = &mut ctx.accounts.buyer_ledger;
+    let seller_ledger = &mut ctx.accounts.seller_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let total_price = bundle.price;
+
+        // Fees: flat protocol_fee_bps, not buy_fixed's maker/taker tiers; bundle trades don't
+        // count toward fee_volume
+        let game_fee = total_price
+            .checked_mul(u64::from(game.fee_bps))
+            .ok_or(PgError::Overflow)?
+            .checked_div(BPS_DENOM)
+            .ok_or(PgError::Overflow)?;
+
+        let protocol_fee = total_price
+            .checked_mul(u64::from(cfg.protocol_fee_bps))
+            .ok_or(PgError::Overflow)?
+            .checked_div(BPS_DENOM)
+            .ok_or(PgError::Overflow)?;
+
+        let royalty_amount =
+            if bundle.royalty_bps > 0 && bundle.royalty_recipient != Pubkey::default() {
+                total_price
+                    .checked_mul(u64::from(bundle.royalty_bps))
+                    .ok_or(PgError::Overflow)?
+                    .checked_div(BPS_DENOM)
+                    .ok_or(PgError::Overflow)?
+            } else {
+                0u64
+            };
+
+        // CRITICAL: Ensure total fees don't exceed 50% of total price
+        let total_fees = game_fee
+            .checked_add(protocol_fee)
+            .ok_or(PgError::Overflow)?
+            .checked_add(royalty_amount)
+            .ok_or(PgError::Overflow)?;
+        let max_allowed_fees = total_price.checked_div(2).ok_or(PgError::Overflow)?; // 50% max
+        require!(total_fees <= max_allowed_fees, PgError::FeeTooHigh);
+
+        let seller_amount = total_price
+            .checked_sub(total_fees)
+            .ok_or(PgError::Overflow)?;
+        require!(seller_amount > 0, PgError::InvalidSellerAmount);
+
+        // Spending limits: the bundle price counts as spent
+        let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+        buyer_ledger
+            .limits
+            .record_spend(total_price, now, approver)?;
+        // KYC tier limits (trade size + daily volume)
+        buyer_ledger.record_kyc_trade(cfg, game.kyc_required, total_price, now)?;
+
+        // Move credits
+        buyer_ledger.available = buyer_ledger
+            .available
+            .checked_sub(total_price)
+            .ok_or(PgError::Overflow)?;
+        seller_ledger.available = seller_ledger
+            .available
+            .checked_add(seller_amount)
+            .ok_or(PgError::Overflow)?;
+
+        // CRITICAL: Only process royalties when royalty_amount > 0 (prevents griefing via init_if_needed)
+        if royalty_amount > 0 {
+            let royalty_ledger = &mut ctx.accounts.royalty_recipient_ledger;
+            if royalty_ledger.authority == Pubkey::default() {
+                // Initialize new ledger
+                royalty_ledger.game = game.key();
+                royalty_ledger.authority = bundle.royalty_recipient;
+                royalty_ledger.available = 0;
+                royalty_ledger.locked = 0;
+                royalty_ledger.kyc_verified = false;
+                royalty_ledger.kyc_provider = Pubkey::default();
+                royalty_ledger.kyc_verified_at = 0;
+                royalty_ledger.kyc_proof_hash = [0u8; 32];
+            }
+            require!(
+                royalty_ledger.authority == bundle.royalty_recipient,
+                PgError::Unauthorized
+            );
+
+            // Split royalties: recipient 0 takes its share plus rounding dust
+            let royalty_shares = match &ctx.accounts.royalty_split {
+                Some(split) if bundle.royalty_split != Pubkey::default() => {
+                    Some(split.shares(royalty_amount)?)
+                }
+                _ => None,
+            };
+            let primary_royalty = royalty_shares.map_or(royalty_amount, |shares| shares[0]);
+            royalty_ledger.available = royalty_ledger
+                .available
+                .checked_add(primary_royalty)
+                .ok_or(PgError::Overflow)?;
+
+            emit!(RoyaltyPaid {
+                listing: bundle.key(),
+                game: game.key(),
+                recipient: bundle.royalty_recipient,
+                amount: primary_royalty,
+            });
+
+            if let (Some(split), Some(shares)) = (&ctx.accounts.royalty_split, &royalty_shares) {
+                instructions::royalty_split::credit_royalty_split(
+                    split,
+                    shares,
+                    bundle.key(),
+                    &mut [&mut **buyer_ledger, &mut **seller_ledger],
+                    &ctx.remaining_accounts[component_accounts..],
+                    ctx.program_id,
+                )?;
+            }
+        }
+
+        // CRITICAL: Track protocol and game fees per-game
+        game.protocol_fees_accumulated = game
+            .protocol_fees_accumulated
+            .checked_add(protocol_fee)
+            .ok_or(PgError::Overflow)?;
+        game.accumulated_game_fees = game
+            .accumulated_game_fees
+            .checked_add(game_fee)
+            .ok_or(PgError::Overflow)?;
+
+        // Deliver every component: escrow -> buyer ATA (game PDA signs)
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+        for (component, quantity) in components.iter().zip(bundle.item_quantities.iter()) {
+            let cpi_accounts = token_interface::TransferChecked {
+                from: component.escrow_item_ata.clone(),
+                mint: component.item_mint.to_account_info(),
+                to: component.party_item_ata.to_account_info(),
+                authority: game.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, *quantity, component.item_mint.decimals)?;
+            close_component_escrow(
+                component.escrow_item_ata,
+                &ctx.accounts.seller.to_account_info(),
+                &game.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                signer,
+            )?;
+        }
+
+        bundle.status = ListingStatus::Settled;
+        bundle.updated_at = now;
+
+        emit!(BundleSold {
+            bundle: bundle.key(),
+            game: game.key(),
+            seller: bundle.seller,
+            buyer,
+            price: total_price,
+            protocol_fee,
+            game_fee,
+            royalty_amount,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Cancel a bundle and return every escrowed component to the seller.
+///
+/// `remaining_accounts` holds `[item_mint, escrow_item_ata, seller_item_ata]` per component.
+/// Items ALWAYS go back to the seller, even if admin/governance calls this, and the drained
+/// escrows are closed to the seller.
+///
+/// # Errors
+/// - `PgError::InvalidListingStatus` if the bundle is not Active
+/// - `PgError::Unauthorized` if caller is not the seller, game owner / listing moderator,
+///   admin, governance or server
+/// - `PgError::ItemMintMismatch` if components are missing, reordered or use the wrong mint
+pub fn cancel_bundle_listing<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelBundleListing<'info>>,
+) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let bundle = &ctx.accounts.bundle;
+
+    require!(bundle.game == game.key(), PgError::Unauthorized);
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+    require!(
+        bundle.status == ListingStatus::Active,
+        PgError::InvalidListingStatus
+    );
+
+    // Only seller, game owner / listing moderator, admin, governance or server can cancel
+    let caller = ctx.accounts.caller.key();
+    let seller = bundle.seller;
+    let allowed = caller == seller
+        || is_owner_or_role(
+            game,
+            ctx.accounts.roles.as_deref(),
+            &caller,
+            GameRole::ListingModerator,
+        )
+        || caller == cfg.admin
+        || caller == cfg.governance
+        || caller == cfg.server_authority;
+    require!(allowed, PgError::Unauthorized);
+
+    let item_count = usize::from(bundle.item_count);
+    require!(
+        ctx.remaining_accounts.len() == item_count * BUNDLE_ACCOUNTS_PER_ITEM,
+        PgError::ItemMintMismatch
+    );
+    let bundle_key = bundle.key();
+    let token_program_id = ctx.accounts.token_program.key();
+    let mut components: Vec<BundleComponent<'info>> = Vec::with_capacity(item_count);
+    for index in 0..item_count {
+        let component = load_bundle_component(
+            ctx.remaining_accounts,
+            index,
+            &bundle_key,
+            &seller,
+            &token_program_id,
+            ctx.program_id,
+        )?;
+        require!(
+            component.item_mint.key() == bundle.item_mints[index],
+            PgError::ItemMintMismatch
+        );
+        components.push(component);
+    }
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let bundle = &mut ctx.accounts.bundle;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        bundle.status = ListingStatus::Cancelled;
+        bundle.updated_at = now;
+
+        // Return every component: escrow -> seller (game PDA signs)
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+        for (component, quantity) in components.iter().zip(bundle.item_quantities.iter()) {
+            let cpi_accounts = token_interface::TransferChecked {
+                from: component.escrow_item_ata.clone(),
+                mint: component.item_mint.to_account_info(),
+                to: component.party_item_ata.to_account_info(),
+                authority: game.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, *quantity, component.item_mint.decimals)?;
+            close_component_escrow(
+                component.escrow_item_ata,
+                &ctx.accounts.seller.to_account_info(),
+                &game.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                signer,
+            )?;
+        }
+
+        emit!(BundleCancelled {
+            bundle: bundle.key(),
+            game: game.key(),
+            seller,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+#[instruction(bundle_id: u64)]
+pub struct CreateBundleListing<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        seeds = [LEDGER_SEED, game.key().as_ref(), seller_signer.key().as_ref()],
+        bump
+    )]
+    pub seller_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        init,
+        payer = seller_signer,
+        seeds = [BUNDLE_LISTING_SEED, game.key().as_ref(), &bundle_id.to_le_bytes()],
+        bump,
+        space = 8 + std::mem::size_of::<BundleListing>()
+    )]
+    pub bundle: Box<Account<'info, BundleListing>>,
+
+    #[account(mut)]
+    pub seller_signer: Signer<'info>,
+
+    /// Optional multi-recipient royalty split (recipient 0 must be `royalty_recipient`)
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct BuyBundle<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [BUNDLE_LISTING_SEED, game.key().as_ref(), &bundle.bundle_id.to_le_bytes()],
+        bump = bundle.bump
+    )]
+    pub bundle: Box<Account<'info, BundleListing>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), buyer_signer.key().as_ref()],
+        bump
+    )]
+    pub buyer_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), bundle.seller.as_ref()],
+        bump
+    )]
+    pub seller_ledger: Box<Account<'info, PlayerLedger>>,
+
+    /// CHECK: Bundle seller (receives the escrow rent); validated against bundle.seller
+    #[account(mut, address = bundle.seller)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Royalty recipient ledger (only initialized when `royalty_bps` > 0 to prevent grief)
+    #[account(
+        init_if_needed,
+        payer = buyer_signer,
+        seeds = [LEDGER_SEED, game.key().as_ref(), bundle.royalty_recipient.as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PlayerLedger>()
+    )]
+    pub royalty_recipient_ledger: Box<Account<'info, PlayerLedger>>,
+
+    /// Required when `bundle.royalty_split` is set; other recipients' ledgers go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    #[account(mut)]
+    pub buyer_signer: Signer<'info>,
+
+    /// Guardian co-signature for spends above the buyer's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBundleListing<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [BUNDLE_LISTING_SEED, game.key().as_ref(), &bundle.bundle_id.to_le_bytes()],
+        bump = bundle.bump
+    )]
+    pub bundle: Box<Account<'info, BundleListing>>,
+
+    /// CHECK: Bundle seller (receives the escrow rent); validated against bundle.seller
+    #[account(mut, address = bundle.seller)]
+    pub seller: UncheckedAccount<'info>,
+
+    pub caller: Signer<'info>,
+
+    /// Game roles (required when the caller acts as a `ListingModerator`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
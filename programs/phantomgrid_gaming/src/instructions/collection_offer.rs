@@ -0,0 +1,657 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+use solana_program::hash::hashv;
+
+use crate::{
+    enter_execution_game, exit_execution_game, instructions, metadata, verify_merkle_proof,
+    CollectionOffer, CollectionOfferAccepted, CollectionOfferCancelled, CollectionOfferCreated,
+    GameConfig, GlobalConfig, OfferScope, PgError, PlayerLedger, RoyaltyPaid, RoyaltySplit,
+    BPS_DENOM, COLLECTION_OFFER_SEED, CONFIG_SEED, GAME_SEED, LEDGER_SEED, MAX_BULK_QTY,
+    MAX_LISTING_DURATION_SECS, MAX_ROYALTY_BPS, MIN_ROYALTY_BPS,
+};
+
+// ======================================================================
+// COLLECTION / TRAIT OFFER INSTRUCTIONS
+// ======================================================================
+
+/// Merkle leaf for an item mint in a collection offer criteria root
+pub fn collection_offer_leaf(item_mint: &Pubkey) -> [u8; 32] {
+    hashv(&[item_mint.as_ref()]).to_bytes()
+}
+
+/// Create a standing offer for any item matching `criteria_root`.
+///
+/// Locks `price_per_unit * quantity` in the bidder's ledger.
+///
+/// # Errors
+/// - `PgError::ListingsPaused` if new listings are paused
+/// - `PgError::InvalidAmount` if price/quantity are 0 or quantity exceeds `MAX_BULK_QTY`
+/// - `PgError::InvalidTime` if `expires_at` is in the past or beyond `MAX_LISTING_DURATION_SECS`
+/// - `PgError::InsufficientCredits` if bidder doesn't have enough available credits
+/// - `PgError::SelfExcluded` if the bidder is in a self-exclusion cool-off
+/// - `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired` if the bidder's
+///   spending limits don't allow it
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded`
+///   if the locked amount exceeds the bidder's KYC tier limits
+pub fn create_collection_offer(
+    ctx: Context<CreateCollectionOffer>,
+    offer_id: u64,
+    scope: OfferScope,
+    criteria_root: [u8; 32],
+    price_per_unit: u64,
+    quantity: u64,
+    expires_at: i64,
+) -> Result<()> {
+    require!(price_per_unit > 0, PgError::InvalidAmount);
+    require!(quantity > 0, PgError::InvalidAmount);
+    require!(quantity <= MAX_BULK_QTY, PgError::InvalidAmount);
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    require!(!cfg.paused_new, PgError::ListingsPaused);
+    require!(!game.paused_new, PgError::ListingsPaused);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at > now, PgError::InvalidTime);
+    let duration = expires_at.checked_sub(now).ok_or(PgError::Overflow)?;
+    require!(duration <= MAX_LISTING_DURATION_SECS, PgError::InvalidTime);
+
+    let total_locked = price_per_unit
+        .checked_mul(quantity)
+        .ok_or(PgError::Overflow)?;
+    let bidder_ledger = &ctx.accounts.bidder_ledger;
+    require!(
+        bidder_ledger.authority == ctx.accounts.bidder.key(),
+        PgError::Unauthorized
+    );
+    bidder_ledger.check_kyc_trade(cfg, game.kyc_required, total_locked, now)?;
+    require!(
+        bidder_ledger.available >= total_locked,
+        PgError::InsufficientCredits
+    );
+    require!(
+        !bidder_ledger.limits.is_excluded(now),
+        PgError::SelfExcluded
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let bidder_ledger = &mut ctx.accounts.bidder_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        // Spending limits: the locked amount counts as spent
+        let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+        bidder_ledger
+            .limits
+            .record_spend(total_locked, now, approver)?;
+        // KYC tier limits: the whole offer counts toward the bidder's daily volume
+        bidder_ledger.record_kyc_trade(cfg, game.kyc_required, total_locked, now)?;
+
+        bidder_ledger.available = bidder_ledger
+            .available
+            .checked_sub(total_locked)
+            .ok_or(PgError::Overflow)?;
+        bidder_ledger.locked = bidder_ledger
+            .locked
+            .checked_add(total_locked)
+            .ok_or(PgError::Overflow)?;
+
+        let offer = &mut ctx.accounts.offer;
+        offer.game = game.key();
+        offer.bidder = ctx.accounts.bidder.key();
+        offer.offer_id = offer_id;
+        offer.scope = scope;
+        offer.criteria_root = criteria_root;
+        offer.price_per_unit = price_per_unit;
+        offer.quantity_total = quantity;
+        offer.quantity_remaining = quantity;
+        offer.expires_at = expires_at;
+        offer.created_at = now;
+        offer.bump = ctx.bumps.offer;
+
+        emit!(CollectionOfferCreated {
+            offer: offer.key(),
+            game: game.key(),
+            bidder: offer.bidder,
+            scope,
+            criteria_root,
+            price_per_unit,
+            quantity,
+            expires_at,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Accept a collection offer by selling matching items into it.
+///
+/// The item goes straight from the seller's ATA to the bidder's ATA; payment comes from the
+/// bidder's locked credits. The game fee, the flat `protocol_fee_bps` (no maker/taker tiers;
+/// the trade doesn't count toward tier volume) and the royalty come out of the price.
+///
+/// One offer matches many mints, so the royalty is resolved per accepted item: the seller
+/// supplies `royalty_recipient`/`royalty_bps` like on `create_listing`, and the item's
+/// Metaplex metadata overrides them under the game's `RoyaltyEnforcement` policy. With a
+/// `royalty_split`, the other recipients' ledgers go in `remaining_accounts`.
+///
+/// # Errors
+/// - `PgError::InvalidTime` if the offer has expired
+/// - `PgError::InsufficientQuantity` if `quantity` exceeds the offer's remaining quantity
+/// - `PgError::InvalidMerkleProof` if `item_mint` is not covered by `criteria_root`
+/// - `PgError::SelfExcluded` if the bidder has since entered a self-exclusion cool-off
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` if the fill exceeds the seller's
+///   KYC tier
+/// - `PgError::InvalidRoyalty` if the seller's royalty is outside `MIN_ROYALTY_BPS..=MAX_ROYALTY_BPS`
+///   or the resolved recipient isn't `royalty_recipient`
+/// - `PgError::MetadataRequired` / `PgError::InvalidMetadata` / `PgError::RoyaltySplitMismatch`
+///   if the item's metadata royalties can't be resolved (see `resolve_listing_royalty`)
+/// - `PgError::FeeTooHigh` if total fees exceed 50% of the price
+/// - `PgError::InvalidSellerAmount` if the seller would receive nothing after fees
+#[allow(clippy::too_many_lines)] // Settlement logic mirrors buy_fixed
+pub fn accept_collection_offer<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptCollectionOffer<'info>>,
+    quantity: u64,
+    proof: Vec<[u8; 32]>,
+    royalty_recipient: Pubkey,
+    royalty_bps: u16,
+) -> Result<()> {
+    require!(quantity > 0, PgError::InvalidAmount);
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let offer = &ctx.accounts.offer;
+
+    require!(offer.game == game.key(), PgError::Unauthorized);
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now < offer.expires_at, PgError::InvalidTime);
+    require!(
+        offer.quantity_remaining >= quantity,
+        PgError::InsufficientQuantity
+    );
+
+    // Item must be in the offer's criteria set
+    let leaf = collection_offer_leaf(&ctx.accounts.item_mint.key());
+    verify_merkle_proof(&leaf, &proof, &offer.criteria_root)?;
+
+    let seller = ctx.accounts.seller_signer.key();
+    require!(seller != offer.bidder, PgError::Unauthorized);
+    require!(
+        ctx.accounts.seller_ledger.authority == seller,
+        PgError::Unauthorized
+    );
+    let bidder_ledger = &ctx.accounts.bidder_ledger;
+    require!(
+        bidder_ledger.authority == offer.bidder,
+        PgError::Unauthorized
+    );
+    if game.kyc_required {
+        require!(bidder_ledger.has_valid_kyc(now), PgError::KycRequired);
+    }
+    // The offer's credits were counted as spent when locked; fills stop while excluded
+    require!(
+        !bidder_ledger.limits.is_excluded(now),
+        PgError::SelfExcluded
+    );
+
+    let total_price = offer
+        .price_per_unit
+        .checked_mul(quantity)
+        .ok_or(PgError::Overflow)?;
+    require!(
+        bidder_ledger.locked >= total_price,
+        PgError::InsufficientCredits
+    );
+    // KYC: the fill must fit the seller's tier (the bidder's volume was counted at create)
+    ctx.accounts
+        .seller_ledger
+        .check_kyc_trade(cfg, game.kyc_required, total_price, now)?;
+
+    // Same royalty bounds as listings (dust royalties rejected to prevent PDA spam)
+    if royalty_bps > 0 {
+        require!(
+            (MIN_ROYALTY_BPS..=MAX_ROYALTY_BPS).contains(&royalty_bps),
+            PgError::InvalidRoyalty
+        );
+        require!(
+            royalty_recipient != Pubkey::default(),
+            PgError::InvalidRoyalty
+        );
+    }
+    // Creator royalties from the accepted item's metadata override seller input (per-game policy)
+    let royalty_split = ctx.accounts.royalty_split.as_deref().map(|split| &**split);
+    let (resolved_recipient, resolved_bps) = metadata::resolve_listing_royalty(
+        game.royalty_enforcement,
+        ctx.accounts.item_metadata.as_deref(),
+        &ctx.accounts.item_mint.key(),
+        royalty_split,
+        royalty_recipient,
+        royalty_bps,
+    )?;
+    // royalty_recipient_ledger is derived from the argument, so it must name the payee
+    if resolved_bps > 0 {
+        require!(
+            resolved_recipient == royalty_recipient,
+            PgError::InvalidRoyalty
+        );
+    }
+    if let Some(split) = royalty_split {
+        require!(split.game == game.key(), PgError::RoyaltySplitMismatch);
+        require!(resolved_bps > 0, PgError::InvalidRoyalty);
+        require!(
+            split.recipients[0] == resolved_recipient,
+            PgError::RoyaltySplitMismatch
+        );
+    }
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let offer = &mut ctx.accounts.offer;
+    let bidder_ledger = &mut ctx.accounts.bidder_ledger;
+    let seller_ledger = &mut ctx.accounts.seller_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        // Fees: flat protocol_fee_bps, not buy_fixed's maker/taker tiers; accepted offers don't
+        // count toward fee_volume
+        let game_fee = total_price
+            .checked_mul(u64::from(game.fee_bps))
+            .ok_or(PgError::Overflow)?
+            .checked_div(BPS_DENOM)
+            .ok_or(PgError::Overflow)?;
+
+        let protocol_fee = total_price
+            .checked_mul(u64::from(cfg.protocol_fee_bps))
+            .ok_or(PgError::Overflow)?
+            .checked_div(BPS_DENOM)
+            .ok_or(PgError::Overflow)?;
+
+        let royalty_amount = if resolved_bps > 0 {
+            total_price
+                .checked_mul(u64::from(resolved_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?
+        } else {
+            0u64
+        };
+
+        // CRITICAL: Ensure total fees don't exceed 50% of total price
+        let total_fees = game_fee
+            .checked_add(protocol_fee)
+            .ok_or(PgError::Overflow)?
+            .checked_add(royalty_amount)
+            .ok_or(PgError::Overflow)?;
+        let max_allowed_fees = total_price.checked_div(2).ok_or(PgError::Overflow)?; // 50% max
+        require!(total_fees <= max_allowed_fees, PgError::FeeTooHigh);
+
+        let seller_amount = total_price
+            .checked_sub(total_fees)
+            .ok_or(PgError::Overflow)?;
+        require!(seller_amount > 0, PgError::InvalidSellerAmount);
+
+        // Move credits: bidder locked -> seller available
+        bidder_ledger.locked = bidder_ledger
+            .locked
+            .checked_sub(total_price)
+            .ok_or(PgError::Overflow)?;
+        seller_ledger.available = seller_ledger
+            .available
+            .checked_add(seller_amount)
+            .ok_or(PgError::Overflow)?;
+
+        // CRITICAL: Only process royalties when royalty_amount > 0 (prevents griefing via init_if_needed)
+        if royalty_amount > 0 {
+            let royalty_ledger = &mut ctx.accounts.royalty_recipient_ledger;
+            if royalty_ledger.authority == Pubkey::default() {
+                // Initialize new ledger
+                royalty_ledger.game = game.key();
+                royalty_ledger.authority = resolved_recipient;
+                royalty_ledger.available = 0;
+                royalty_ledger.locked = 0;
+                royalty_ledger.kyc_verified = false;
+                royalty_ledger.kyc_provider = Pubkey::default();
+                royalty_ledger.kyc_verified_at = 0;
+                royalty_ledger.kyc_proof_hash = [0u8; 32];
+            }
+            require!(
+                royalty_ledger.authority == resolved_recipient,
+                PgError::Unauthorized
+            );
+
+            // Split royalties: recipient 0 takes its share plus rounding dust
+            let royalty_shares = royalty_split
+                .map(|split| split.shares(royalty_amount))
+                .transpose()?;
+            let primary_royalty = royalty_shares.map_or(royalty_amount, |shares| shares[0]);
+            royalty_ledger.available = royalty_ledger
+                .available
+                .checked_add(primary_royalty)
+                .ok_or(PgError::Overflow)?;
+
+            emit!(RoyaltyPaid {
+                listing: offer.key(),
+                game: game.key(),
+                recipient: resolved_recipient,
+                amount: primary_royalty,
+            });
+
+            if let (Some(split), Some(shares)) = (royalty_split, &royalty_shares) {
+                instructions::royalty_split::credit_royalty_split(
+                    split,
+                    shares,
+                    offer.key(),
+                    &mut [&mut **bidder_ledger, &mut **seller_ledger],
+                    ctx.remaining_accounts,
+                    ctx.program_id,
+                )?;
+            }
+        }
+
+        // CRITICAL: Track protocol and game fees per-game
+        game.protocol_fees_accumulated = game
+            .protocol_fees_accumulated
+            .checked_add(protocol_fee)
+            .ok_or(PgError::Overflow)?;
+        game.accumulated_game_fees = game
+            .accumulated_game_fees
+            .checked_add(game_fee)
+            .ok_or(PgError::Overflow)?;
+
+        // Deliver items: seller ATA -> bidder ATA (seller signs, no escrow needed)
+        let decimals = ctx.accounts.item_mint.decimals;
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.seller_item_ata.to_account_info(),
+            mint: ctx.accounts.item_mint.to_account_info(),
+            to: ctx.accounts.bidder_item_ata.to_account_info(),
+            authority: ctx.accounts.seller_signer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, quantity, decimals)?;
+
+        offer.quantity_remaining = offer
+            .quantity_remaining
+            .checked_sub(quantity)
+            .ok_or(PgError::Overflow)?;
+
+        emit!(CollectionOfferAccepted {
+            offer: offer.key(),
+            game: game.key(),
+            seller,
+            bidder: offer.bidder,
+            item_mint: ctx.accounts.item_mint.key(),
+            quantity,
+            price_total: total_price,
+            protocol_fee,
+            game_fee,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Cancel a collection offer, unlock the remaining credits and close the account.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not the bidder
+/// - `PgError::InsufficientCredits` if locked credits don't cover the remaining offer
+pub fn cancel_collection_offer(ctx: Context<CancelCollectionOffer>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let game = &ctx.accounts.game;
+    let offer = &ctx.accounts.offer;
+    require!(offer.game == game.key(), PgError::Unauthorized);
+    require!(
+        offer.bidder == ctx.accounts.bidder.key(),
+        PgError::Unauthorized
+    );
+
+    let refund = offer
+        .price_per_unit
+        .checked_mul(offer.quantity_remaining)
+        .ok_or(PgError::Overflow)?;
+    require!(
+        ctx.accounts.bidder_ledger.locked >= refund,
+        PgError::InsufficientCredits
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let bidder_ledger = &mut ctx.accounts.bidder_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        bidder_ledger.locked = bidder_ledger
+            .locked
+            .checked_sub(refund)
+            .ok_or(PgError::Overflow)?;
+        bidder_ledger.available = bidder_ledger
+            .available
+            .checked_add(refund)
+            .ok_or(PgError::Overflow)?;
+
+        emit!(CollectionOfferCancelled {
+            offer: ctx.accounts.offer.key(),
+            game: game.key(),
+            bidder: bidder_ledger.authority,
+            refunded: refund,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct CreateCollectionOffer<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_ledger: Account<'info, PlayerLedger>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + std::mem::size_of::<CollectionOffer>(),
+        seeds = [
+            COLLECTION_OFFER_SEED,
+            game.key().as_ref(),
+            bidder.key().as_ref(),
+            &offer_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub offer: Account<'info, CollectionOffer>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// Guardian co-signature for spends above the bidder's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+#[instruction(quantity: u64, proof: Vec<[u8; 32]>, royalty_recipient: Pubkey)]
+pub struct AcceptCollectionOffer<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            COLLECTION_OFFER_SEED,
+            game.key().as_ref(),
+            offer.bidder.as_ref(),
+            &offer.offer_id.to_le_bytes()
+        ],
+        bump = offer.bump
+    )]
+    pub offer: Box<Account<'info, CollectionOffer>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), offer.bidder.as_ref()],
+        bump
+    )]
+    pub bidder_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), seller_signer.key().as_ref()],
+        bump
+    )]
+    pub seller_ledger: Box<Account<'info, PlayerLedger>>,
+
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = item_mint,
+        associated_token::authority = seller_signer
+    )]
+    pub seller_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Offer bidder (receives the item); validated against offer.bidder
+    #[account(address = offer.bidder)]
+    pub bidder: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = seller_signer,
+        associated_token::mint = item_mint,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program
+    )]
+    pub bidder_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Royalty recipient ledger (only initialized when `royalty_bps` > 0 to prevent grief)
+    #[account(
+        init_if_needed,
+        payer = seller_signer,
+        seeds = [LEDGER_SEED, game.key().as_ref(), royalty_recipient.as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PlayerLedger>()
+    )]
+    pub royalty_recipient_ledger: Box<Account<'info, PlayerLedger>>,
+
+    /// Multi-recipient royalty split (recipient 0 must be `royalty_recipient`); other
+    /// recipients' ledgers go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    /// CHECK: Metaplex metadata PDA of `item_mint`; address, owner and layout verified in handler
+    pub item_metadata: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub seller_signer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelCollectionOffer<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [
+            COLLECTION_OFFER_SEED,
+            game.key().as_ref(),
+            bidder.key().as_ref(),
+            &offer.offer_id.to_le_bytes()
+        ],
+        bump = offer.bump
+    )]
+    pub offer: Account<'info, CollectionOffer>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_ledger: Account<'info, PlayerLedger>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collection_offer_leaf_proves_membership_in_criteria_root() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (leaf_a, leaf_b) = (collection_offer_leaf(&a), collection_offer_leaf(&b));
+        assert_eq!(leaf_a, hashv(&[a.as_ref()]).to_bytes());
+
+        // Single-item offer: the root is the leaf itself
+        assert!(verify_merkle_proof(&leaf_a, &[], &leaf_a).is_ok());
+
+        let (lo, hi) = if leaf_a <= leaf_b {
+            (leaf_a, leaf_b)
+        } else {
+            (leaf_b, leaf_a)
+        };
+        let root = hashv(&[&[lo, hi].concat()]).to_bytes();
+        assert!(verify_merkle_proof(&leaf_a, &[leaf_b], &root).is_ok());
+        assert!(verify_merkle_proof(&leaf_b, &[leaf_a], &root).is_ok());
+
+        let outsider = collection_offer_leaf(&Pubkey::new_unique());
+        assert_eq!(
+            verify_merkle_proof(&outsider, &[leaf_b], &root),
+            Err(error!(PgError::InvalidMerkleProof))
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    enter_execution_game, exit_execution_game, DaoTreasuryVault, GameConfig, GlobalConfig,
+    LpGrowthManager, PgError, ProtocolFeesDistributed, BPS_DENOM, CONFIG_SEED, DAO_TREASURY_SEED,
+    GAME_SEED, LP_GROWTH_SEED, VAULT_SEED,
+};
+
+// ======================================================================
+// PROTOCOL FEE ROUTER INSTRUCTIONS
+// ======================================================================
+
+/// Sweep a game's `protocol_fees_accumulated` along the governance fee router splits
+/// (permissionless).
+///
+/// The treasury share goes to the DAO treasury vault's token account, the LP share to the
+/// LP growth manager's `fee_accumulation_account` and the burn share is burned from the game
+/// vault. Rounding dust goes to the treasury. Destination accounts are only needed for
+/// shares that are non-zero.
+///
+/// # Errors
+/// - `PgError::FeeRouterDisabled` if governance hasn't configured the router
+/// - `PgError::InvalidAmount` if the game has no protocol fees to distribute
+/// - `PgError::FeeRouteMismatch` if a needed destination is missing, is not the vault's or
+///   manager's fee account, or doesn't hold the game currency
+/// - `PgError::Overflow` on arithmetic overflow
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+#[allow(clippy::too_many_lines)] // Three destinations with their bookkeeping
+pub fn distribute_protocol_fees(ctx: Context<DistributeProtocolFees>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let splits = ctx.accounts.config.fee_router;
+    require!(splits.is_enabled(), PgError::FeeRouterDisabled);
+
+    let game = &ctx.accounts.game;
+    let amount = game.protocol_fees_accumulated;
+    require!(amount > 0, PgError::InvalidAmount);
+
+    let share = |bps: u16| -> Result<u64> {
+        Ok(amount
+            .checked_mul(u64::from(bps))
+            .ok_or(PgError::Overflow)?
+            .checked_div(BPS_DENOM)
+            .ok_or(PgError::Overflow)?)
+    };
+    let lp_growth_amount = share(splits.lp_growth_bps)?;
+    let burned = share(splits.burn_bps)?;
+    let treasury_amount = amount
+        .checked_sub(lp_growth_amount)
+        .ok_or(PgError::Overflow)?
+        .checked_sub(burned)
+        .ok_or(PgError::Overflow)?;
+
+    if treasury_amount > 0 {
+        let vault = ctx
+            .accounts
+            .dao_treasury
+            .as_ref()
+            .ok_or(PgError::FeeRouteMismatch)?;
+        let account = ctx
+            .accounts
+            .treasury_token_account
+            .as_ref()
+            .ok_or(PgError::FeeRouteMismatch)?;
+        // The vault books one mint; it must be the currency being routed
+        require!(vault.mint == game.currency_mint, PgError::FeeRouteMismatch);
+        require!(
+            account.owner == vault.key() && account.mint == game.currency_mint,
+            PgError::FeeRouteMismatch
+        );
+    }
+    if lp_growth_amount > 0 {
+        let manager = ctx
+            .accounts
+            .lp_growth
+            .as_ref()
+            .ok_or(PgError::FeeRouteMismatch)?;
+        let account = ctx
+            .accounts
+            .lp_fee_account
+            .as_ref()
+            .ok_or(PgError::FeeRouteMismatch)?;
+        require!(
+            account.key() == manager.fee_accumulation_account && account.mint == game.currency_mint,
+            PgError::FeeRouteMismatch
+        );
+    }
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        game.protocol_fees_accumulated = 0;
+
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+        let decimals = ctx.accounts.currency_mint.decimals;
+
+        let route = |to, route_amount: u64| -> Result<()> {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_interface::TransferChecked {
+                        from: ctx.accounts.game_vault.to_account_info(),
+                        mint: ctx.accounts.currency_mint.to_account_info(),
+                        to,
+                        authority: game.to_account_info(),
+                    },
+                    signer,
+                ),
+                route_amount,
+                decimals,
+            )
+        };
+
+        if let (Some(vault), Some(account)) = (
+            ctx.accounts.dao_treasury.as_deref_mut(),
+            ctx.accounts.treasury_token_account.as_ref(),
+        ) {
+            if treasury_amount > 0 {
+                route(account.to_account_info(), treasury_amount)?;
+                vault.total_received = vault
+                    .total_received
+                    .checked_add(treasury_amount)
+                    .ok_or(PgError::Overflow)?;
+                vault.balance = vault
+                    .balance
+                    .checked_add(treasury_amount)
+                    .ok_or(PgError::Overflow)?;
+            }
+        }
+
+        if let (Some(manager), Some(account)) = (
+            ctx.accounts.lp_growth.as_deref_mut(),
+            ctx.accounts.lp_fee_account.as_ref(),
+        ) {
+            if lp_growth_amount > 0 {
+                route(account.to_account_info(), lp_growth_amount)?;
+                manager.total_fees_accumulated = manager
+                    .total_fees_accumulated
+                    .checked_add(lp_growth_amount)
+                    .ok_or(PgError::Overflow)?;
+            }
+        }
+
+        if burned > 0 {
+            token_interface::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_interface::Burn {
+                        mint: ctx.accounts.currency_mint.to_account_info(),
+                        from: ctx.accounts.game_vault.to_account_info(),
+                        authority: game.to_account_info(),
+                    },
+                    signer,
+                ),
+                burned,
+            )?;
+        }
+
+        emit!(ProtocolFeesDistributed {
+            game: game.key(),
+            amount,
+            treasury_amount,
+            lp_growth_amount,
+            burned,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct DistributeProtocolFees<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump,
+        token::mint = currency_mint,
+        token::authority = game,
+        constraint = currency_mint.key() == game.currency_mint @ PgError::CurrencyMintMismatch
+    )]
+    pub game_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Writable for the burn share
+    #[account(mut)]
+    pub currency_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Required when the treasury share is non-zero
+    #[account(
+        mut,
+        seeds = [DAO_TREASURY_SEED, config.governance.as_ref()],
+        bump = dao_treasury.bump
+    )]
+    pub dao_treasury: Option<Box<Account<'info, DaoTreasuryVault>>>,
+
+    /// DAO treasury vault's token account for the game currency
+    #[account(mut)]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Required when the LP growth share is non-zero
+    #[account(
+        mut,
+        seeds = [LP_GROWTH_SEED, config.pdox_mint.as_ref()],
+        bump = lp_growth.bump
+    )]
+    pub lp_growth: Option<Box<Account<'info, LpGrowthManager>>>,
+
+    /// LP growth manager's `fee_accumulation_account`
+    #[account(mut)]
+    pub lp_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    enter_execution_game, exit_execution_game, GameConfig, GlobalConfig, NetBatchFeesCharged,
+    NetTradeVolumeData, PgError, PlayerLedger, BPS_DENOM, LEDGER_SEED,
+};
+
+/// Maker/taker protocol fees for one trade
+#[derive(Clone, Copy, Default)]
+pub struct TradeFees {
+    pub maker_fee_bps: u16,
+    pub taker_fee_bps: u16,
+    pub maker_fee: u64, // Deducted from the seller's proceeds
+    pub taker_fee: u64, // Paid by the buyer on top of the price
+}
+
+impl TradeFees {
+    /// Protocol fee booked for the trade (maker + taker)
+    ///
+    /// # Errors
+    /// - `PgError::Overflow` on arithmetic overflow
+    pub fn total(&self) -> Result<u64> {
+        Ok(self
+            .maker_fee
+            .checked_add(self.taker_fee)
+            .ok_or(PgError::Overflow)?)
+    }
+}
+
+/// `amount * bps / BPS_DENOM`, rounded down
+fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    Ok(amount
+        .checked_mul(u64::from(bps))
+        .ok_or(PgError::Overflow)?
+        .checked_div(BPS_DENOM)
+        .ok_or(PgError::Overflow)?)
+}
+
+// ======================================================================
+// FEE TIER HELPERS
+// ======================================================================
+
+/// Reduce a fee rate by `discount_bps` of itself (rounded in the payer's favour)
+fn discounted_bps(fee_bps: u16, discount_bps: u16) -> Result<u16> {
+    let discount = u64::from(fee_bps)
+        .checked_mul(u64::from(discount_bps))
+        .ok_or(PgError::Overflow)?
+        .div_ceil(BPS_DENOM);
+    let discount = u16::try_from(discount).map_err(|_| PgError::Overflow)?;
+    Ok(fee_bps.saturating_sub(discount))
+}
+
+/// Quote maker/taker fees on `total_price` from each side's 30-day volume (before this trade),
+/// after each side's PDOX staker discount.
+///
+/// # Errors
+/// - `PgError::Overflow` on arithmetic overflow
+pub fn quote_trade_fees(
+    cfg: &GlobalConfig,
+    maker_volume: u64,
+    taker_volume: u64,
+    maker_discount_bps: u16,
+    taker_discount_bps: u16,
+    total_price: u64,
+) -> Result<TradeFees> {
+    let (maker_fee_bps, taker_fee_bps) = cfg.fee_rates(maker_volume, taker_volume);
+    let maker_fee_bps = discounted_bps(maker_fee_bps, maker_discount_bps)?;
+    let taker_fee_bps = discounted_bps(taker_fee_bps, taker_discount_bps)?;
+    Ok(TradeFees {
+        maker_fee_bps,
+        taker_fee_bps,
+        maker_fee: bps_of(total_price, maker_fee_bps)?,
+        taker_fee: bps_of(total_price, taker_fee_bps)?,
+    })
+}
+
+/// Charge tiered maker/taker fees for a net batch, one entry per (game, wallet).
+///
+/// Each entry's ledger (`[LEDGER_SEED, game, owner]`) and `GameConfig` must be in
+/// `remaining_accounts`. The fee is debited from the ledger, booked to the game's
+/// `protocol_fees_accumulated` (under the game's reentrancy guard) and the batch volume is
+/// added to the wallet's 30-day volume.
+///
+/// # Errors
+/// - `PgError::InvalidAmount` if a ledger or game account is missing
+/// - `PgError::Unauthorized` if an account isn't owned by this program, doesn't match the entry
+///   or the game is already in execution
+/// - `PgError::InsufficientCredits` if a wallet can't cover its fee
+pub fn charge_net_batch_fees(
+    cfg: &GlobalConfig,
+    volumes: &[NetTradeVolumeData],
+    remaining_accounts: &[AccountInfo],
+    batch_id: u64,
+    now: i64,
+    program_id: &Pubkey,
+) -> Result<()> {
+    for entry in volumes {
+        let (ledger_key, _) = Pubkey::find_program_address(
+            &[LEDGER_SEED, entry.game.as_ref(), entry.owner.as_ref()],
+            program_id,
+        );
+        let ledger_info = remaining_accounts
+            .iter()
+            .find(|acc| acc.key() == ledger_key)
+            .ok_or(PgError::InvalidAmount)?;
+        let game_info = remaining_accounts
+            .iter()
+            .find(|acc| acc.key() == entry.game)
+            .ok_or(PgError::InvalidAmount)?;
+        require!(ledger_info.owner == program_id, PgError::Unauthorized);
+        require!(game_info.owner == program_id, PgError::Unauthorized);
+
+        let mut game_data = game_info.try_borrow_mut_data()?;
+        let mut game = GameConfig::try_deserialize(&mut &game_data[..])?;
+        enter_execution_game(&mut game)?;
+
+        let mut ledger_data = ledger_info.try_borrow_mut_data()?;
+        let mut ledger = PlayerLedger::try_deserialize(&mut &ledger_data[..])?;
+        require!(
+            ledger.game == entry.game && ledger.authority == entry.owner,
+            PgError::Unauthorized
+        );
+
+        // Rates come from the wallet's volume before this batch
+        let volume_30d = ledger.fee_volume.volume(now)?;
+        let (maker_fee_bps, taker_fee_bps) = cfg.fee_rates(volume_30d, volume_30d);
+        let fee = bps_of(entry.maker_volume, maker_fee_bps)?
+            .checked_add(bps_of(entry.taker_volume, taker_fee_bps)?)
+            .ok_or(PgError::Overflow)?;
+
+        require!(ledger.available >= fee, PgError::InsufficientCredits);
+        ledger.available = ledger.available.checked_sub(fee).ok_or(PgError::Overflow)?;
+        let batch_volume = entry
+            .maker_volume
+            .checked_add(entry.taker_volume)
+            .ok_or(PgError::Overflow)?;
+        ledger.fee_volume.record(batch_volume, now)?;
+        ledger.try_serialize(&mut &mut ledger_data[..])?;
+
+        game.protocol_fees_accumulated = game
+            .protocol_fees_accumulated
+            .checked_add(fee)
+            .ok_or(PgError::Overflow)?;
+        exit_execution_game(&mut game);
+        game.try_serialize(&mut &mut game_data[..])?;
+
+        emit!(NetBatchFeesCharged {
+            batch_id,
+            game: entry.game,
+            player: entry.owner,
+            maker_volume: entry.maker_volume,
+            taker_volume: entry.taker_volume,
+            volume_30d,
+            maker_fee_bps,
+            taker_fee_bps,
+            fee,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::zeroed;
+    use crate::FeeTier;
+
+    #[test]
+    fn quote_trade_fees_applies_tiers_then_stake_discounts() {
+        let mut cfg = zeroed::<GlobalConfig>();
+        cfg.protocol_fee_bps = 250;
+        let fees = quote_trade_fees(&cfg, 0, 0, 0, 0, 10_000).unwrap();
+        assert_eq!((fees.maker_fee_bps, fees.taker_fee_bps), (250, 0));
+        assert_eq!((fees.maker_fee, fees.taker_fee), (250, 0));
+
+        cfg.fee_tiers[0] = FeeTier {
+            min_volume: 0,
+            maker_fee_bps: 100,
+            taker_fee_bps: 255,
+        };
+        cfg.fee_tier_count = 1;
+        // 10% off 255 bps is 25.5 bps, rounded in the taker's favour
+        let fees = quote_trade_fees(&cfg, 0, 0, 5_000, 1_000, 10_000).unwrap();
+        assert_eq!((fees.maker_fee_bps, fees.taker_fee_bps), (50, 229));
+        assert_eq!((fees.maker_fee, fees.taker_fee), (50, 229));
+        assert_eq!(fees.total().unwrap(), 279);
+
+        // Fee amounts round down
+        let fees = quote_trade_fees(&cfg, 0, 0, 0, 0, 99).unwrap();
+        assert_eq!((fees.maker_fee, fees.taker_fee), (0, 2));
+    }
+}
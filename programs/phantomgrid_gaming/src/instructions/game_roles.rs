@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    GameConfig, GameRole, GameRoleMember, GameRoleUpdated, GameRoles, PgError, GAME_ROLES_SEED,
+    GAME_SEED,
+};
+
+/// Whether `caller` is the game owner or holds `role` in the game's roles account
+pub fn is_owner_or_role(
+    game: &GameConfig,
+    roles: Option<&Account<GameRoles>>,
+    caller: &Pubkey,
+    role: GameRole,
+) -> bool {
+    *caller == game.owner || roles.is_some_and(|roles| roles.has_role(caller, role))
+}
+
+// ======================================================================
+// GAME ROLE INSTRUCTIONS
+// ======================================================================
+
+/// Grant `member` the `roles` bit set (see `GameRole`), replacing its previous roles.
+/// `roles == 0` removes the member. Creates the game's roles account on first call.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not the game owner
+/// - `PgError::InvalidGameRoles` if `member` is the default pubkey or `roles` has unknown bits
+/// - `PgError::GameRolesFull` if all `MAX_GAME_ROLE_MEMBERS` slots are taken
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn set_game_role(ctx: Context<SetGameRole>, member: Pubkey, roles: u8) -> Result<()> {
+    require!(
+        member != Pubkey::default() && roles & !GameRole::ALL == 0,
+        PgError::InvalidGameRoles
+    );
+
+    let game_key = ctx.accounts.game.key();
+    let account = &mut ctx.accounts.roles;
+    if account.game == Pubkey::default() {
+        account.game = game_key;
+        account.bump = ctx.bumps.roles;
+    }
+
+    let existing = account.members.iter().position(|m| m.key == member);
+    match (roles, existing) {
+        (0, Some(slot)) => account.members[slot] = GameRoleMember::default(),
+        (0, None) => {} // Nothing to remove
+        (_, Some(slot)) => account.members[slot].roles = roles,
+        (_, None) => {
+            let slot = account
+                .members
+                .iter()
+                .position(|m| m.key == Pubkey::default())
+                .ok_or(PgError::GameRolesFull)?;
+            account.members[slot] = GameRoleMember { key: member, roles };
+        }
+    }
+
+    emit!(GameRoleUpdated {
+        game: game_key,
+        member,
+        roles,
+    });
+
+    Ok(())
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct SetGameRole<'info> {
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump,
+        has_one = owner @ PgError::Unauthorized
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<GameRoles>()
+    )]
+    pub roles: Box<Account<'info, GameRoles>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{leaked_account, zeroed};
+
+    #[test]
+    fn is_owner_or_role_checks_owner_then_the_role_bit() {
+        let owner = Pubkey::new_unique();
+        let pauser = Pubkey::new_unique();
+        let mut game = zeroed::<GameConfig>();
+        game.owner = owner;
+
+        let mut roles = zeroed::<GameRoles>();
+        roles.members[0] = GameRoleMember {
+            key: pauser,
+            roles: GameRole::Pauser.bit(),
+        };
+        let mut data = Vec::new();
+        roles.try_serialize(&mut data).unwrap();
+        let info = leaked_account(Pubkey::new_unique(), 1, data, crate::ID);
+        let roles = Account::<GameRoles>::try_from(Box::leak(Box::new(info))).unwrap();
+
+        assert!(is_owner_or_role(
+            &game,
+            None,
+            &owner,
+            GameRole::ConfigManager
+        ));
+        assert!(is_owner_or_role(
+            &game,
+            Some(&roles),
+            &pauser,
+            GameRole::Pauser
+        ));
+        assert!(!is_owner_or_role(
+            &game,
+            Some(&roles),
+            &pauser,
+            GameRole::FeeWithdrawer
+        ));
+        assert!(!is_owner_or_role(&game, None, &pauser, GameRole::Pauser));
+        assert!(!is_owner_or_role(
+            &game,
+            Some(&roles),
+            &Pubkey::new_unique(),
+            GameRole::Pauser
+        ));
+
+        // Free slots (default key) never hold a role
+        assert!(!is_owner_or_role(
+            &game,
+            Some(&roles),
+            &Pubkey::default(),
+            GameRole::Pauser
+        ));
+    }
+}
@@ -0,0 +1,686 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::game_roles::is_owner_or_role;
+use crate::{
+    enter_execution_game, exit_execution_game, GameConfig, GameRole, GameRoles, GlobalConfig,
+    KeeperBountyPaid, KeeperDepositUpdated, KeeperPool, KeeperPoolUpdated, Listing,
+    ListingActivated, ListingExpired, ListingKind, ListingStatus, PgError, PlayerLedger,
+    CONFIG_SEED, ESCROW_SEED, GAME_ROLES_SEED, GAME_SEED, KEEPER_POOL_MATCH_MULTIPLE,
+    KEEPER_POOL_SEED, LEDGER_SEED, LISTING_SEED,
+};
+
+// ======================================================================
+// KEEPER CRANK INSTRUCTIONS
+// ======================================================================
+
+/// Initialize the keeper ledger on first bounty (same defaults as other lazily created ledgers)
+fn init_keeper_ledger(ledger: &mut PlayerLedger, game: Pubkey, keeper: Pubkey) -> Result<()> {
+    if ledger.authority == Pubkey::default() {
+        ledger.game = game;
+        ledger.authority = keeper;
+        ledger.available = 0;
+        ledger.locked = 0;
+        ledger.kyc_verified = false;
+        ledger.kyc_provider = Pubkey::default();
+        ledger.kyc_verified_at = 0;
+        ledger.kyc_proof_hash = [0u8; 32];
+    }
+    require!(ledger.authority == keeper, PgError::Unauthorized);
+    Ok(())
+}
+
+/// Pay `bounty` to the keeper: from the listing's keeper deposit first, with the pool covering
+/// any shortfall.
+///
+/// The pool only matches the listing's keeper deposit, up to `KEEPER_POOL_MATCH_MULTIPLE` times
+/// the amount drawn from it, and never pays the seller's own wallet. A seller cranking their
+/// own listing from a second wallet still collects the match, so the pool's exposure per
+/// crank is bounded by the deposit the seller had to lock, not by the bounty. Nothing is paid
+/// when the bounty can't be covered in full (deposit too small for the match or pool short);
+/// the crank still runs.
+/// Returns `(amount, from_deposit)`.
+fn pay_keeper_bounty(
+    listing: &mut Listing,
+    pool: &mut KeeperPool,
+    bounty: u64,
+    keeper_ledger: &mut PlayerLedger,
+) -> Result<(u64, u64)> {
+    if bounty == 0 {
+        return Ok((0, 0));
+    }
+
+    let from_deposit = listing.keeper_deposit.min(bounty);
+    let from_pool = bounty.checked_sub(from_deposit).ok_or(PgError::Overflow)?;
+    if from_pool > 0 {
+        let pool_cap = from_deposit
+            .checked_mul(KEEPER_POOL_MATCH_MULTIPLE)
+            .ok_or(PgError::Overflow)?;
+        let pool_eligible = from_pool <= pool_cap && keeper_ledger.authority != listing.seller;
+        if !pool_eligible || pool.balance < from_pool {
+            return Ok((0, 0));
+        }
+        pool.balance = pool
+            .balance
+            .checked_sub(from_pool)
+            .ok_or(PgError::Overflow)?;
+    }
+    listing.keeper_deposit = listing
+        .keeper_deposit
+        .checked_sub(from_deposit)
+        .ok_or(PgError::Overflow)?;
+
+    pool.total_paid = pool
+        .total_paid
+        .checked_add(bounty)
+        .ok_or(PgError::Overflow)?;
+    keeper_ledger.available = keeper_ledger
+        .available
+        .checked_add(bounty)
+        .ok_or(PgError::Overflow)?;
+
+    Ok((bounty, from_deposit))
+}
+
+/// Configure per-game keeper bounties (creates the keeper pool on first call).
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not game owner, config manager, admin or governance
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn configure_keeper_pool(
+    ctx: Context<ConfigureKeeperPool>,
+    activation_bounty: u64,
+    expiry_bounty: u64,
+) -> Result<()> {
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let caller = ctx.accounts.caller.key();
+    let allowed = is_owner_or_role(
+        game,
+        ctx.accounts.roles.as_deref(),
+        &caller,
+        GameRole::ConfigManager,
+    ) || caller == cfg.admin
+        || caller == cfg.governance;
+    require!(allowed, PgError::Unauthorized);
+
+    let pool = &mut ctx.accounts.keeper_pool;
+    if pool.game == Pubkey::default() {
+        pool.game = game.key();
+        pool.balance = 0;
+        pool.total_paid = 0;
+        pool.bump = ctx.bumps.keeper_pool;
+    }
+    pool.activation_bounty = activation_bounty;
+    pool.expiry_bounty = expiry_bounty;
+
+    emit!(KeeperPoolUpdated {
+        pool: pool.key(),
+        game: game.key(),
+        activation_bounty,
+        expiry_bounty,
+        balance: pool.balance,
+    });
+
+    Ok(())
+}
+
+/// Fund the keeper pool from the funder's ledger credits (anyone may fund).
+///
+/// # Errors
+/// - `PgError::InvalidAmount` if amount is 0
+/// - `PgError::InsufficientCredits` if the funder doesn't have enough available credits
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn fund_keeper_pool(ctx: Context<FundKeeperPool>, amount: u64) -> Result<()> {
+    require!(amount > 0, PgError::InvalidAmount);
+    require!(
+        ctx.accounts.funder_ledger.available >= amount,
+        PgError::InsufficientCredits
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let funder_ledger = &mut ctx.accounts.funder_ledger;
+        let pool = &mut ctx.accounts.keeper_pool;
+        funder_ledger.available = funder_ledger
+            .available
+            .checked_sub(amount)
+            .ok_or(PgError::Overflow)?;
+        pool.balance = pool.balance.checked_add(amount).ok_or(PgError::Overflow)?;
+
+        emit!(KeeperPoolUpdated {
+            pool: pool.key(),
+            game: game.key(),
+            activation_bounty: pool.activation_bounty,
+            expiry_bounty: pool.expiry_bounty,
+            balance: pool.balance,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Post (or top up) a seller-funded keeper deposit on a listing; cranks draw on it before the pool.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not the seller
+/// - `PgError::InvalidListingStatus` if listing is not Pending, Active or `PartiallyFilled`
+/// - `PgError::InsufficientCredits` if the seller doesn't have enough available credits
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn post_keeper_deposit(ctx: Context<KeeperDeposit>, amount: u64) -> Result<()> {
+    require!(amount > 0, PgError::InvalidAmount);
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let listing = &ctx.accounts.listing;
+    require!(
+        listing.seller == ctx.accounts.seller.key(),
+        PgError::Unauthorized
+    );
+    require!(
+        listing.status == ListingStatus::Pending
+            || listing.status == ListingStatus::Active
+            || listing.status == ListingStatus::PartiallyFilled,
+        PgError::InvalidListingStatus
+    );
+    require!(
+        ctx.accounts.seller_ledger.available >= amount,
+        PgError::InsufficientCredits
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        let seller_ledger = &mut ctx.accounts.seller_ledger;
+        seller_ledger.available = seller_ledger
+            .available
+            .checked_sub(amount)
+            .ok_or(PgError::Overflow)?;
+        listing.keeper_deposit = listing
+            .keeper_deposit
+            .checked_add(amount)
+            .ok_or(PgError::Overflow)?;
+
+        emit!(KeeperDepositUpdated {
+            listing: listing.key(),
+            game: game.key(),
+            seller: listing.seller,
+            keeper_deposit: listing.keeper_deposit,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Return an unused keeper deposit to the seller once the listing is closed.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not the seller
+/// - `PgError::InvalidListingStatus` if listing is still open
+/// - `PgError::InvalidAmount` if there is no deposit left
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn withdraw_keeper_deposit(ctx: Context<KeeperDeposit>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let listing = &ctx.accounts.listing;
+    require!(
+        listing.seller == ctx.accounts.seller.key(),
+        PgError::Unauthorized
+    );
+    require!(
+        listing.status == ListingStatus::Settled
+            || listing.status == ListingStatus::Cancelled
+            || listing.status == ListingStatus::Expired,
+        PgError::InvalidListingStatus
+    );
+    require!(listing.keeper_deposit > 0, PgError::InvalidAmount);
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        let seller_ledger = &mut ctx.accounts.seller_ledger;
+        seller_ledger.available = seller_ledger
+            .available
+            .checked_add(listing.keeper_deposit)
+            .ok_or(PgError::Overflow)?;
+        listing.keeper_deposit = 0;
+
+        emit!(KeeperDepositUpdated {
+            listing: listing.key(),
+            game: game.key(),
+            seller: listing.seller,
+            keeper_deposit: 0,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Permissionless crank: activate a `Pending` listing once `start_time` has passed.
+///
+/// Pays the keeper `keeper_pool.activation_bounty` (listing deposit first, then pool; see
+/// `pay_keeper_bounty`).
+///
+/// # Errors
+/// - `PgError::ListingsPaused` if new listings are paused
+/// - `PgError::InvalidListingStatus` if listing is not Pending
+/// - `PgError::InvalidTime` if `start_time` hasn't passed or the listing already ended
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn crank_activate_listing(ctx: Context<CrankActivateListing>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let listing = &ctx.accounts.listing;
+    require!(!cfg.paused_new, PgError::ListingsPaused);
+    require!(!game.paused_new, PgError::ListingsPaused);
+    require!(
+        listing.status == ListingStatus::Pending,
+        PgError::InvalidListingStatus
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= listing.start_time, PgError::InvalidTime);
+    require!(now <= listing.end_time, PgError::InvalidTime);
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        let pool = &mut ctx.accounts.keeper_pool;
+        let keeper_ledger = &mut ctx.accounts.keeper_ledger;
+        let keeper = ctx.accounts.keeper.key();
+
+        listing.status = ListingStatus::Active;
+        listing.updated_at = now;
+
+        init_keeper_ledger(keeper_ledger, game.key(), keeper)?;
+        let bounty = pool.activation_bounty;
+        let (amount, from_deposit) = pay_keeper_bounty(listing, pool, bounty, keeper_ledger)?;
+
+        emit!(ListingActivated {
+            listing: listing.key(),
+            game: game.key(),
+            at: now
+        });
+        if amount > 0 {
+            emit!(KeeperBountyPaid {
+                listing: listing.key(),
+                game: game.key(),
+                keeper,
+                amount,
+                from_deposit,
+            });
+        }
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Permissionless crank: return the unsold escrow of an expired listing to the seller.
+///
+/// Auctions with a standing bid (or sealed commitments) must be settled instead. No cancel
+/// penalty applies. Pays the keeper `keeper_pool.expiry_bounty` (listing deposit first, then pool;
+/// see `pay_keeper_bounty`).
+///
+/// # Errors
+/// - `PgError::SettlementsPaused` if settlements are paused
+/// - `PgError::InvalidListingStatus` if listing is not Pending, Active or `PartiallyFilled`
+/// - `PgError::InvalidTime` if `end_time` hasn't passed
+/// - `PgError::AuctionHasBids` if an auction has bids awaiting settlement
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn crank_expire_listing(ctx: Context<CrankExpireListing>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let listing = &ctx.accounts.listing;
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+    require!(
+        listing.status == ListingStatus::Pending
+            || listing.status == ListingStatus::Active
+            || listing.status == ListingStatus::PartiallyFilled,
+        PgError::InvalidListingStatus
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > listing.end_time, PgError::InvalidTime);
+    if listing.kind != ListingKind::Fixed {
+        require!(
+            listing.highest_bid == 0 && !listing.has_interest,
+            PgError::AuctionHasBids
+        );
+    }
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        let pool = &mut ctx.accounts.keeper_pool;
+        let keeper_ledger = &mut ctx.accounts.keeper_ledger;
+        let keeper = ctx.accounts.keeper.key();
+
+        listing.status = ListingStatus::Expired;
+        listing.updated_at = now;
+
+        // Return unsold items: escrow -> seller (always the original seller)
+        let quantity_returned = listing.quantity_remaining;
+        if quantity_returned > 0 {
+            let game_id = game.game_id;
+            let game_bump = game.bump;
+            let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+            let signer = &[seeds];
+
+            let cpi_accounts = token_interface::TransferChecked {
+                from: ctx.accounts.escrow_item_ata.to_account_info(),
+                mint: ctx.accounts.item_mint.to_account_info(),
+                to: ctx.accounts.seller_item_ata.to_account_info(),
+                authority: game.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(
+                cpi_ctx,
+                quantity_returned,
+                ctx.accounts.item_mint.decimals,
+            )?;
+        }
+
+        init_keeper_ledger(keeper_ledger, game.key(), keeper)?;
+        let bounty = pool.expiry_bounty;
+        let (amount, from_deposit) = pay_keeper_bounty(listing, pool, bounty, keeper_ledger)?;
+
+        emit!(ListingExpired {
+            listing: listing.key(),
+            game: game.key(),
+            seller: listing.seller,
+            quantity_returned,
+        });
+        if amount > 0 {
+            emit!(KeeperBountyPaid {
+                listing: listing.key(),
+                game: game.key(),
+                keeper,
+                amount,
+                from_deposit,
+            });
+        }
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct ConfigureKeeperPool<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        seeds = [KEEPER_POOL_SEED, game.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<KeeperPool>()
+    )]
+    pub keeper_pool: Box<Account<'info, KeeperPool>>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// Game roles (required when the caller acts as a `ConfigManager`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundKeeperPool<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [KEEPER_POOL_SEED, game.key().as_ref()],
+        bump = keeper_pool.bump
+    )]
+    pub keeper_pool: Box<Account<'info, KeeperPool>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), funder.key().as_ref()],
+        bump
+    )]
+    pub funder_ledger: Box<Account<'info, PlayerLedger>>,
+
+    pub funder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct KeeperDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, game.key().as_ref(), &listing.listing_id.to_le_bytes()],
+        bump
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub seller_ledger: Box<Account<'info, PlayerLedger>>,
+
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CrankActivateListing<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, game.key().as_ref(), &listing.listing_id.to_le_bytes()],
+        bump
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(
+        mut,
+        seeds = [KEEPER_POOL_SEED, game.key().as_ref()],
+        bump = keeper_pool.bump
+    )]
+    pub keeper_pool: Box<Account<'info, KeeperPool>>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        seeds = [LEDGER_SEED, game.key().as_ref(), keeper.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PlayerLedger>()
+    )]
+    pub keeper_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrankExpireListing<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, game.key().as_ref(), &listing.listing_id.to_le_bytes()],
+        bump
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(
+        mut,
+        seeds = [KEEPER_POOL_SEED, game.key().as_ref()],
+        bump = keeper_pool.bump
+    )]
+    pub keeper_pool: Box<Account<'info, KeeperPool>>,
+
+    #[account(address = listing.item_mint)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, listing.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub escrow_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Original seller (items always return to the listing's seller)
+    #[account(address = listing.seller)]
+    pub seller: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        associated_token::mint = item_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        seeds = [LEDGER_SEED, game.key().as_ref(), keeper.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PlayerLedger>()
+    )]
+    pub keeper_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::zeroed;
+
+    /// Pay `bounty` on a listing with `deposit` posted, from a pool holding `balance`
+    fn pay(
+        deposit: u64,
+        balance: u64,
+        bounty: u64,
+        keeper_is_seller: bool,
+    ) -> (u64, u64, u64, u64) {
+        let mut listing = zeroed::<Listing>();
+        listing.seller = Pubkey::new_unique();
+        listing.keeper_deposit = deposit;
+        let mut pool = zeroed::<KeeperPool>();
+        pool.balance = balance;
+        let mut keeper_ledger = zeroed::<PlayerLedger>();
+        keeper_ledger.authority = if keeper_is_seller {
+            listing.seller
+        } else {
+            Pubkey::new_unique()
+        };
+
+        let (amount, from_deposit) =
+            pay_keeper_bounty(&mut listing, &mut pool, bounty, &mut keeper_ledger).unwrap();
+        assert_eq!(keeper_ledger.available, amount);
+        assert_eq!(pool.total_paid, amount);
+        (amount, from_deposit, listing.keeper_deposit, pool.balance)
+    }
+
+    #[test]
+    fn keeper_bounty_draws_deposit_then_a_capped_pool_match() {
+        assert_eq!(pay(0, 1_000, 0, false), (0, 0, 0, 1_000));
+        // Deposit covers it all: the pool isn't touched
+        assert_eq!(pay(150, 1_000, 100, false), (100, 100, 50, 1_000));
+        // Pool matches the deposit 1:1
+        assert_eq!(pay(50, 1_000, 100, false), (100, 50, 0, 950));
+        // A 1-credit deposit can't unlock the rest of the bounty from the pool
+        assert_eq!(pay(1, 1_000, 100, false), (0, 0, 1, 1_000));
+        assert_eq!(pay(0, 1_000, 100, false), (0, 0, 0, 1_000));
+        // The seller's own wallet never gets a pool match, and a short pool pays nothing
+        assert_eq!(pay(50, 1_000, 100, true), (0, 0, 50, 1_000));
+        assert_eq!(pay(50, 49, 100, false), (0, 0, 50, 49));
+    }
+}
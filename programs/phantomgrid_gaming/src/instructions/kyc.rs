@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{GlobalConfig, KycProviderUpdated, PgError, CONFIG_SEED};
+
+// ======================================================================
+// KYC PROVIDER REGISTRY
+// ======================================================================
+
+/// Approve or remove a KYC provider allowed to attest tiers via `set_player_kyc`.
+///
+/// Removing a provider doesn't revoke attestations it already made; they still expire
+/// at their `kyc_expires_at`.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not governance
+/// - `PgError::InvalidAmount` if `provider` is the default pubkey
+/// - `PgError::KycProviderRegistryFull` if all `MAX_KYC_PROVIDERS` slots are taken
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn set_kyc_provider(
+    ctx: Context<SetKycProvider>,
+    provider: Pubkey,
+    approved: bool,
+) -> Result<()> {
+    require!(provider != Pubkey::default(), PgError::InvalidAmount);
+
+    let cfg = &mut ctx.accounts.config;
+    let existing = cfg.kyc_providers.iter().position(|p| *p == provider);
+    match (approved, existing) {
+        (true, None) => {
+            let slot = cfg
+                .kyc_providers
+                .iter()
+                .position(|p| *p == Pubkey::default())
+                .ok_or(PgError::KycProviderRegistryFull)?;
+            cfg.kyc_providers[slot] = provider;
+        }
+        (false, Some(slot)) => cfg.kyc_providers[slot] = Pubkey::default(),
+        _ => {} // Already in the requested state
+    }
+
+    emit!(KycProviderUpdated { provider, approved });
+
+    Ok(())
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct SetKycProvider<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = governance @ PgError::Unauthorized
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+    pub governance: Signer<'info>,
+}
@@ -0,0 +1,554 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022::{self, extension::ExtensionType};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{
+    self, spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::TokenMetadata,
+};
+use solana_program::hash::hashv;
+
+use crate::instructions::game_roles::is_owner_or_role;
+use crate::{
+    create_pda_account, enter_execution_game, exit_execution_game, GameConfig, GameRole, GameRoles,
+    GlobalConfig, LazyItemMinted, LazyMintCollection, LazyMintConfigured, PgError, CONFIG_SEED,
+    GAME_ROLES_SEED, GAME_SEED, LAZY_ITEM_SEED, LAZY_MINT_SEED, MAX_LAZY_NAME_LEN,
+    MAX_LAZY_SYMBOL_LEN, MAX_LAZY_URI_LEN,
+};
+
+/// Token-2022 metadata of a lazy-minted item. Its `hash()` is what the auction leaf stores
+/// in `asset_mint_or_hash` until the item is minted on first trade.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, PartialEq, Eq)]
+pub struct LazyMintMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl LazyMintMetadata {
+    /// SHA256 over the Borsh encoding (u32 length-prefixed `name`, `symbol`, `uri`)
+    ///
+    /// # Errors
+    /// - `PgError::Overflow` if a field length doesn't fit in a u32
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        let len = |field: &String| -> Result<[u8; 4]> {
+            Ok(u32::try_from(field.len())
+                .map_err(|_| PgError::Overflow)?
+                .to_le_bytes())
+        };
+        Ok(hashv(&[
+            &len(&self.name)?,
+            self.name.as_bytes(),
+            &len(&self.symbol)?,
+            self.symbol.as_bytes(),
+            &len(&self.uri)?,
+            self.uri.as_bytes(),
+        ])
+        .to_bytes())
+    }
+
+    /// Check field lengths (Metaplex-compatible limits)
+    ///
+    /// # Errors
+    /// - `PgError::InvalidLazyMetadata` if a field is empty (name/uri) or too long
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.name.is_empty() && self.name.len() <= MAX_LAZY_NAME_LEN,
+            PgError::InvalidLazyMetadata
+        );
+        require!(
+            self.symbol.len() <= MAX_LAZY_SYMBOL_LEN,
+            PgError::InvalidLazyMetadata
+        );
+        require!(
+            !self.uri.is_empty() && self.uri.len() <= MAX_LAZY_URI_LEN,
+            PgError::InvalidLazyMetadata
+        );
+        Ok(())
+    }
+}
+
+/// Accounts used to mint a lazy item on its first settlement
+pub struct LazyMintAccounts<'info> {
+    /// Game PDA: mint authority and metadata update authority
+    pub game: AccountInfo<'info>,
+    /// `[LAZY_ITEM_SEED, game, metadata_hash]` PDA, created here
+    pub mint: AccountInfo<'info>,
+    pub recipient: AccountInfo<'info>,
+    /// Recipient's Token-2022 ATA for `mint`, created here
+    pub recipient_token_account: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+// ======================================================================
+// LAZY MINT INSTRUCTIONS
+// ======================================================================
+
+/// Enable/disable lazy minting for a game (creates the collection account on first call).
+///
+/// While enabled, the program (game PDA) is the mint authority of every item minted from a
+/// metadata-hash auction leaf. `decimals` can only change before the first mint.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not game owner, admin or governance
+/// - `PgError::InvalidAmount` if `decimals` changes after items were minted
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn configure_lazy_mint(
+    ctx: Context<ConfigureLazyMint>,
+    enabled: bool,
+    decimals: u8,
+) -> Result<()> {
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let caller = ctx.accounts.caller.key();
+    let allowed = is_owner_or_role(
+        game,
+        ctx.accounts.roles.as_deref(),
+        &caller,
+        GameRole::ConfigManager,
+    ) || caller == cfg.admin
+        || caller == cfg.governance;
+    require!(allowed, PgError::Unauthorized);
+
+    let collection = &mut ctx.accounts.collection;
+    if collection.game == Pubkey::default() {
+        collection.game = game.key();
+        collection.minted_count = 0;
+        collection.created_at = Clock::get()?.unix_timestamp;
+        collection.bump = ctx.bumps.collection;
+    }
+    require!(
+        collection.minted_count == 0 || collection.decimals == decimals,
+        PgError::InvalidAmount
+    );
+    collection.enabled = enabled;
+    collection.decimals = decimals;
+
+    emit!(LazyMintConfigured {
+        collection: collection.key(),
+        game: game.key(),
+        enabled,
+        decimals,
+    });
+
+    Ok(())
+}
+
+/// Mint a lazy item on its first trade: create the Token-2022 mint (metadata pointer +
+/// embedded metadata) at its PDA and mint `quantity` to the recipient's new ATA.
+///
+/// Returns the new mint address.
+///
+/// # Errors
+/// - `PgError::LazyMintDisabled` if lazy minting is off for the game
+/// - `PgError::InvalidLazyMetadata` if the metadata fields are invalid
+/// - `PgError::LazyMintMismatch` if `metadata` doesn't hash to `metadata_hash` or the mint isn't its PDA
+/// - `PgError::LazyItemAlreadyMinted` if the item was already minted
+/// - `PgError::Unauthorized` if the token program is not Token-2022
+#[allow(clippy::too_many_arguments)] // Game signer seeds and collection state are all required
+pub fn mint_lazy_item<'info>(
+    accounts: &LazyMintAccounts<'info>,
+    collection: &mut LazyMintCollection,
+    game: &GameConfig,
+    game_key: Pubkey,
+    metadata: &LazyMintMetadata,
+    metadata_hash: [u8; 32],
+    quantity: u64,
+    program_id: &Pubkey,
+) -> Result<Pubkey> {
+    require!(
+        collection.enabled && collection.game == game_key,
+        PgError::LazyMintDisabled
+    );
+    require!(quantity > 0, PgError::InvalidAmount);
+    metadata.validate()?;
+    require!(metadata.hash()? == metadata_hash, PgError::LazyMintMismatch);
+    require!(
+        accounts.token_program.key() == spl_token_2022::ID,
+        PgError::Unauthorized
+    );
+
+    let (mint_key, mint_bump) = Pubkey::find_program_address(
+        &[LAZY_ITEM_SEED, game_key.as_ref(), &metadata_hash],
+        program_id,
+    );
+    require!(accounts.mint.key() == mint_key, PgError::LazyMintMismatch);
+    // Only an unused (system-owned) address can become the mint
+    require!(
+        accounts.mint.data_is_empty() && accounts.mint.owner == &system_program::ID,
+        PgError::LazyItemAlreadyMinted
+    );
+
+    let game_id_bytes = game.game_id.to_le_bytes();
+    let game_seeds: &[&[u8]] = &[GAME_SEED, &game_id_bytes, &[game.bump]];
+    let mint_seeds: &[&[u8]] = &[
+        LAZY_ITEM_SEED,
+        game_key.as_ref(),
+        &metadata_hash,
+        &[mint_bump],
+    ];
+
+    // Allocate the base mint + metadata pointer; fund for the metadata TLV appended on init
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::MetadataPointer,
+    ])?;
+    let token_metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(game_key),
+        mint: mint_key,
+        name: metadata.name.clone(),
+        symbol: metadata.symbol.clone(),
+        uri: metadata.uri.clone(),
+        additional_metadata: Vec::new(),
+    };
+    let metadata_space = token_metadata.tlv_size_of()?;
+    let lamports =
+        Rent::get()?.minimum_balance(space.checked_add(metadata_space).ok_or(PgError::Overflow)?);
+    let space = u64::try_from(space).map_err(|_| PgError::Overflow)?;
+    create_pda_account(
+        &accounts.mint,
+        &accounts.payer,
+        &accounts.system_program,
+        lamports,
+        space,
+        &spl_token_2022::ID,
+        mint_seeds,
+    )?;
+
+    // Metadata lives in the mint itself
+    token_interface::metadata_pointer_initialize(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            token_interface::MetadataPointerInitialize {
+                token_program_id: accounts.token_program.clone(),
+                mint: accounts.mint.clone(),
+            },
+        ),
+        Some(game_key),
+        Some(mint_key),
+    )?;
+    token_interface::initialize_mint2(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            token_interface::InitializeMint2 {
+                mint: accounts.mint.clone(),
+            },
+        ),
+        collection.decimals,
+        &game_key,
+        None,
+    )?;
+    token_interface::token_metadata_initialize(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            token_interface::TokenMetadataInitialize {
+                program_id: accounts.token_program.clone(),
+                metadata: accounts.mint.clone(),
+                update_authority: accounts.game.clone(),
+                mint_authority: accounts.game.clone(),
+                mint: accounts.mint.clone(),
+            },
+            &[game_seeds],
+        ),
+        metadata.name.clone(),
+        metadata.symbol.clone(),
+        metadata.uri.clone(),
+    )?;
+
+    // Mint straight to the recipient
+    associated_token::create(CpiContext::new(
+        accounts.associated_token_program.clone(),
+        associated_token::Create {
+            payer: accounts.payer.clone(),
+            associated_token: accounts.recipient_token_account.clone(),
+            authority: accounts.recipient.clone(),
+            mint: accounts.mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+        },
+    ))?;
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            token_interface::MintTo {
+                mint: accounts.mint.clone(),
+                to: accounts.recipient_token_account.clone(),
+                authority: accounts.game.clone(),
+            },
+            &[game_seeds],
+        ),
+        quantity,
+    )?;
+
+    collection.minted_count = collection
+        .minted_count
+        .checked_add(1)
+        .ok_or(PgError::Overflow)?;
+
+    emit!(LazyItemMinted {
+        game: game_key,
+        mint: mint_key,
+        metadata_hash,
+        recipient: accounts.recipient.key(),
+        quantity,
+    });
+
+    Ok(mint_key)
+}
+
+/// Settle the first trade of a lazy-minted item outside state compression: the server
+/// authority mints the item described by `metadata` to the buyer it matched off-chain.
+/// Payment is netted through `settle_net_batch` like any other off-chain trade.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if the signer is not the config `server_authority`
+/// - `PgError::SettlementsPaused` if settlements are paused
+/// - Any `mint_lazy_item` error (disabled collection, metadata mismatch, already minted)
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn settle_lazy_item(
+    ctx: Context<SettleLazyItem>,
+    metadata: LazyMintMetadata,
+    metadata_hash: [u8; 32],
+    quantity: u64,
+) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    require!(
+        !ctx.accounts.config.paused_settlements,
+        PgError::SettlementsPaused
+    );
+    require!(
+        !ctx.accounts.game.paused_settlements,
+        PgError::SettlementsPaused
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let accounts = LazyMintAccounts {
+            game: game.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            recipient: ctx.accounts.recipient.to_account_info(),
+            recipient_token_account: ctx.accounts.recipient_token_account.to_account_info(),
+            payer: ctx.accounts.server_authority.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        mint_lazy_item(
+            &accounts,
+            &mut ctx.accounts.collection,
+            game,
+            game.key(),
+            &metadata,
+            metadata_hash,
+            quantity,
+            ctx.program_id,
+        )?;
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct ConfigureLazyMint<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        seeds = [LAZY_MINT_SEED, game.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<LazyMintCollection>()
+    )]
+    pub collection: Box<Account<'info, LazyMintCollection>>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// Game roles (required when the caller acts as a `ConfigManager`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleLazyItem<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = server_authority @ PgError::Unauthorized
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LAZY_MINT_SEED, game.key().as_ref()],
+        bump = collection.bump
+    )]
+    pub collection: Box<Account<'info, LazyMintCollection>>,
+
+    /// CHECK: `[LAZY_ITEM_SEED, game, metadata_hash]` mint PDA, checked and created by `mint_lazy_item`
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Buyer the item was settled to off-chain
+    pub recipient: UncheckedAccount<'info>,
+
+    /// CHECK: Recipient's Token-2022 ATA for `mint`, created by `mint_lazy_item`
+    #[account(mut)]
+    pub recipient_token_account: UncheckedAccount<'info>,
+
+    /// Server authority: signs the settlement and pays the mint and ATA rent
+    #[account(mut)]
+    pub server_authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{leaked_account, zeroed};
+
+    fn metadata(name: &str, symbol: &str, uri: &str) -> LazyMintMetadata {
+        LazyMintMetadata {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+        }
+    }
+
+    #[test]
+    fn hash_is_sha256_of_the_borsh_encoding() {
+        let item = metadata("Sword", "SWD", "https://example.com/sword.json");
+        let mut encoded = Vec::new();
+        item.serialize(&mut encoded).unwrap();
+        assert_eq!(item.hash().unwrap(), hashv(&[&encoded]).to_bytes());
+    }
+
+    #[test]
+    fn hash_separates_fields_by_length_prefix() {
+        let a = metadata("ab", "c", "uri").hash().unwrap();
+        let b = metadata("a", "bc", "uri").hash().unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a, metadata("ab", "c", "uri").hash().unwrap());
+    }
+
+    fn lazy_accounts(
+        mint: AccountInfo<'static>,
+        token_program: Pubkey,
+    ) -> LazyMintAccounts<'static> {
+        let system = system_program::ID;
+        LazyMintAccounts {
+            game: leaked_account(Pubkey::new_unique(), 0, Vec::new(), crate::ID),
+            mint,
+            recipient: leaked_account(Pubkey::new_unique(), 0, Vec::new(), system),
+            recipient_token_account: leaked_account(Pubkey::new_unique(), 0, Vec::new(), system),
+            payer: leaked_account(Pubkey::new_unique(), 0, Vec::new(), system),
+            token_program: leaked_account(token_program, 0, Vec::new(), system),
+            associated_token_program: leaked_account(associated_token::ID, 0, Vec::new(), system),
+            system_program: leaked_account(system, 0, Vec::new(), system),
+        }
+    }
+
+    #[test]
+    fn mint_lazy_item_rejects_before_creating_anything() {
+        let game_key = Pubkey::new_unique();
+        let game = zeroed::<GameConfig>();
+        let mut collection = zeroed::<LazyMintCollection>();
+        collection.enabled = true;
+        collection.game = game_key;
+        let item = metadata("Sword", "SWD", "https://example.com/sword.json");
+        let hash = item.hash().unwrap();
+        let (mint_key, _) =
+            Pubkey::find_program_address(&[LAZY_ITEM_SEED, game_key.as_ref(), &hash], &crate::ID);
+        let unused = || leaked_account(mint_key, 0, Vec::new(), system_program::ID);
+        let mint = |accounts: &LazyMintAccounts<'static>,
+                    collection: &mut LazyMintCollection,
+                    hash: [u8; 32]| {
+            mint_lazy_item(
+                accounts,
+                collection,
+                &game,
+                game_key,
+                &item,
+                hash,
+                1,
+                &crate::ID,
+            )
+        };
+
+        let accounts = lazy_accounts(unused(), spl_token_2022::ID);
+        assert_eq!(
+            mint(&accounts, &mut collection, [7; 32]),
+            Err(error!(PgError::LazyMintMismatch))
+        );
+
+        let wrong_pda = leaked_account(Pubkey::new_unique(), 0, Vec::new(), system_program::ID);
+        let accounts = lazy_accounts(wrong_pda, spl_token_2022::ID);
+        assert_eq!(
+            mint(&accounts, &mut collection, hash),
+            Err(error!(PgError::LazyMintMismatch))
+        );
+
+        let accounts = lazy_accounts(unused(), anchor_spl::token::ID);
+        assert_eq!(
+            mint(&accounts, &mut collection, hash),
+            Err(error!(PgError::Unauthorized))
+        );
+
+        // Already a mint, or an address some other program owns
+        let minted = leaked_account(mint_key, 1, vec![0; 82], spl_token_2022::ID);
+        let accounts = lazy_accounts(minted, spl_token_2022::ID);
+        assert_eq!(
+            mint(&accounts, &mut collection, hash),
+            Err(error!(PgError::LazyItemAlreadyMinted))
+        );
+        let assigned = leaked_account(mint_key, 1, Vec::new(), spl_token_2022::ID);
+        let accounts = lazy_accounts(assigned, spl_token_2022::ID);
+        assert_eq!(
+            mint(&accounts, &mut collection, hash),
+            Err(error!(PgError::LazyItemAlreadyMinted))
+        );
+
+        collection.enabled = false;
+        let accounts = lazy_accounts(unused(), spl_token_2022::ID);
+        assert_eq!(
+            mint(&accounts, &mut collection, hash),
+            Err(error!(PgError::LazyMintDisabled))
+        );
+    }
+}
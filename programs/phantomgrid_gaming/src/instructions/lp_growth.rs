@@ -208,10 +208,7 @@ pub fn execute_lp_growth(ctx: Context<ExecuteLpGrowth>) -> Result<()> {
     // For now, we'll track the values
     
     // Update manager state
-    manager.total_fees_accumulated = manager.total_fees_accumulated
-        .checked_add(sol_to_add)
-        .ok_or(PgError::Overflow)?;
-    
+    // NOTE: total_fees_accumulated is booked by distribute_protocol_fees when fees arrive
     manager.total_fees_used_for_growth = manager.total_fees_used_for_growth
         .checked_add(sol_to_add)
         .ok_or(PgError::Overflow)?;
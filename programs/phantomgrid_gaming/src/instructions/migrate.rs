@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::{
+    AccountMigrated, GameConfig, GlobalConfig, Listing, PgError, PlayerLedger, CONFIG_SEED,
+};
+
+/// Byte offset of `GlobalConfig.governance` (after the discriminator and `admin`)
+const CONFIG_GOVERNANCE_OFFSET: usize = 8 + 32;
+
+/// Grow a `T` account created before fields were appended to `T` to `8 + size_of::<T>()`.
+///
+/// The appended bytes are zeroed, which is the default of every field added since the
+/// account was created (`Pubkey::default()`, first enum variant, zero limits). `payer`
+/// tops up rent for the new size. Already migrated accounts are left untouched.
+///
+/// # Errors
+/// - `PgError::InvalidAccountLayout` if `account` isn't a program-owned `T` account
+pub(crate) fn grow_account<'info, T: Discriminator>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    require_keys_eq!(*account.owner, crate::ID, PgError::InvalidAccountLayout);
+    {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= T::DISCRIMINATOR.len()
+                && data[..T::DISCRIMINATOR.len()] == *T::DISCRIMINATOR,
+            PgError::InvalidAccountLayout
+        );
+    }
+
+    let old_len = account.data_len();
+    let new_len = 8 + std::mem::size_of::<T>();
+    if old_len >= new_len {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?.minimum_balance(new_len);
+    let top_up = rent.saturating_sub(account.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.resize(new_len)?;
+
+    emit!(AccountMigrated {
+        account: account.key(),
+        old_len: u64::try_from(old_len).map_err(|_| PgError::Overflow)?,
+        new_len: u64::try_from(new_len).map_err(|_| PgError::Overflow)?,
+    });
+
+    Ok(())
+}
+
+// ======================================================================
+// MIGRATION INSTRUCTIONS
+// ======================================================================
+
+/// Resize the CONFIG PDA to the current `GlobalConfig` layout (governance only).
+///
+/// Configs created before the referral, KYC tier, fee tier, staker discount, PDOX fee and
+/// fee router fields no longer deserialize; this appends those fields with their defaults
+/// (all features off, matching `init_config`). Calling it on a current config is a no-op.
+///
+/// # Errors
+/// - `PgError::InvalidAccountLayout` if `config` isn't a program-owned `GlobalConfig` account
+/// - `PgError::Unauthorized` if caller is not the governance stored in the config
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+    let config = ctx.accounts.config.to_account_info();
+    require_keys_eq!(*config.owner, crate::ID, PgError::InvalidAccountLayout);
+    {
+        // The leading fields never moved, so governance is read from the old layout
+        let data = config.try_borrow_data()?;
+        let governance = data
+            .get(CONFIG_GOVERNANCE_OFFSET..CONFIG_GOVERNANCE_OFFSET + 32)
+            .ok_or(PgError::InvalidAccountLayout)?;
+        require!(
+            governance == ctx.accounts.governance.key().as_ref(),
+            PgError::Unauthorized
+        );
+    }
+
+    grow_account::<GlobalConfig>(
+        &config,
+        &ctx.accounts.governance.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
+
+/// Resize a `GameConfig` to the current layout (permissionless, `payer` funds the rent).
+///
+/// Games created before jurisdiction lists no longer deserialize, which blocks every
+/// instruction on the game. The old `reserved` bytes were never written, so the added
+/// `jurisdictions` policy starts `Disabled` with no countries listed.
+///
+/// # Errors
+/// - `PgError::InvalidAccountLayout` if `game` isn't a program-owned `GameConfig`
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn migrate_game_config(ctx: Context<MigrateGameConfig>) -> Result<()> {
+    grow_account::<GameConfig>(
+        &ctx.accounts.game.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
+
+/// Resize a `Listing` to the current layout (permissionless, `payer` funds the rent).
+///
+/// Listings opened before second-price settlement, Dutch curves, soft-close, royalty splits,
+/// version guards, keeper deposits and private buyers no longer deserialize, which strands
+/// their escrowed items. The old `reserved` bytes were never written, so every added field
+/// starts at its default: first-price, linear Dutch decay, the game's soft-close, no split,
+/// version 0, no keeper deposit and open to every buyer.
+///
+/// # Errors
+/// - `PgError::InvalidAccountLayout` if `listing` isn't a program-owned `Listing`
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn migrate_listing(ctx: Context<MigrateListing>) -> Result<()> {
+    grow_account::<Listing>(
+        &ctx.accounts.listing.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
+
+/// Resize a `PlayerLedger` to the current layout (permissionless, `payer` funds the rent).
+///
+/// Ledgers created before KYC tiers, jurisdictions, spending limits and fee volume tracking
+/// no longer deserialize, which locks their credits. The old `reserved` bytes were never
+/// written, so every added field starts at its default: no tier beyond the legacy
+/// `kyc_verified` flag, no country, no limits and no recorded volume.
+///
+/// # Errors
+/// - `PgError::InvalidAccountLayout` if `player_ledger` isn't a program-owned `PlayerLedger`
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn migrate_player_ledger(ctx: Context<MigratePlayerLedger>) -> Result<()> {
+    grow_account::<PlayerLedger>(
+        &ctx.accounts.player_ledger.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: May predate the current `GlobalConfig` layout; owner, discriminator and
+    /// governance are checked by hand in `migrate_config`
+    #[account(mut, seeds = [CONFIG_SEED], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGameConfig<'info> {
+    /// CHECK: May predate the current `GameConfig` layout; owner and discriminator are
+    /// checked in `grow_account`
+    #[account(mut)]
+    pub game: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateListing<'info> {
+    /// CHECK: May predate the current `Listing` layout; owner and discriminator are checked
+    /// in `grow_account`
+    #[account(mut)]
+    pub listing: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePlayerLedger<'info> {
+    /// CHECK: May predate the current `PlayerLedger` layout; owner and discriminator are
+    /// checked in `grow_account`
+    #[account(mut)]
+    pub player_ledger: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
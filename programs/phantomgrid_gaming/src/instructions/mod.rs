@@ -9,6 +9,24 @@ pub mod job_marketplace;
 pub mod location_dispute;
 pub mod dispute_jury;
 pub mod hydra;
+pub mod sealed_bid;
+pub mod collection_offer;
+pub mod bundle;
+pub mod swap;
+pub mod order_book;
+pub mod rental;
+pub mod royalty_split;
+pub mod referral;
+pub mod keeper;
+pub mod sweep;
+pub mod lazy_mint;
+pub mod spending_limits;
+pub mod kyc;
+pub mod fee_tiers;
+pub mod pdox_fees;
+pub mod fee_router;
+pub mod game_roles;
+pub mod migrate;
 
 pub use initialize::*;
 pub use marketplace::*;
@@ -20,3 +38,21 @@ pub use lp_growth::*;
 pub use job_marketplace::*;
 pub use location_dispute::*;
 pub use dispute_jury::*;
+pub use sealed_bid::*;
+pub use collection_offer::*;
+pub use bundle::*;
+pub use swap::*;
+pub use order_book::*;
+pub use rental::*;
+pub use royalty_split::*;
+pub use referral::*;
+pub use keeper::*;
+pub use sweep::*;
+pub use lazy_mint::*;
+pub use spending_limits::*;
+pub use kyc::*;
+pub use fee_tiers::*;
+pub use pdox_fees::*;
+pub use fee_router::*;
+pub use game_roles::*;
+pub use migrate::*;
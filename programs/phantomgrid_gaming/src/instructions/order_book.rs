@@ -0,0 +1,930 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    enter_execution_game, exit_execution_game, instructions, metadata, BookOrder, GameConfig,
+    GameRole, GameRoles, GlobalConfig, OrderBook, OrderBookCreated, OrderCancelled, OrderPlaced,
+    OrderSide, OrdersMatched, PgError, PlayerLedger, RoyaltyPaid, RoyaltySplit, BPS_DENOM,
+    CONFIG_SEED, ESCROW_SEED, GAME_ROLES_SEED, GAME_SEED, LEDGER_SEED, MAX_BOOK_ORDERS,
+    MAX_BULK_QTY, ORDER_BOOK_SEED,
+};
+
+// ======================================================================
+// ORDER BOOK INSTRUCTIONS
+// ======================================================================
+
+/// Accounts passed per fill in `match_orders` `remaining_accounts`:
+/// `[buyer_ledger, seller_ledger, buyer_item_ata]`
+pub const MATCH_ACCOUNTS_PER_FILL: usize = 3;
+
+/// Create an order book (and its item escrow) for one item mint of a game.
+///
+/// New orders must be worth at least `min_notional` credits (`0` = no minimum). The item
+/// mint can't be the game currency or carry a Token-2022 transfer fee, which would make
+/// escrowed quantities differ from the booked ones.
+///
+/// The book's creator royalty is resolved once from the item's Metaplex metadata under the
+/// game's `RoyaltyEnforcement` policy (books carry no seller-chosen royalty) and charged on
+/// every fill.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not the game owner, config manager, admin or server
+/// - `PgError::InvalidOrderBookMint` if the item mint can't be traded on a book
+/// - `PgError::MetadataRequired` / `PgError::InvalidMetadata` / `PgError::RoyaltySplitMismatch`
+///   if the item's metadata royalties can't be resolved (see `resolve_listing_royalty`)
+/// - `PgError::FeeTooHigh` if royalty plus fees exceed 100%
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn create_order_book(ctx: Context<CreateOrderBook>, min_notional: u64) -> Result<()> {
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let caller = ctx.accounts.payer.key();
+    let allowed = instructions::game_roles::is_owner_or_role(
+        game,
+        ctx.accounts.roles.as_deref(),
+        &caller,
+        GameRole::ConfigManager,
+    ) || caller == cfg.admin
+        || caller == cfg.server_authority;
+    require!(allowed, PgError::Unauthorized);
+
+    let item_mint = ctx.accounts.item_mint.to_account_info();
+    require!(
+        item_mint.key() != game.currency_mint,
+        PgError::InvalidOrderBookMint
+    );
+    require!(
+        token_interface::get_mint_extension_data::<TransferFeeConfig>(&item_mint).is_err(),
+        PgError::InvalidOrderBookMint
+    );
+
+    let (royalty_recipient, royalty_bps) = metadata::resolve_listing_royalty(
+        game.royalty_enforcement,
+        ctx.accounts.item_metadata.as_deref(),
+        &item_mint.key(),
+        ctx.accounts.royalty_split.as_deref().map(|split| &**split),
+        Pubkey::default(),
+        0,
+    )?;
+    let total_fees = u64::from(royalty_bps)
+        .checked_add(u64::from(game.fee_bps))
+        .ok_or(PgError::Overflow)?
+        .checked_add(u64::from(cfg.protocol_fee_bps))
+        .ok_or(PgError::Overflow)?;
+    require!(total_fees <= BPS_DENOM, PgError::FeeTooHigh);
+    let royalty_split = match &ctx.accounts.royalty_split {
+        Some(split) => {
+            require!(split.game == game.key(), PgError::RoyaltySplitMismatch);
+            require!(royalty_bps > 0, PgError::InvalidRoyalty);
+            require!(
+                split.recipients[0] == royalty_recipient,
+                PgError::RoyaltySplitMismatch
+            );
+            split.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    let book = &mut ctx.accounts.order_book;
+    book.game = game.key();
+    book.item_mint = ctx.accounts.item_mint.key();
+    book.next_order_id = 0;
+    book.bids = [BookOrder::default(); MAX_BOOK_ORDERS];
+    book.asks = [BookOrder::default(); MAX_BOOK_ORDERS];
+    book.last_trade_price = 0;
+    book.total_volume = 0;
+    book.bump = ctx.bumps.order_book;
+    book.min_notional = min_notional;
+    book.royalty_recipient = royalty_recipient;
+    book.royalty_bps = royalty_bps;
+    book.royalty_split = royalty_split;
+
+    emit!(OrderBookCreated {
+        order_book: book.key(),
+        game: game.key(),
+        item_mint: book.item_mint,
+    });
+
+    Ok(())
+}
+
+/// Place a resting order.
+///
+/// Bids lock `price * quantity` credits; asks move items into the book escrow.
+/// Orders never match on placement - crossed orders are filled by `match_orders`.
+///
+/// When the side is full, the worst-priced resting order is evicted if the new order is
+/// strictly better priced, and its credits or items are returned. `remaining_accounts[0]`
+/// then holds the evicted owner's ledger (bid) or item token account (ask).
+///
+/// # Errors
+/// - `PgError::InvalidAmount` if price is 0 or quantity is 0 / exceeds `MAX_BULK_QTY`
+/// - `PgError::OrderTooSmall` if `price * quantity` is below the book's `min_notional`
+/// - `PgError::ListingsPaused` if new listings are paused
+/// - `PgError::InsufficientCredits` if a bid isn't covered by available credits
+/// - `PgError::SelfExcluded` if a bidder is in a self-exclusion cool-off
+/// - `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired` if a bidder's
+///   spending limits don't allow it
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded`
+///   if the order notional exceeds the owner's KYC tier limits (bids count toward daily volume)
+/// - `PgError::OrderBookFull` if the side is full and the order doesn't beat the worst one
+/// - `PgError::Unauthorized` if the evicted owner's account is missing or doesn't match
+/// - `PgError::SelfTrade` if the order would cross the owner's own resting order
+#[allow(clippy::too_many_lines)] // Placement plus eviction refund
+pub fn place_order<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PlaceOrder<'info>>,
+    side: OrderSide,
+    price: u64,
+    quantity: u64,
+) -> Result<()> {
+    require!(price > 0, PgError::InvalidAmount);
+    require!(
+        (1..=MAX_BULK_QTY).contains(&quantity),
+        PgError::InvalidAmount
+    );
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    require!(!cfg.paused_new, PgError::ListingsPaused);
+    require!(!game.paused_new, PgError::ListingsPaused);
+
+    let owner = ctx.accounts.owner.key();
+    let owner_ledger = &ctx.accounts.owner_ledger;
+    require!(owner_ledger.authority == owner, PgError::Unauthorized);
+    let now = Clock::get()?.unix_timestamp;
+
+    let notional = price.checked_mul(quantity).ok_or(PgError::Overflow)?;
+    owner_ledger.check_kyc_trade(cfg, game.kyc_required, notional, now)?;
+    require!(
+        notional >= ctx.accounts.order_book.min_notional,
+        PgError::OrderTooSmall
+    );
+    if side == OrderSide::Bid {
+        require!(
+            owner_ledger.available >= notional,
+            PgError::InsufficientCredits
+        );
+        require!(!owner_ledger.limits.is_excluded(now), PgError::SelfExcluded);
+    }
+
+    let book = &ctx.accounts.order_book;
+    require!(!book.crosses_own(side, &owner, price), PgError::SelfTrade);
+    let free_slot = book.side(side).iter().position(|o| o.quantity == 0);
+    let (slot, evicted) = match free_slot {
+        Some(slot) => (slot, None),
+        None => {
+            // Full side: only a strictly better price displaces the worst resting order
+            let slot = book.worst(side).ok_or(PgError::OrderBookFull)?;
+            let worst = book.side(side)[slot];
+            let better = match side {
+                OrderSide::Bid => price > worst.price,
+                OrderSide::Ask => price < worst.price,
+            };
+            require!(better, PgError::OrderBookFull);
+            (slot, Some(worst))
+        }
+    };
+    let evicted_account = match evicted {
+        Some(_) => Some(
+            ctx.remaining_accounts
+                .first()
+                .ok_or(PgError::Unauthorized)?,
+        ),
+        None => None,
+    };
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let book = &mut ctx.accounts.order_book;
+    let owner_ledger = &mut ctx.accounts.owner_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        if let (Some(order), Some(account)) = (evicted, evicted_account) {
+            match side {
+                OrderSide::Bid => {
+                    let unlock = order
+                        .price
+                        .checked_mul(order.quantity)
+                        .ok_or(PgError::Overflow)?;
+                    if order.owner == owner {
+                        owner_ledger.locked = owner_ledger
+                            .locked
+                            .checked_sub(unlock)
+                            .ok_or(PgError::Overflow)?;
+                        owner_ledger.available = owner_ledger
+                            .available
+                            .checked_add(unlock)
+                            .ok_or(PgError::Overflow)?;
+                    } else {
+                        let mut ledger = Account::<PlayerLedger>::try_from(account)?;
+                        require!(
+                            ledger.game == game.key() && ledger.authority == order.owner,
+                            PgError::Unauthorized
+                        );
+                        ledger.locked =
+                            ledger.locked.checked_sub(unlock).ok_or(PgError::Overflow)?;
+                        ledger.available = ledger
+                            .available
+                            .checked_add(unlock)
+                            .ok_or(PgError::Overflow)?;
+                        ledger.exit(ctx.program_id)?;
+                    }
+                }
+                OrderSide::Ask => {
+                    // Return items: book escrow -> evicted owner (game PDA signs)
+                    let owner_item_ata = InterfaceAccount::<TokenAccount>::try_from(account)?;
+                    require!(
+                        owner_item_ata.mint == book.item_mint,
+                        PgError::ItemMintMismatch
+                    );
+                    require!(owner_item_ata.owner == order.owner, PgError::Unauthorized);
+                    let game_id = game.game_id;
+                    let game_bump = game.bump;
+                    let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+                    let signer = &[seeds];
+                    let cpi_accounts = token_interface::TransferChecked {
+                        from: ctx.accounts.book_escrow.to_account_info(),
+                        mint: ctx.accounts.item_mint.to_account_info(),
+                        to: owner_item_ata.to_account_info(),
+                        authority: game.to_account_info(),
+                    };
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        cpi_accounts,
+                        signer,
+                    );
+                    token_interface::transfer_checked(
+                        cpi_ctx,
+                        order.quantity,
+                        ctx.accounts.item_mint.decimals,
+                    )?;
+                }
+            }
+
+            emit!(OrderCancelled {
+                order_book: book.key(),
+                game: game.key(),
+                owner: order.owner,
+                order_id: order.order_id,
+                side,
+                quantity_returned: order.quantity,
+            });
+        }
+
+        match side {
+            OrderSide::Bid => {
+                // Spending limits: the locked amount counts as spent (fills only refund)
+                let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+                owner_ledger.limits.record_spend(notional, now, approver)?;
+                // KYC tier limits: the locked amount counts toward daily volume
+                owner_ledger.record_kyc_trade(cfg, game.kyc_required, notional, now)?;
+
+                owner_ledger.available = owner_ledger
+                    .available
+                    .checked_sub(notional)
+                    .ok_or(PgError::Overflow)?;
+                owner_ledger.locked = owner_ledger
+                    .locked
+                    .checked_add(notional)
+                    .ok_or(PgError::Overflow)?;
+            }
+            OrderSide::Ask => {
+                // Escrow items: owner -> book escrow
+                let cpi_accounts = token_interface::TransferChecked {
+                    from: ctx.accounts.owner_item_ata.to_account_info(),
+                    mint: ctx.accounts.item_mint.to_account_info(),
+                    to: ctx.accounts.book_escrow.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                };
+                let cpi_ctx =
+                    CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    quantity,
+                    ctx.accounts.item_mint.decimals,
+                )?;
+            }
+        }
+
+        let order_id = book.next_order_id;
+        book.next_order_id = order_id.checked_add(1).ok_or(PgError::Overflow)?;
+        book.side_mut(side)[slot] = BookOrder {
+            owner,
+            order_id,
+            price,
+            quantity,
+            placed_at: now,
+        };
+
+        emit!(OrderPlaced {
+            order_book: book.key(),
+            game: game.key(),
+            owner,
+            order_id,
+            side,
+            price,
+            quantity,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Cancel a resting order and release its locked credits or escrowed items.
+///
+/// # Errors
+/// - `PgError::OrderNotFound` if no live order with `order_id` is on that side
+/// - `PgError::Unauthorized` if caller does not own the order
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn cancel_order(ctx: Context<CancelOrder>, side: OrderSide, order_id: u64) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let book = &ctx.accounts.order_book;
+    let slot = book
+        .side(side)
+        .iter()
+        .position(|o| o.quantity > 0 && o.order_id == order_id)
+        .ok_or(PgError::OrderNotFound)?;
+    let order = book.side(side)[slot];
+    let owner = ctx.accounts.owner.key();
+    require!(order.owner == owner, PgError::Unauthorized);
+    require!(
+        ctx.accounts.owner_ledger.authority == owner,
+        PgError::Unauthorized
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let book = &mut ctx.accounts.order_book;
+    let owner_ledger = &mut ctx.accounts.owner_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        match side {
+            OrderSide::Bid => {
+                let unlock = order
+                    .price
+                    .checked_mul(order.quantity)
+                    .ok_or(PgError::Overflow)?;
+                owner_ledger.locked = owner_ledger
+                    .locked
+                    .checked_sub(unlock)
+                    .ok_or(PgError::Overflow)?;
+                owner_ledger.available = owner_ledger
+                    .available
+                    .checked_add(unlock)
+                    .ok_or(PgError::Overflow)?;
+            }
+            OrderSide::Ask => {
+                // Return items: book escrow -> owner (game PDA signs)
+                let game_id = game.game_id;
+                let game_bump = game.bump;
+                let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+                let signer = &[seeds];
+                let cpi_accounts = token_interface::TransferChecked {
+                    from: ctx.accounts.book_escrow.to_account_info(),
+                    mint: ctx.accounts.item_mint.to_account_info(),
+                    to: ctx.accounts.owner_item_ata.to_account_info(),
+                    authority: game.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer,
+                );
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    order.quantity,
+                    ctx.accounts.item_mint.decimals,
+                )?;
+            }
+        }
+
+        book.side_mut(side)[slot] = BookOrder::default();
+
+        emit!(OrderCancelled {
+            order_book: book.key(),
+            game: game.key(),
+            owner,
+            order_id,
+            side,
+            quantity_returned: order.quantity,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Permissionless crank: fill crossed orders by price-time priority.
+///
+/// Repeatedly takes the best bid and best ask; while they cross, fills the smaller side at the
+/// price of the earlier (resting) order. Buyers are refunded the difference to their limit.
+/// The game fee, the flat `protocol_fee_bps` (no maker/taker tiers; fills don't count toward
+/// tier volume) and the book's creator royalty are deducted from the seller's proceeds;
+/// royalties are credited once after the last fill. `remaining_accounts` holds
+/// the other royalty split recipients' ledgers (when the book has a split), then
+/// `[buyer_ledger, seller_ledger, buyer_item_ata]` per fill, in fill order; matching stops at
+/// `max_matches` or when the supplied accounts run out.
+///
+/// # Errors
+/// - `PgError::OrderBookNotCrossed` if nothing was matched
+/// - `PgError::RoyaltySplitMismatch` if the book's royalty split or its ledgers are missing
+/// - `PgError::Unauthorized` if a supplied ledger or token account doesn't belong to the order owner
+/// - `PgError::FeeTooHigh` if fees exceed 50% of a fill
+/// - `PgError::InvalidSellerAmount` if a seller would receive nothing after fees
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+#[allow(clippy::too_many_lines)] // Matching loop with inline settlement
+pub fn match_orders<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MatchOrders<'info>>,
+    max_matches: u8,
+) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+    require!(max_matches > 0, PgError::InvalidAmount);
+
+    // Multi-recipient royalties: the split must be the one recorded on the book
+    let book = &ctx.accounts.order_book;
+    let mut split_ledgers = 0;
+    if book.royalty_split != Pubkey::default() {
+        let split = ctx
+            .accounts
+            .royalty_split
+            .as_ref()
+            .ok_or(PgError::RoyaltySplitMismatch)?;
+        require!(
+            split.key() == book.royalty_split,
+            PgError::RoyaltySplitMismatch
+        );
+        split_ledgers = usize::from(split.recipient_count).saturating_sub(1);
+    }
+    require!(
+        ctx.remaining_accounts.len() >= split_ledgers,
+        PgError::RoyaltySplitMismatch
+    );
+    let (split_accounts, fill_accounts) = ctx.remaining_accounts.split_at(split_ledgers);
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let book = &mut ctx.accounts.order_book;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let game_key = game.key();
+        let book_key = book.key();
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+
+        let mut fills: usize = 0;
+        let mut royalty_total = 0u64;
+        while fills < usize::from(max_matches) {
+            let (Some(bi), Some(ai)) = (book.best(OrderSide::Bid), book.best(OrderSide::Ask))
+            else {
+                break;
+            };
+            let bid = book.bids[bi];
+            let ask = book.asks[ai];
+            if bid.price < ask.price {
+                break;
+            }
+            let base = fills
+                .checked_mul(MATCH_ACCOUNTS_PER_FILL)
+                .ok_or(PgError::Overflow)?;
+            if fill_accounts.len() < base + MATCH_ACCOUNTS_PER_FILL {
+                break;
+            }
+
+            // Price-time priority: the earlier order sets the price
+            let fill_qty = bid.quantity.min(ask.quantity);
+            let exec_price = if bid.order_id < ask.order_id {
+                bid.price
+            } else {
+                ask.price
+            };
+            let notional = exec_price.checked_mul(fill_qty).ok_or(PgError::Overflow)?;
+            let bid_locked = bid.price.checked_mul(fill_qty).ok_or(PgError::Overflow)?;
+            let buyer_refund = bid_locked.checked_sub(notional).ok_or(PgError::Overflow)?;
+
+            // Fees: flat protocol_fee_bps, not buy_fixed's maker/taker tiers; fills don't count
+            // toward fee_volume
+            let game_fee = notional
+                .checked_mul(u64::from(game.fee_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+            let protocol_fee = notional
+                .checked_mul(u64::from(cfg.protocol_fee_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+            let royalty_amount = notional
+                .checked_mul(u64::from(book.royalty_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+            let total_fees = game_fee
+                .checked_add(protocol_fee)
+                .ok_or(PgError::Overflow)?
+                .checked_add(royalty_amount)
+                .ok_or(PgError::Overflow)?;
+            let max_allowed_fees = notional.checked_div(2).ok_or(PgError::Overflow)?; // 50% max
+            require!(total_fees <= max_allowed_fees, PgError::FeeTooHigh);
+            let seller_amount = notional.checked_sub(total_fees).ok_or(PgError::Overflow)?;
+            require!(seller_amount > 0, PgError::InvalidSellerAmount);
+
+            // Buyer: locked bid -> payment, excess back to available
+            let mut buyer_ledger = Account::<PlayerLedger>::try_from(&fill_accounts[base])?;
+            require!(
+                buyer_ledger.game == game_key && buyer_ledger.authority == bid.owner,
+                PgError::Unauthorized
+            );
+            buyer_ledger.locked = buyer_ledger
+                .locked
+                .checked_sub(bid_locked)
+                .ok_or(PgError::Overflow)?;
+            buyer_ledger.available = buyer_ledger
+                .available
+                .checked_add(buyer_refund)
+                .ok_or(PgError::Overflow)?;
+            buyer_ledger.exit(ctx.program_id)?;
+
+            // Seller: proceeds after fees (SelfTrade check at placement keeps ledgers distinct)
+            let mut seller_ledger = Account::<PlayerLedger>::try_from(&fill_accounts[base + 1])?;
+            require!(
+                seller_ledger.game == game_key && seller_ledger.authority == ask.owner,
+                PgError::Unauthorized
+            );
+            seller_ledger.available = seller_ledger
+                .available
+                .checked_add(seller_amount)
+                .ok_or(PgError::Overflow)?;
+            seller_ledger.exit(ctx.program_id)?;
+
+            // Deliver items: book escrow -> buyer (game PDA signs)
+            let buyer_item_ata =
+                InterfaceAccount::<TokenAccount>::try_from(&fill_accounts[base + 2])?;
+            require!(
+                buyer_item_ata.mint == book.item_mint,
+                PgError::ItemMintMismatch
+            );
+            require!(buyer_item_ata.owner == bid.owner, PgError::Unauthorized);
+            let cpi_accounts = token_interface::TransferChecked {
+                from: ctx.accounts.book_escrow.to_account_info(),
+                mint: ctx.accounts.item_mint.to_account_info(),
+                to: buyer_item_ata.to_account_info(),
+                authority: game.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, fill_qty, ctx.accounts.item_mint.decimals)?;
+
+            // CRITICAL: Track protocol and game fees per-game
+            game.protocol_fees_accumulated = game
+                .protocol_fees_accumulated
+                .checked_add(protocol_fee)
+                .ok_or(PgError::Overflow)?;
+            game.accumulated_game_fees = game
+                .accumulated_game_fees
+                .checked_add(game_fee)
+                .ok_or(PgError::Overflow)?;
+            royalty_total = royalty_total
+                .checked_add(royalty_amount)
+                .ok_or(PgError::Overflow)?;
+
+            // Update resting orders (fully filled slots are freed)
+            book.bids[bi].quantity = bid
+                .quantity
+                .checked_sub(fill_qty)
+                .ok_or(PgError::Overflow)?;
+            if book.bids[bi].quantity == 0 {
+                book.bids[bi] = BookOrder::default();
+            }
+            book.asks[ai].quantity = ask
+                .quantity
+                .checked_sub(fill_qty)
+                .ok_or(PgError::Overflow)?;
+            if book.asks[ai].quantity == 0 {
+                book.asks[ai] = BookOrder::default();
+            }
+            book.last_trade_price = exec_price;
+            book.total_volume = book
+                .total_volume
+                .checked_add(fill_qty)
+                .ok_or(PgError::Overflow)?;
+
+            emit!(OrdersMatched {
+                order_book: book_key,
+                game: game_key,
+                bid_order_id: bid.order_id,
+                ask_order_id: ask.order_id,
+                buyer: bid.owner,
+                seller: ask.owner,
+                price: exec_price,
+                quantity: fill_qty,
+                protocol_fee,
+                game_fee,
+                royalty_amount,
+            });
+
+            fills += 1;
+        }
+
+        require!(fills > 0, PgError::OrderBookNotCrossed);
+
+        // CRITICAL: Only process royalties when royalty_total > 0 (prevents griefing via init_if_needed)
+        if royalty_total > 0 {
+            let royalty_ledger = &mut ctx.accounts.royalty_recipient_ledger;
+            if royalty_ledger.authority == Pubkey::default() {
+                // Initialize new ledger
+                royalty_ledger.game = game_key;
+                royalty_ledger.authority = book.royalty_recipient;
+                royalty_ledger.available = 0;
+                royalty_ledger.locked = 0;
+                royalty_ledger.kyc_verified = false;
+                royalty_ledger.kyc_provider = Pubkey::default();
+                royalty_ledger.kyc_verified_at = 0;
+                royalty_ledger.kyc_proof_hash = [0u8; 32];
+            } else {
+                // The recipient may have traded in a fill above; pick up those writes
+                royalty_ledger.reload()?;
+            }
+            require!(
+                royalty_ledger.authority == book.royalty_recipient,
+                PgError::Unauthorized
+            );
+
+            // Split royalties: recipient 0 takes its share plus rounding dust
+            let royalty_shares = match &ctx.accounts.royalty_split {
+                Some(split) if book.royalty_split != Pubkey::default() => {
+                    Some(split.shares(royalty_total)?)
+                }
+                _ => None,
+            };
+            let primary_royalty = royalty_shares.map_or(royalty_total, |shares| shares[0]);
+            royalty_ledger.available = royalty_ledger
+                .available
+                .checked_add(primary_royalty)
+                .ok_or(PgError::Overflow)?;
+
+            emit!(RoyaltyPaid {
+                listing: book_key,
+                game: game_key,
+                recipient: book.royalty_recipient,
+                amount: primary_royalty,
+            });
+
+            if let (Some(split), Some(shares)) = (&ctx.accounts.royalty_split, &royalty_shares) {
+                instructions::royalty_split::credit_royalty_split(
+                    split,
+                    shares,
+                    book_key,
+                    &mut [],
+                    split_accounts,
+                    ctx.program_id,
+                )?;
+            }
+        }
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct CreateOrderBook<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [ORDER_BOOK_SEED, game.key().as_ref(), item_mint.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<OrderBook>()
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [ESCROW_SEED, order_book.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub book_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Multi-recipient royalty split, required when the item's metadata has several creators
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    /// CHECK: Metaplex metadata PDA of `item_mint`; address, owner and layout verified in handler
+    pub item_metadata: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Game roles (required when the payer acts as a `ConfigManager`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_BOOK_SEED, game.key().as_ref(), order_book.item_mint.as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub owner_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(address = order_book.item_mint)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    /// Owner's item ATA (source for asks; must exist for bids so fills can be delivered)
+    #[account(
+        mut,
+        associated_token::mint = item_mint,
+        associated_token::authority = owner
+    )]
+    pub owner_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, order_book.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub book_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Guardian co-signature for bids above the owner's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_BOOK_SEED, game.key().as_ref(), order_book.item_mint.as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub owner_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(address = order_book.item_mint)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = item_mint,
+        associated_token::authority = owner
+    )]
+    pub owner_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, order_book.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub book_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [ORDER_BOOK_SEED, game.key().as_ref(), order_book.item_mint.as_ref()],
+        bump = order_book.bump
+    )]
+    pub order_book: Box<Account<'info, OrderBook>>,
+
+    #[account(address = order_book.item_mint)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, order_book.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub book_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Royalty recipient ledger (only credited when the book carries a royalty)
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        seeds = [LEDGER_SEED, game.key().as_ref(), order_book.royalty_recipient.as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PlayerLedger>()
+    )]
+    pub royalty_recipient_ledger: Box<Account<'info, PlayerLedger>>,
+
+    /// Required when `order_book.royalty_split` is set; other recipients' ledgers go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    /// Permissionless crank caller
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,561 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_interface::{self, Mint, TokenAccount};
+
+use crate::{
+    GlobalConfig, PdoxFeeConfigUpdated, PdoxFeeSink, PdoxPriceSource, PdoxPriceSourceUpdated,
+    PgError, ProtocolFeePaidInPdox, BPS_DENOM, CONFIG_SEED, DAO_TREASURY_SEED, LP_GROWTH_SEED,
+    MAX_PDOX_FEE_DISCOUNT_BPS, MAX_PDOX_PRICE_DEVIATION_BPS, PDOX_PRICE_SCALE, PDOX_PRICE_SEED,
+};
+
+/// Accounts used to pay a trade's protocol fee in PDOX instead of the game currency
+pub struct PdoxFeeAccounts<'a, 'info> {
+    pub pdox_mint: &'a InterfaceAccount<'info, Mint>,
+    /// Payer's PDOX token account (debited or burned from)
+    pub payer_pdox_account: &'a InterfaceAccount<'info, TokenAccount>,
+    /// Price source of the game currency (`[PDOX_PRICE_SEED, currency_mint]`)
+    pub price_source: &'a PdoxPriceSource,
+    /// Pool reserves named by `price_source`
+    pub pdox_reserve: &'a InterfaceAccount<'info, TokenAccount>,
+    pub quote_reserve: &'a InterfaceAccount<'info, TokenAccount>,
+    /// `GlobalConfig.pdox_fee_sink_account` (not needed when the sink is `Burn`)
+    pub sink_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+}
+
+// ======================================================================
+// PDOX FEE PAYMENT INSTRUCTIONS
+// ======================================================================
+
+/// Set where PDOX-paid protocol fees go and the discount for paying in PDOX.
+///
+/// `sink_account` must be a PDOX token account owned by the DAO treasury vault
+/// (`DaoTreasury`) or the LP growth manager (`LpGrowth`); it is ignored for `Burn`/`Disabled`.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not governance
+/// - `PgError::InvalidAmount` if `discount_bps` exceeds `MAX_PDOX_FEE_DISCOUNT_BPS`
+/// - `PgError::InvalidPdoxFeeSink` if `sink_account` is missing or not the sink's PDOX account
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn set_pdox_fee_config(
+    ctx: Context<SetPdoxFeeConfig>,
+    sink: PdoxFeeSink,
+    discount_bps: u16,
+) -> Result<()> {
+    require!(
+        discount_bps <= MAX_PDOX_FEE_DISCOUNT_BPS,
+        PgError::InvalidAmount
+    );
+
+    let cfg = &mut ctx.accounts.config;
+    let sink_owner = match sink {
+        PdoxFeeSink::DaoTreasury => Some(
+            Pubkey::find_program_address(
+                &[DAO_TREASURY_SEED, cfg.governance.as_ref()],
+                ctx.program_id,
+            )
+            .0,
+        ),
+        PdoxFeeSink::LpGrowth => Some(
+            Pubkey::find_program_address(&[LP_GROWTH_SEED, cfg.pdox_mint.as_ref()], ctx.program_id)
+                .0,
+        ),
+        PdoxFeeSink::Disabled | PdoxFeeSink::Burn => None,
+    };
+    let sink_account = match sink_owner {
+        Some(owner) => {
+            let account = ctx
+                .accounts
+                .sink_account
+                .as_ref()
+                .ok_or(PgError::InvalidPdoxFeeSink)?;
+            require!(account.owner == owner, PgError::InvalidPdoxFeeSink);
+            require!(account.mint == cfg.pdox_mint, PgError::InvalidPdoxFeeSink);
+            account.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    cfg.pdox_fee_sink = sink;
+    cfg.pdox_fee_sink_account = sink_account;
+    cfg.pdox_fee_discount_bps = discount_bps;
+
+    emit!(PdoxFeeConfigUpdated {
+        sink,
+        sink_account,
+        discount_bps,
+    });
+
+    Ok(())
+}
+
+/// Set the PDOX price source for a game currency: a PDOX/currency pool given by its two
+/// reserve token accounts, priced at the reserve ratio.
+///
+/// `min_quote_liquidity` is the smallest currency reserve the price is trusted at. The spot
+/// price can still be moved within one transaction, so fees are only quoted while it stays
+/// within `max_deviation_bps` of `reference_price` (PDOX base units per `PDOX_PRICE_SCALE`
+/// currency units); governance re-sets the reference as the market moves.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if caller is not governance
+/// - `PgError::InvalidPdoxPriceSource` if `pdox_reserve` doesn't hold PDOX
+/// - `PgError::InvalidAmount` if `min_quote_liquidity` or `reference_price` is zero, or
+///   `max_deviation_bps` is zero or exceeds `MAX_PDOX_PRICE_DEVIATION_BPS`
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn set_pdox_price_source(
+    ctx: Context<SetPdoxPriceSource>,
+    min_quote_liquidity: u64,
+    reference_price: u64,
+    max_deviation_bps: u16,
+) -> Result<()> {
+    require!(min_quote_liquidity > 0, PgError::InvalidAmount);
+    require!(reference_price > 0, PgError::InvalidAmount);
+    require!(
+        (1..=MAX_PDOX_PRICE_DEVIATION_BPS).contains(&max_deviation_bps),
+        PgError::InvalidAmount
+    );
+
+    let source = &mut ctx.accounts.price_source;
+    source.quote_mint = ctx.accounts.quote_mint.key();
+    source.pdox_reserve = ctx.accounts.pdox_reserve.key();
+    source.quote_reserve = ctx.accounts.quote_reserve.key();
+    source.min_quote_liquidity = min_quote_liquidity;
+    source.reference_price = reference_price;
+    source.max_deviation_bps = max_deviation_bps;
+    source.updated_at = Clock::get()?.unix_timestamp;
+    source.bump = ctx.bumps.price_source;
+
+    emit!(PdoxPriceSourceUpdated {
+        price_source: source.key(),
+        quote_mint: source.quote_mint,
+        pdox_reserve: source.pdox_reserve,
+        quote_reserve: source.quote_reserve,
+        min_quote_liquidity,
+        reference_price,
+        max_deviation_bps,
+    });
+
+    Ok(())
+}
+
+/// PDOX owed for a `fee` in `currency_mint`: the governance discount applies first, then the
+/// pool spot price (rounded up, in the protocol's favour).
+///
+/// # Errors
+/// - `PgError::InvalidPdoxPriceSource` if the price source or reserves don't match `currency_mint`,
+///   or the source has no reference price
+/// - `PgError::PdoxLiquidityTooLow` if the pool is below `min_quote_liquidity` or empty
+/// - `PgError::PdoxPriceDeviation` if the spot price is outside `max_deviation_bps` of the
+///   reference price (e.g. a swap in the same transaction moved the pool)
+/// - `PgError::Overflow` on arithmetic overflow
+pub fn quote_pdox_fee(
+    accounts: &PdoxFeeAccounts,
+    cfg: &GlobalConfig,
+    currency_mint: Pubkey,
+    fee: u64,
+) -> Result<u64> {
+    let source = accounts.price_source;
+    require!(
+        source.quote_mint == currency_mint,
+        PgError::InvalidPdoxPriceSource
+    );
+    require!(
+        accounts.pdox_reserve.key() == source.pdox_reserve,
+        PgError::InvalidPdoxPriceSource
+    );
+    require!(
+        accounts.quote_reserve.key() == source.quote_reserve,
+        PgError::InvalidPdoxPriceSource
+    );
+
+    let pdox_liquidity = accounts.pdox_reserve.amount;
+    let quote_liquidity = accounts.quote_reserve.amount;
+    require!(
+        pdox_liquidity > 0 && quote_liquidity >= source.min_quote_liquidity,
+        PgError::PdoxLiquidityTooLow
+    );
+
+    // Cap the spot price's deviation from the governance reference, so pushing the pool
+    // within the transaction can't drive the PDOX owed toward zero
+    require!(source.reference_price > 0, PgError::InvalidPdoxPriceSource);
+    let spot_price = u128::from(pdox_liquidity)
+        .checked_mul(u128::from(PDOX_PRICE_SCALE))
+        .ok_or(PgError::Overflow)?
+        / u128::from(quote_liquidity);
+    let reference_price = u128::from(source.reference_price);
+    let band = reference_price
+        .checked_mul(u128::from(source.max_deviation_bps))
+        .ok_or(PgError::Overflow)?
+        / u128::from(BPS_DENOM);
+    require!(
+        spot_price.abs_diff(reference_price) <= band,
+        PgError::PdoxPriceDeviation
+    );
+
+    let discounted_fee = u128::from(fee)
+        .checked_mul(u128::from(
+            BPS_DENOM
+                .checked_sub(u64::from(cfg.pdox_fee_discount_bps))
+                .ok_or(PgError::Overflow)?,
+        ))
+        .ok_or(PgError::Overflow)?
+        .div_ceil(u128::from(BPS_DENOM));
+    let pdox_amount = discounted_fee
+        .checked_mul(u128::from(pdox_liquidity))
+        .ok_or(PgError::Overflow)?
+        .div_ceil(u128::from(quote_liquidity));
+    Ok(u64::try_from(pdox_amount).map_err(|_| PgError::Overflow)?)
+}
+
+/// Amount to send so the recipient gets `amount` after the mint's Token-2022 transfer fee
+fn gross_up_transfer_fee(pdox_mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let transfer_fee = match token_interface::get_mint_extension_data::<TransferFeeConfig>(
+        &pdox_mint.to_account_info(),
+    ) {
+        Ok(fee_config) => fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(PgError::Overflow)?,
+        Err(_) => 0, // No transfer fee extension
+    };
+    Ok(amount.checked_add(transfer_fee).ok_or(PgError::Overflow)?)
+}
+
+/// Pay a trade's protocol fee (`fee`, in the game currency) in PDOX and route it to the
+/// configured sink. Returns the PDOX amount the sink received (or that was burned).
+///
+/// The payer also covers the PDOX transfer fee, so the sink gets the full quoted amount.
+///
+/// # Errors
+/// - `PgError::PdoxFeesDisabled` if governance hasn't set a sink
+/// - `PgError::InvalidPdoxPayment` if the mint, payer account or token program don't match
+/// - `PgError::InvalidPdoxFeeSink` if the sink account is missing or not the configured one
+/// - Any `quote_pdox_fee` error
+pub fn pay_protocol_fee_in_pdox(
+    accounts: &PdoxFeeAccounts,
+    cfg: &GlobalConfig,
+    game: Pubkey,
+    currency_mint: Pubkey,
+    fee: u64,
+) -> Result<u64> {
+    require!(
+        cfg.pdox_fee_sink != PdoxFeeSink::Disabled,
+        PgError::PdoxFeesDisabled
+    );
+    require!(
+        accounts.pdox_mint.key() == cfg.pdox_mint,
+        PgError::InvalidPdoxPayment
+    );
+    require!(
+        accounts.payer_pdox_account.mint == cfg.pdox_mint,
+        PgError::InvalidPdoxPayment
+    );
+    require!(
+        accounts.payer_pdox_account.owner == accounts.payer.key(),
+        PgError::InvalidPdoxPayment
+    );
+    require!(
+        accounts.token_program.key() == *accounts.pdox_mint.to_account_info().owner,
+        PgError::InvalidPdoxPayment
+    );
+
+    let pdox_amount = quote_pdox_fee(accounts, cfg, currency_mint, fee)?;
+    if pdox_amount > 0 {
+        if cfg.pdox_fee_sink == PdoxFeeSink::Burn {
+            token_interface::burn(
+                CpiContext::new(
+                    accounts.token_program.clone(),
+                    token_interface::Burn {
+                        mint: accounts.pdox_mint.to_account_info(),
+                        from: accounts.payer_pdox_account.to_account_info(),
+                        authority: accounts.payer.clone(),
+                    },
+                ),
+                pdox_amount,
+            )?;
+        } else {
+            let sink = accounts.sink_account.ok_or(PgError::InvalidPdoxFeeSink)?;
+            require!(
+                sink.key() == cfg.pdox_fee_sink_account,
+                PgError::InvalidPdoxFeeSink
+            );
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    accounts.token_program.clone(),
+                    token_interface::TransferChecked {
+                        from: accounts.payer_pdox_account.to_account_info(),
+                        mint: accounts.pdox_mint.to_account_info(),
+                        to: sink.to_account_info(),
+                        authority: accounts.payer.clone(),
+                    },
+                ),
+                gross_up_transfer_fee(accounts.pdox_mint, pdox_amount)?,
+                accounts.pdox_mint.decimals,
+            )?;
+        }
+    }
+
+    emit!(ProtocolFeePaidInPdox {
+        game,
+        payer: accounts.payer.key(),
+        fee,
+        discount_bps: cfg.pdox_fee_discount_bps,
+        pdox_amount,
+        sink: cfg.pdox_fee_sink,
+    });
+
+    Ok(pdox_amount)
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct SetPdoxFeeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = governance @ PgError::Unauthorized
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+    pub governance: Signer<'info>,
+
+    /// PDOX token account of the DAO treasury vault or LP growth manager
+    pub sink_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPdoxPriceSource<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump,
+        has_one = governance @ PgError::Unauthorized
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        constraint = pdox_reserve.mint == config.pdox_mint @ PgError::InvalidPdoxPriceSource
+    )]
+    pub pdox_reserve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        constraint = quote_reserve.mint == quote_mint.key() @ PgError::InvalidPdoxPriceSource
+    )]
+    pub quote_reserve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = governance,
+        seeds = [PDOX_PRICE_SEED, quote_mint.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PdoxPriceSource>()
+    )]
+    pub price_source: Box<Account<'info, PdoxPriceSource>>,
+
+    #[account(mut)]
+    pub governance: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::zeroed;
+    use anchor_spl::token_2022::spl_token_2022::state::{
+        Account as TokenAccountState, AccountState, Mint as MintState,
+    };
+    use solana_program::program_pack::Pack;
+
+    const PDOX_RESERVE: u64 = 2_000_000_000;
+    const QUOTE_RESERVE: u64 = 1_000_000;
+
+    fn token_account_data(mint: Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; TokenAccountState::LEN];
+        TokenAccountState {
+            mint,
+            owner: Pubkey::new_unique(),
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccountState::default()
+        }
+        .pack_into_slice(&mut data);
+        data
+    }
+
+    /// Price source for a pool holding `PDOX_RESERVE` PDOX against `QUOTE_RESERVE` currency
+    fn price_source(quote_mint: Pubkey) -> PdoxPriceSource {
+        let mut source = zeroed::<PdoxPriceSource>();
+        source.quote_mint = quote_mint;
+        source.pdox_reserve = Pubkey::new_unique();
+        source.quote_reserve = Pubkey::new_unique();
+        source.min_quote_liquidity = QUOTE_RESERVE;
+        source.reference_price = 2_000 * PDOX_PRICE_SCALE;
+        source.max_deviation_bps = 500;
+        source
+    }
+
+    /// `quote_pdox_fee` against a pool holding `pdox_liquidity` / `quote_liquidity`
+    fn quote(
+        source: &PdoxPriceSource,
+        cfg: &GlobalConfig,
+        currency_mint: Pubkey,
+        pdox_liquidity: u64,
+        fee: u64,
+    ) -> Result<u64> {
+        let token_program = anchor_spl::token::ID;
+        let pdox_mint = Pubkey::new_unique();
+        let (mut mint_lamports, mut pdox_lamports, mut quote_lamports) = (0, 0, 0);
+        let (mut payer_lamports, mut program_lamports) = (0, 0);
+
+        let mut mint_data = vec![0u8; MintState::LEN];
+        MintState {
+            decimals: 9,
+            is_initialized: true,
+            ..MintState::default()
+        }
+        .pack_into_slice(&mut mint_data);
+        let mut pdox_data = token_account_data(pdox_mint, pdox_liquidity);
+        let mut quote_data = token_account_data(source.quote_mint, QUOTE_RESERVE);
+        let (mut payer_data, mut program_data) = (Vec::new(), Vec::new());
+        let payer = Pubkey::new_unique();
+        let system = Pubkey::default();
+
+        let mint_info = AccountInfo::new(
+            &pdox_mint,
+            false,
+            false,
+            &mut mint_lamports,
+            &mut mint_data,
+            &token_program,
+            false,
+            0,
+        );
+        let pdox_info = AccountInfo::new(
+            &source.pdox_reserve,
+            false,
+            false,
+            &mut pdox_lamports,
+            &mut pdox_data,
+            &token_program,
+            false,
+            0,
+        );
+        let quote_info = AccountInfo::new(
+            &source.quote_reserve,
+            false,
+            false,
+            &mut quote_lamports,
+            &mut quote_data,
+            &token_program,
+            false,
+            0,
+        );
+        let payer_info = AccountInfo::new(
+            &payer,
+            true,
+            false,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system,
+            false,
+            0,
+        );
+        let program_info = AccountInfo::new(
+            &token_program,
+            false,
+            false,
+            &mut program_lamports,
+            &mut program_data,
+            &system,
+            true,
+            0,
+        );
+
+        let pdox_mint = InterfaceAccount::<Mint>::try_from(&mint_info)?;
+        let pdox_reserve = InterfaceAccount::<TokenAccount>::try_from(&pdox_info)?;
+        let quote_reserve = InterfaceAccount::<TokenAccount>::try_from(&quote_info)?;
+        let accounts = PdoxFeeAccounts {
+            pdox_mint: &pdox_mint,
+            payer_pdox_account: &pdox_reserve,
+            price_source: source,
+            pdox_reserve: &pdox_reserve,
+            quote_reserve: &quote_reserve,
+            sink_account: None,
+            payer: payer_info,
+            token_program: program_info,
+        };
+        quote_pdox_fee(&accounts, cfg, currency_mint, fee)
+    }
+
+    #[test]
+    fn quote_pdox_fee_prices_the_discounted_fee_at_the_pool_rate() {
+        let currency = Pubkey::new_unique();
+        let source = price_source(currency);
+        let mut cfg = zeroed::<GlobalConfig>();
+        assert_eq!(
+            quote(&source, &cfg, currency, PDOX_RESERVE, 1_000),
+            Ok(2_000_000)
+        );
+
+        cfg.pdox_fee_discount_bps = 2_000;
+        assert_eq!(
+            quote(&source, &cfg, currency, PDOX_RESERVE, 1_000),
+            Ok(1_600_000)
+        );
+
+        // Rounded up in the protocol's favour
+        cfg.pdox_fee_discount_bps = 0;
+        assert_eq!(
+            quote(&source, &cfg, currency, PDOX_RESERVE + 1, 1),
+            Ok(2_001)
+        );
+    }
+
+    #[test]
+    fn quote_pdox_fee_rejects_moved_or_thin_pools() {
+        let currency = Pubkey::new_unique();
+        let cfg = zeroed::<GlobalConfig>();
+        let source = price_source(currency);
+
+        // Within 5% of the reference price, but not beyond
+        assert!(quote(&source, &cfg, currency, PDOX_RESERVE / 20 * 21, 1_000).is_ok());
+        assert_eq!(
+            quote(
+                &source,
+                &cfg,
+                currency,
+                PDOX_RESERVE / 20 * 21 + 1_000,
+                1_000
+            ),
+            Err(error!(PgError::PdoxPriceDeviation))
+        );
+        assert_eq!(
+            quote(&source, &cfg, currency, PDOX_RESERVE / 10, 1_000),
+            Err(error!(PgError::PdoxPriceDeviation))
+        );
+
+        let mut thin = price_source(currency);
+        thin.min_quote_liquidity = QUOTE_RESERVE + 1;
+        assert_eq!(
+            quote(&thin, &cfg, currency, PDOX_RESERVE, 1_000),
+            Err(error!(PgError::PdoxLiquidityTooLow))
+        );
+
+        let mut unpriced = price_source(currency);
+        unpriced.reference_price = 0;
+        assert_eq!(
+            quote(&unpriced, &cfg, currency, PDOX_RESERVE, 1_000),
+            Err(error!(PgError::InvalidPdoxPriceSource))
+        );
+        assert_eq!(
+            quote(&source, &cfg, Pubkey::new_unique(), PDOX_RESERVE, 1_000),
+            Err(error!(PgError::InvalidPdoxPriceSource))
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    enter_execution_game, exit_execution_game, GameConfig, PgError, PlayerLedger, ReferralAccount,
+    ReferralFeesClaimed, ReferralPaid, ReferrerRegistered, BPS_DENOM, GAME_SEED, LEDGER_SEED,
+    REFERRAL_SEED,
+};
+
+// ======================================================================
+// REFERRAL INSTRUCTIONS
+// ======================================================================
+
+/// Register the signer as a referrer for a game (front-ends pass this account on purchases).
+///
+/// # Errors
+/// - Anchor init error if the referrer is already registered for this game
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referral = &mut ctx.accounts.referral;
+    referral.game = ctx.accounts.game.key();
+    referral.referrer = ctx.accounts.referrer.key();
+    referral.claimable = 0;
+    referral.total_earned = 0;
+    referral.referred_volume = 0;
+    referral.created_at = Clock::get()?.unix_timestamp;
+    referral.bump = ctx.bumps.referral;
+
+    emit!(ReferrerRegistered {
+        referral: referral.key(),
+        game: referral.game,
+        referrer: referral.referrer,
+    });
+
+    Ok(())
+}
+
+/// Move accrued referral fees into the referrer's `PlayerLedger` (withdrawable via `withdraw_credits`).
+///
+/// Referral fees are carved out of the protocol fee and stay backed by the game vault, so
+/// claiming only moves credits.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if the referral account belongs to another game or referrer
+/// - `PgError::InvalidAmount` if nothing is claimable
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let referral = &ctx.accounts.referral;
+    require!(
+        referral.game == ctx.accounts.game.key(),
+        PgError::Unauthorized
+    );
+    require!(
+        referral.referrer == ctx.accounts.referrer.key(),
+        PgError::Unauthorized
+    );
+    require!(referral.claimable > 0, PgError::InvalidAmount);
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let referral = &mut ctx.accounts.referral;
+        let ledger = &mut ctx.accounts.referrer_ledger;
+        if ledger.authority == Pubkey::default() {
+            ledger.game = game.key();
+            ledger.authority = referral.referrer;
+            ledger.available = 0;
+            ledger.locked = 0;
+            ledger.kyc_verified = false;
+            ledger.kyc_provider = Pubkey::default();
+            ledger.kyc_verified_at = 0;
+            ledger.kyc_proof_hash = [0u8; 32];
+        }
+        require!(ledger.authority == referral.referrer, PgError::Unauthorized);
+
+        let amount = referral.claimable;
+        referral.claimable = 0;
+        ledger.available = ledger
+            .available
+            .checked_add(amount)
+            .ok_or(PgError::Overflow)?;
+
+        emit!(ReferralFeesClaimed {
+            referral: referral.key(),
+            game: game.key(),
+            referrer: referral.referrer,
+            amount,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Carve the referrer's cut (`referral_bps` of `protocol_fee`) out of the protocol fee and
+/// accrue it to `referral`. Returns the referral fee; the caller books the remainder as
+/// protocol fees.
+///
+/// # Errors
+/// - `PgError::Overflow` on arithmetic overflow
+pub fn accrue_referral(
+    referral: &mut Account<ReferralAccount>,
+    referral_bps: u16,
+    protocol_fee: u64,
+    listing: Pubkey,
+    buyer: Pubkey,
+    volume: u64,
+) -> Result<u64> {
+    let referral_fee = protocol_fee
+        .checked_mul(u64::from(referral_bps))
+        .ok_or(PgError::Overflow)?
+        .checked_div(BPS_DENOM)
+        .ok_or(PgError::Overflow)?;
+
+    referral.referred_volume = referral
+        .referred_volume
+        .checked_add(volume)
+        .ok_or(PgError::Overflow)?;
+    if referral_fee == 0 {
+        return Ok(0);
+    }
+
+    referral.claimable = referral
+        .claimable
+        .checked_add(referral_fee)
+        .ok_or(PgError::Overflow)?;
+    referral.total_earned = referral
+        .total_earned
+        .checked_add(referral_fee)
+        .ok_or(PgError::Overflow)?;
+
+    emit!(ReferralPaid {
+        referral: referral.key(),
+        game: referral.game,
+        referrer: referral.referrer,
+        listing,
+        buyer,
+        amount: referral_fee,
+    });
+
+    Ok(referral_fee)
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        init,
+        payer = referrer,
+        seeds = [REFERRAL_SEED, game.key().as_ref(), referrer.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<ReferralAccount>()
+    )]
+    pub referral: Box<Account<'info, ReferralAccount>>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralFees<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [REFERRAL_SEED, game.key().as_ref(), referrer.key().as_ref()],
+        bump = referral.bump
+    )]
+    pub referral: Box<Account<'info, ReferralAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = referrer,
+        seeds = [LEDGER_SEED, game.key().as_ref(), referrer.key().as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PlayerLedger>()
+    )]
+    pub referrer_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::zeroed;
+
+    #[test]
+    fn referral_check_rejects_self_referral_and_other_games() {
+        let (game, buyer, seller) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut referral = zeroed::<ReferralAccount>();
+        referral.game = game;
+        referral.referrer = Pubkey::new_unique();
+        assert!(referral.check(&game, &buyer, &seller).is_ok());
+        assert_eq!(
+            referral.check(&Pubkey::new_unique(), &buyer, &seller),
+            Err(error!(PgError::Unauthorized))
+        );
+
+        for self_referrer in [buyer, seller] {
+            referral.referrer = self_referrer;
+            assert_eq!(
+                referral.check(&game, &buyer, &seller),
+                Err(error!(PgError::SelfReferral))
+            );
+        }
+    }
+
+    #[test]
+    fn accrue_referral_carves_its_share_out_of_the_protocol_fee() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = Vec::new();
+        zeroed::<ReferralAccount>()
+            .try_serialize(&mut data)
+            .unwrap();
+        let info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &crate::ID,
+            false,
+            0,
+        );
+        let mut referral = Account::<ReferralAccount>::try_from(&info).unwrap();
+        let (listing, buyer) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        assert_eq!(
+            accrue_referral(&mut referral, 2_000, 250, listing, buyer, 10_000).unwrap(),
+            50
+        );
+        // Rounds down to nothing on a tiny fee, but the volume still counts
+        assert_eq!(
+            accrue_referral(&mut referral, 2_000, 4, listing, buyer, 100).unwrap(),
+            0
+        );
+        assert_eq!(
+            (
+                referral.claimable,
+                referral.total_earned,
+                referral.referred_volume
+            ),
+            (50, 50, 10_100)
+        );
+    }
+}
@@ -0,0 +1,884 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::bundle::close_component_escrow;
+use crate::{
+    enter_execution_game, exit_execution_game, GameConfig, GlobalConfig, ItemRented, PgError,
+    PlayerLedger, RentalCancelled, RentalEnded, RentalListed, RentalListing, RentalStatus,
+    BPS_DENOM, CONFIG_SEED, ESCROW_SEED, GAME_SEED, LEDGER_SEED, MAX_BULK_QTY,
+    MAX_LISTING_DURATION_SECS, RENTAL_SEED,
+};
+
+// ======================================================================
+// ITEM RENTAL INSTRUCTIONS
+// ======================================================================
+
+/// List items for rent. Items are escrowed until rented.
+///
+/// The owner's KYC tier and jurisdiction are checked against rent plus collateral (the
+/// collateral is paid to the owner if the items aren't returned).
+///
+/// # Errors
+/// - `PgError::ListingsPaused` if new listings are paused
+/// - `PgError::InvalidAmount` if quantity, rent price or collateral is invalid
+/// - `PgError::InvalidTime` if `duration_secs` is 0 or exceeds `MAX_LISTING_DURATION_SECS`
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` if the owner's KYC tier doesn't allow it
+/// - `PgError::JurisdictionBlocked` if the owner's country may not trade in this game
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn create_rental_listing(
+    ctx: Context<CreateRentalListing>,
+    rental_id: u64,
+    quantity: u64,
+    rent_price: u64,
+    duration_secs: i64,
+    collateral: u64,
+) -> Result<()> {
+    require!(
+        (1..=MAX_BULK_QTY).contains(&quantity),
+        PgError::InvalidAmount
+    );
+    require!(rent_price > 0, PgError::InvalidAmount);
+    require!(collateral > 0, PgError::InvalidAmount);
+    require!(
+        (1..=MAX_LISTING_DURATION_SECS).contains(&duration_secs),
+        PgError::InvalidTime
+    );
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    require!(!cfg.paused_new, PgError::ListingsPaused);
+    require!(!game.paused_new, PgError::ListingsPaused);
+    let owner = ctx.accounts.owner.key();
+    let owner_ledger = &ctx.accounts.owner_ledger;
+    require!(owner_ledger.authority == owner, PgError::Unauthorized);
+    let now = Clock::get()?.unix_timestamp;
+    let notional = rent_price
+        .checked_add(collateral)
+        .ok_or(PgError::Overflow)?;
+    owner_ledger.check_kyc_trade(cfg, game.kyc_required, notional, now)?;
+    game.jurisdictions.check(owner_ledger, now)?;
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let rental = &mut ctx.accounts.rental;
+        rental.game = game.key();
+        rental.owner = owner;
+        rental.rental_id = rental_id;
+        rental.item_mint = ctx.accounts.item_mint.key();
+        rental.quantity = quantity;
+        rental.rent_price = rent_price;
+        rental.duration_secs = duration_secs;
+        rental.collateral = collateral;
+        rental.status = RentalStatus::Available;
+        rental.renter = Pubkey::default();
+        rental.rented_at = 0;
+        rental.expires_at = 0;
+        rental.created_at = now;
+        rental.bump = ctx.bumps.rental;
+
+        // Escrow items: owner -> escrow
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.owner_item_ata.to_account_info(),
+            mint: ctx.accounts.item_mint.to_account_info(),
+            to: ctx.accounts.escrow_item_ata.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, quantity, ctx.accounts.item_mint.decimals)?;
+
+        emit!(RentalListed {
+            rental: rental.key(),
+            game: game.key(),
+            owner,
+            item_mint: rental.item_mint,
+            quantity,
+            rent_price,
+            duration_secs,
+            collateral,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Rent items for `duration_secs`.
+///
+/// Pays `rent_price` to the owner (game/protocol fees deducted), locks `collateral` in the
+/// renter's ledger, moves the items to the renter's ATA and approves the game PDA as delegate
+/// so `end_rental` can pull them back. Rent plus collateral counts against the renter's
+/// spending limits and KYC tier limits.
+///
+/// # Errors
+/// - `PgError::InvalidListingStatus` if the rental is not Available
+/// - `PgError::JurisdictionBlocked` if the renter's country may not trade in this game
+/// - `PgError::SelfExcluded` if the renter is in a self-exclusion cool-off
+/// - `PgError::InsufficientCredits` if renter can't cover rent plus collateral
+/// - `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired` if the renter's
+///   spending limits don't allow it
+/// - `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded` if the renter's KYC
+///   tier doesn't allow it
+/// - `PgError::FeeTooHigh` if fees exceed 50% of the rent
+/// - `PgError::InvalidSellerAmount` if the owner would receive nothing after fees
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+#[allow(clippy::too_many_lines)] // Payment, collateral and delegate setup
+pub fn rent_item(ctx: Context<RentItem>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let rental = &ctx.accounts.rental;
+
+    require!(rental.game == game.key(), PgError::Unauthorized);
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+    require!(
+        rental.status == RentalStatus::Available,
+        PgError::InvalidListingStatus
+    );
+
+    let renter = ctx.accounts.renter.key();
+    require!(renter != rental.owner, PgError::Unauthorized);
+    let renter_ledger = &ctx.accounts.renter_ledger;
+    require!(renter_ledger.authority == renter, PgError::Unauthorized);
+    let now = Clock::get()?.unix_timestamp;
+    if game.kyc_required {
+        require!(renter_ledger.has_valid_kyc(now), PgError::KycRequired);
+    }
+    game.jurisdictions.check(renter_ledger, now)?;
+    require!(
+        !renter_ledger.limits.is_excluded(now),
+        PgError::SelfExcluded
+    );
+    require!(
+        ctx.accounts.owner_ledger.authority == rental.owner,
+        PgError::Unauthorized
+    );
+
+    let total_due = rental
+        .rent_price
+        .checked_add(rental.collateral)
+        .ok_or(PgError::Overflow)?;
+    require!(
+        renter_ledger.available >= total_due,
+        PgError::InsufficientCredits
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let rental = &mut ctx.accounts.rental;
+    let renter_ledger = &mut ctx.accounts.renter_ledger;
+    let owner_ledger = &mut ctx.accounts.owner_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        // Fees on the rent (collateral is never charged)
+        let rent_price = rental.rent_price;
+        let game_fee = rent_price
+            .checked_mul(u64::from(game.fee_bps))
+            .ok_or(PgError::Overflow)?
+            .checked_div(BPS_DENOM)
+            .ok_or(PgError::Overflow)?;
+        let protocol_fee = rent_price
+            .checked_mul(u64::from(cfg.protocol_fee_bps))
+            .ok_or(PgError::Overflow)?
+            .checked_div(BPS_DENOM)
+            .ok_or(PgError::Overflow)?;
+
+        // CRITICAL: Ensure total fees don't exceed 50% of the rent
+        let total_fees = game_fee
+            .checked_add(protocol_fee)
+            .ok_or(PgError::Overflow)?;
+        let max_allowed_fees = rent_price.checked_div(2).ok_or(PgError::Overflow)?; // 50% max
+        require!(total_fees <= max_allowed_fees, PgError::FeeTooHigh);
+        let owner_amount = rent_price
+            .checked_sub(total_fees)
+            .ok_or(PgError::Overflow)?;
+        require!(owner_amount > 0, PgError::InvalidSellerAmount);
+
+        // Collateral can be forfeited to the owner, so it counts as spent with the rent
+        let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+        renter_ledger
+            .limits
+            .record_spend(total_due, now, approver)?;
+        renter_ledger.record_kyc_trade(cfg, game.kyc_required, total_due, now)?;
+
+        // Move credits: rent -> owner, collateral -> renter locked
+        renter_ledger.available = renter_ledger
+            .available
+            .checked_sub(total_due)
+            .ok_or(PgError::Overflow)?;
+        renter_ledger.locked = renter_ledger
+            .locked
+            .checked_add(rental.collateral)
+            .ok_or(PgError::Overflow)?;
+        owner_ledger.available = owner_ledger
+            .available
+            .checked_add(owner_amount)
+            .ok_or(PgError::Overflow)?;
+
+        // CRITICAL: Track protocol and game fees per-game
+        game.protocol_fees_accumulated = game
+            .protocol_fees_accumulated
+            .checked_add(protocol_fee)
+            .ok_or(PgError::Overflow)?;
+        game.accumulated_game_fees = game
+            .accumulated_game_fees
+            .checked_add(game_fee)
+            .ok_or(PgError::Overflow)?;
+
+        // Deliver items: escrow -> renter ATA (game PDA signs)
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.escrow_item_ata.to_account_info(),
+            mint: ctx.accounts.item_mint.to_account_info(),
+            to: ctx.accounts.renter_item_ata.to_account_info(),
+            authority: game.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(
+            cpi_ctx,
+            rental.quantity,
+            ctx.accounts.item_mint.decimals,
+        )?;
+
+        // Game PDA becomes delegate so the items can be recalled after expiry
+        let cpi_accounts = token_interface::Approve {
+            to: ctx.accounts.renter_item_ata.to_account_info(),
+            delegate: game.to_account_info(),
+            authority: ctx.accounts.renter.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::approve(cpi_ctx, rental.quantity)?;
+
+        rental.status = RentalStatus::Rented;
+        rental.renter = renter;
+        rental.rented_at = now;
+        rental.expires_at = now
+            .checked_add(rental.duration_secs)
+            .ok_or(PgError::Overflow)?;
+
+        emit!(ItemRented {
+            rental: rental.key(),
+            game: game.key(),
+            owner: rental.owner,
+            renter,
+            rent_price,
+            collateral: rental.collateral,
+            expires_at: rental.expires_at,
+            protocol_fee,
+            game_fee,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Whether `end_rental` can pull the items back from `renter_item_ata`: it must still hold them
+/// unfrozen, and a crank (not the renter) also needs the game PDA's delegation to cover them.
+fn rental_returnable(
+    renter_item_ata: &AccountInfo,
+    token_program: &Pubkey,
+    rental: &RentalListing,
+    game: &Pubkey,
+    by_renter: bool,
+) -> Result<bool> {
+    if renter_item_ata.owner != token_program || renter_item_ata.data_is_empty() {
+        return Ok(false);
+    }
+    let data = renter_item_ata.try_borrow_data()?;
+    Ok(match TokenAccount::try_deserialize(&mut &data[..]) {
+        Ok(ata) => {
+            let holds_items = ata.mint == rental.item_mint
+                && ata.owner == rental.renter
+                && !ata.is_frozen()
+                && ata.amount >= rental.quantity;
+            let delegated = Option::<Pubkey>::from(ata.delegate) == Some(*game)
+                && ata.delegated_amount >= rental.quantity;
+            holds_items && (by_renter || delegated)
+        }
+        Err(_) => false,
+    })
+}
+
+/// End a rental.
+///
+/// The renter may return early; after `expires_at` anyone can crank it. If the renter's ATA
+/// still holds the items (and, for cranks, the game PDA is still delegate) they go back to
+/// escrow and the collateral is unlocked. Otherwise the collateral is forfeited to the owner,
+/// the rental is closed and its empty escrow is closed to the owner.
+///
+/// # Errors
+/// - `PgError::InvalidListingStatus` if the rental is not Rented
+/// - `PgError::InvalidTime` if a non-renter calls before `expires_at`
+/// - `PgError::InsufficientCredits` if the renter's locked credits don't cover the collateral
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+#[allow(clippy::too_many_lines)] // Return and forfeit paths
+pub fn end_rental(ctx: Context<EndRental>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let game = &ctx.accounts.game;
+    let rental = &ctx.accounts.rental;
+
+    require!(rental.game == game.key(), PgError::Unauthorized);
+    require!(
+        rental.status == RentalStatus::Rented,
+        PgError::InvalidListingStatus
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let caller = ctx.accounts.caller.key();
+    let by_renter = caller == rental.renter;
+    require!(by_renter || now >= rental.expires_at, PgError::InvalidTime);
+
+    require!(
+        ctx.accounts.renter_ledger.authority == rental.renter,
+        PgError::Unauthorized
+    );
+    require!(
+        ctx.accounts.renter_ledger.locked >= rental.collateral,
+        PgError::InsufficientCredits
+    );
+    require!(
+        ctx.accounts.owner_ledger.authority == rental.owner,
+        PgError::Unauthorized
+    );
+
+    // Can the items be pulled back? (ATA may be closed, emptied, frozen or the delegate revoked)
+    let returnable = rental_returnable(
+        &ctx.accounts.renter_item_ata.to_account_info(),
+        &ctx.accounts.token_program.key(),
+        rental,
+        &game.key(),
+        by_renter,
+    )?;
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let rental = &mut ctx.accounts.rental;
+    let renter_ledger = &mut ctx.accounts.renter_ledger;
+    let owner_ledger = &mut ctx.accounts.owner_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let collateral = rental.collateral;
+        renter_ledger.locked = renter_ledger
+            .locked
+            .checked_sub(collateral)
+            .ok_or(PgError::Overflow)?;
+
+        let renter = rental.renter;
+        let mut collateral_forfeited = 0u64;
+        if returnable {
+            // Return items: renter ATA -> escrow (renter signs, or game PDA as delegate)
+            let cpi_accounts = token_interface::TransferChecked {
+                from: ctx.accounts.renter_item_ata.to_account_info(),
+                mint: ctx.accounts.item_mint.to_account_info(),
+                to: ctx.accounts.escrow_item_ata.to_account_info(),
+                authority: if by_renter {
+                    ctx.accounts.caller.to_account_info()
+                } else {
+                    game.to_account_info()
+                },
+            };
+            let game_id = game.game_id;
+            let game_bump = game.bump;
+            let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+            let signer = &[seeds];
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(
+                cpi_ctx,
+                rental.quantity,
+                ctx.accounts.item_mint.decimals,
+            )?;
+
+            // Renter-signed return leaves the allowance in place - clear it
+            if by_renter {
+                let cpi_accounts = token_interface::Revoke {
+                    source: ctx.accounts.renter_item_ata.to_account_info(),
+                    authority: ctx.accounts.caller.to_account_info(),
+                };
+                let cpi_ctx =
+                    CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                token_interface::revoke(cpi_ctx)?;
+            }
+
+            renter_ledger.available = renter_ledger
+                .available
+                .checked_add(collateral)
+                .ok_or(PgError::Overflow)?;
+            rental.status = RentalStatus::Available;
+        } else {
+            // Items can't be recovered - owner keeps the collateral
+            owner_ledger.available = owner_ledger
+                .available
+                .checked_add(collateral)
+                .ok_or(PgError::Overflow)?;
+            collateral_forfeited = collateral;
+            rental.status = RentalStatus::Closed;
+
+            let game_id = game.game_id;
+            let game_bump = game.bump;
+            let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+            close_component_escrow(
+                &ctx.accounts.escrow_item_ata.to_account_info(),
+                &ctx.accounts.owner.to_account_info(),
+                &game.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                &[seeds],
+            )?;
+        }
+
+        rental.renter = Pubkey::default();
+        rental.rented_at = 0;
+        rental.expires_at = 0;
+
+        emit!(RentalEnded {
+            rental: rental.key(),
+            game: game.key(),
+            renter,
+            returned: returnable,
+            collateral_forfeited,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Cancel an available rental listing, return the escrowed items to the owner and close the
+/// escrow.
+///
+/// # Errors
+/// - `PgError::InvalidListingStatus` if the items are currently rented or the rental is closed
+/// - `PgError::Unauthorized` if caller is not the owner
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn cancel_rental_listing(ctx: Context<CancelRentalListing>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let game = &ctx.accounts.game;
+    let rental = &ctx.accounts.rental;
+    require!(rental.game == game.key(), PgError::Unauthorized);
+    require!(
+        rental.owner == ctx.accounts.owner.key(),
+        PgError::Unauthorized
+    );
+    require!(
+        rental.status == RentalStatus::Available,
+        PgError::InvalidListingStatus
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let rental = &mut ctx.accounts.rental;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        rental.status = RentalStatus::Closed;
+
+        // Return items: escrow -> owner (game PDA signs)
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+        let cpi_accounts = token_interface::TransferChecked {
+            from: ctx.accounts.escrow_item_ata.to_account_info(),
+            mint: ctx.accounts.item_mint.to_account_info(),
+            to: ctx.accounts.owner_item_ata.to_account_info(),
+            authority: game.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(
+            cpi_ctx,
+            rental.quantity,
+            ctx.accounts.item_mint.decimals,
+        )?;
+        close_component_escrow(
+            &ctx.accounts.escrow_item_ata.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            &game.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer,
+        )?;
+
+        emit!(RentalCancelled {
+            rental: rental.key(),
+            game: game.key(),
+            owner: rental.owner,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+#[instruction(rental_id: u64)]
+pub struct CreateRentalListing<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        seeds = [LEDGER_SEED, game.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub owner_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            RENTAL_SEED,
+            game.key().as_ref(),
+            owner.key().as_ref(),
+            &rental_id.to_le_bytes()
+        ],
+        bump,
+        space = 8 + std::mem::size_of::<RentalListing>()
+    )]
+    pub rental: Box<Account<'info, RentalListing>>,
+
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = item_mint,
+        associated_token::authority = owner
+    )]
+    pub owner_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [ESCROW_SEED, rental.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub escrow_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct RentItem<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RENTAL_SEED,
+            game.key().as_ref(),
+            rental.owner.as_ref(),
+            &rental.rental_id.to_le_bytes()
+        ],
+        bump = rental.bump
+    )]
+    pub rental: Box<Account<'info, RentalListing>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), rental.owner.as_ref()],
+        bump
+    )]
+    pub owner_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), renter.key().as_ref()],
+        bump
+    )]
+    pub renter_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(address = rental.item_mint)]
+    pub item_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, rental.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub escrow_item_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = renter,
+        associated_token::mint = item_mint,
+        associated_token::authority = renter,
+        associated_token::token_program = token_program
+    )]
+    pub renter_item_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    /// Guardian co-signature for spends above the renter's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct EndRental<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RENTAL_SEED,
+            game.key().as_ref(),
+            rental.owner.as_ref(),
+            &rental.rental_id.to_le_bytes()
+        ],
+        bump = rental.bump
+    )]
+    pub rental: Box<Account<'info, RentalListing>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), rental.owner.as_ref()],
+        bump
+    )]
+    pub owner_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), rental.renter.as_ref()],
+        bump
+    )]
+    pub renter_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(address = rental.item_mint)]
+    pub item_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, rental.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub escrow_item_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Renter's item ATA - may have been closed or emptied, so it is parsed in the handler
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &rental.renter,
+            &rental.item_mint,
+            &token_program.key()
+        )
+    )]
+    pub renter_item_ata: UncheckedAccount<'info>,
+
+    /// CHECK: Rental owner (receives the escrow rent on forfeit); validated against rental.owner
+    #[account(mut, address = rental.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Renter (early return) or permissionless crank after expiry
+    pub caller: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRentalListing<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            RENTAL_SEED,
+            game.key().as_ref(),
+            owner.key().as_ref(),
+            &rental.rental_id.to_le_bytes()
+        ],
+        bump = rental.bump
+    )]
+    pub rental: Box<Account<'info, RentalListing>>,
+
+    #[account(address = rental.item_mint)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, rental.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub escrow_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = item_mint,
+        associated_token::authority = owner
+    )]
+    pub owner_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::zeroed;
+    use anchor_spl::token_2022::spl_token_2022::state::{
+        Account as TokenAccountState, AccountState,
+    };
+    use solana_program::program_option::COption;
+    use solana_program::program_pack::Pack;
+
+    /// `rental_returnable` for a renter ATA in `ata` (`None` = closed)
+    fn returnable(
+        rental: &RentalListing,
+        game: &Pubkey,
+        ata: Option<TokenAccountState>,
+        by_renter: bool,
+    ) -> bool {
+        let (key, token_program) = (Pubkey::new_unique(), anchor_spl::token::ID);
+        let mut lamports = 0;
+        let mut data = match ata {
+            Some(ata) => {
+                let mut data = vec![0u8; TokenAccountState::LEN];
+                ata.pack_into_slice(&mut data);
+                data
+            }
+            None => Vec::new(),
+        };
+        let owner = if ata.is_some() {
+            token_program
+        } else {
+            Pubkey::default()
+        };
+        let info = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        rental_returnable(&info, &token_program, rental, game, by_renter).unwrap()
+    }
+
+    #[test]
+    fn rental_returnable_needs_items_and_delegation_for_cranks() {
+        let game = Pubkey::new_unique();
+        let mut rental = zeroed::<RentalListing>();
+        rental.item_mint = Pubkey::new_unique();
+        rental.renter = Pubkey::new_unique();
+        rental.quantity = 3;
+        let ata = TokenAccountState {
+            mint: rental.item_mint,
+            owner: rental.renter,
+            amount: 3,
+            delegate: COption::Some(game),
+            delegated_amount: 3,
+            state: AccountState::Initialized,
+            ..TokenAccountState::default()
+        };
+
+        assert!(returnable(&rental, &game, Some(ata), false));
+        assert!(!returnable(&rental, &game, None, true));
+        assert!(!returnable(
+            &rental,
+            &game,
+            Some(TokenAccountState { amount: 2, ..ata }),
+            true
+        ));
+        assert!(!returnable(
+            &rental,
+            &game,
+            Some(TokenAccountState {
+                state: AccountState::Frozen,
+                ..ata
+            }),
+            true
+        ));
+
+        // Revoked delegation: only the renter can still hand the items back
+        let revoked = TokenAccountState {
+            delegate: COption::None,
+            delegated_amount: 0,
+            ..ata
+        };
+        assert!(returnable(&rental, &game, Some(revoked), true));
+        assert!(!returnable(&rental, &game, Some(revoked), false));
+    }
+}
@@ -0,0 +1,384 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    create_pda_account, GameConfig, KycTier, PgError, PlayerLedger, RoyaltyPaid, RoyaltySplit,
+    RoyaltySplitCreated, SpendingLimits, VolumeTracker, BPS_DENOM, GAME_SEED, LEDGER_SEED,
+    MAX_ROYALTY_RECIPIENTS, ROYALTY_SPLIT_SEED,
+};
+
+// ======================================================================
+// ROYALTY SPLIT INSTRUCTIONS
+// ======================================================================
+
+/// Create `authority`'s `PlayerLedger` PDA in `game` if it doesn't exist yet, so settlement can
+/// credit it without `init_if_needed`. `payer` funds the rent; a pre-funded ledger address is
+/// handled by `create_pda_account`.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if `ledger_info` is not the ledger PDA
+pub(crate) fn init_player_ledger<'info>(
+    ledger_info: &AccountInfo<'info>,
+    game: &Pubkey,
+    authority: &Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (ledger_key, ledger_bump) = Pubkey::find_program_address(
+        &[LEDGER_SEED, game.as_ref(), authority.as_ref()],
+        program_id,
+    );
+    require!(ledger_info.key() == ledger_key, PgError::Unauthorized);
+    if !ledger_info.data_is_empty() {
+        return Ok(());
+    }
+
+    let space = 8 + std::mem::size_of::<PlayerLedger>();
+    create_pda_account(
+        ledger_info,
+        payer,
+        system_program,
+        Rent::get()?.minimum_balance(space),
+        u64::try_from(space).map_err(|_| PgError::Overflow)?,
+        program_id,
+        &[
+            LEDGER_SEED,
+            game.as_ref(),
+            authority.as_ref(),
+            &[ledger_bump],
+        ],
+    )?;
+    let ledger = PlayerLedger {
+        game: *game,
+        authority: *authority,
+        available: 0,
+        locked: 0,
+        kyc_verified: false,
+        kyc_provider: Pubkey::default(),
+        kyc_verified_at: 0,
+        kyc_proof_hash: [0u8; 32],
+        kyc_tier: KycTier::None,
+        kyc_expires_at: 0,
+        kyc_country: [0u8; 2],
+        kyc_volume_day: 0,
+        kyc_day_volume: 0,
+        limits: SpendingLimits::default(),
+        fee_volume: VolumeTracker::default(),
+        reserved: [0u8; 16],
+    };
+    let mut data = ledger_info.try_borrow_mut_data()?;
+    ledger.try_serialize(&mut &mut data[..])
+}
+
+/// Create a reusable royalty split shared by several recipients (artist, studio, guild, ...).
+///
+/// `weights` are shares of the royalty in bps and must sum to `BPS_DENOM`. Recipient 0 is the
+/// primary recipient: listings using the split set it as `royalty_recipient` and it receives
+/// any rounding dust. `remaining_accounts` carries each recipient's `PlayerLedger` PDA, in
+/// recipient order; missing ledgers are created here so settlement never has to.
+///
+/// # Errors
+/// - `PgError::InvalidRoyaltySplit` if recipient/weight counts mismatch, exceed
+///   `MAX_ROYALTY_RECIPIENTS`, contain zero weights, duplicates or defaults, or weights don't sum to `BPS_DENOM`
+/// - `PgError::Unauthorized` if a ledger account is not the recipient's ledger PDA
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn create_royalty_split<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateRoyaltySplit<'info>>,
+    split_id: u64,
+    recipients: Vec<Pubkey>,
+    weights: Vec<u16>,
+) -> Result<()> {
+    let count = recipients.len();
+    require!(
+        (1..=MAX_ROYALTY_RECIPIENTS).contains(&count),
+        PgError::InvalidRoyaltySplit
+    );
+    require!(weights.len() == count, PgError::InvalidRoyaltySplit);
+    require!(
+        ctx.remaining_accounts.len() == count,
+        PgError::InvalidRoyaltySplit
+    );
+
+    let mut total_weight = 0u64;
+    for (index, (recipient, weight)) in recipients.iter().zip(weights.iter()).enumerate() {
+        require!(*weight > 0, PgError::InvalidRoyaltySplit);
+        require!(
+            *recipient != Pubkey::default(),
+            PgError::InvalidRoyaltySplit
+        );
+        require!(
+            !recipients[..index].contains(recipient),
+            PgError::InvalidRoyaltySplit
+        );
+        total_weight = total_weight
+            .checked_add(u64::from(*weight))
+            .ok_or(PgError::Overflow)?;
+    }
+    require!(total_weight == BPS_DENOM, PgError::InvalidRoyaltySplit);
+
+    let game_key = ctx.accounts.game.key();
+    for (recipient, ledger_info) in recipients.iter().zip(ctx.remaining_accounts.iter()) {
+        init_player_ledger(
+            ledger_info,
+            &game_key,
+            recipient,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.program_id,
+        )?;
+    }
+
+    let split = &mut ctx.accounts.split;
+    split.game = game_key;
+    split.authority = ctx.accounts.authority.key();
+    split.split_id = split_id;
+    split.recipient_count = u8::try_from(count).map_err(|_| PgError::Overflow)?;
+    split.recipients = [Pubkey::default(); MAX_ROYALTY_RECIPIENTS];
+    split.weights = [0u16; MAX_ROYALTY_RECIPIENTS];
+    split.recipients[..count].copy_from_slice(&recipients);
+    split.weights[..count].copy_from_slice(&weights);
+    split.created_at = Clock::get()?.unix_timestamp;
+    split.bump = ctx.bumps.split;
+
+    emit!(RoyaltySplitCreated {
+        split: split.key(),
+        game: game_key,
+        authority: split.authority,
+        recipients,
+        weights,
+    });
+
+    Ok(())
+}
+
+/// Credit split recipients `1..` with their `shares`.
+///
+/// Recipient 0 is paid through the caller's `royalty_recipient_ledger` with `shares[0]`,
+/// which already includes the rounding dust. Recipients whose ledger is already part of the
+/// instruction (buyer/seller) are credited in `in_context` so Anchor's exit doesn't overwrite
+/// the share; every other recipient's ledger is taken from `accounts` in split order.
+///
+/// # Errors
+/// - `PgError::InvalidRoyaltySplit` if fewer ledgers than recipients are supplied
+/// - `PgError::Unauthorized` if a ledger doesn't belong to the matching recipient
+pub fn credit_royalty_split<'info>(
+    split: &RoyaltySplit,
+    shares: &[u64; MAX_ROYALTY_RECIPIENTS],
+    listing: Pubkey,
+    in_context: &mut [&mut Account<'info, PlayerLedger>],
+    accounts: &'info [AccountInfo<'info>],
+    program_id: &Pubkey,
+) -> Result<()> {
+    let mut remaining = accounts.iter();
+    for (recipient, share) in split
+        .recipients
+        .iter()
+        .zip(shares.iter())
+        .take(usize::from(split.recipient_count))
+        .skip(1)
+    {
+        if let Some(ledger) = in_context
+            .iter_mut()
+            .find(|ledger| ledger.authority == *recipient)
+        {
+            ledger.available = ledger
+                .available
+                .checked_add(*share)
+                .ok_or(PgError::Overflow)?;
+        } else {
+            let ledger_info = remaining.next().ok_or(PgError::InvalidRoyaltySplit)?;
+            let mut ledger = Account::<PlayerLedger>::try_from(ledger_info)?;
+            require!(ledger.game == split.game, PgError::Unauthorized);
+            require!(ledger.authority == *recipient, PgError::Unauthorized);
+            ledger.available = ledger
+                .available
+                .checked_add(*share)
+                .ok_or(PgError::Overflow)?;
+            ledger.exit(program_id)?;
+        }
+        if *share == 0 {
+            continue;
+        }
+
+        emit!(RoyaltyPaid {
+            listing,
+            game: split.game,
+            recipient: *recipient,
+            amount: *share,
+        });
+    }
+
+    Ok(())
+}
+
+/// Token-transfer counterpart of `credit_royalty_split` for the compressed (non-ledger) paths.
+///
+/// `primary_token_account` must belong to recipient 0 and receives `shares[0]` (including
+/// dust); recipients `1..` are paid into their token accounts taken from `accounts`, in split order.
+///
+/// # Errors
+/// - `PgError::InvalidRoyaltySplit` if fewer token accounts than recipients are supplied
+/// - `PgError::Unauthorized` if a token account isn't owned by the matching recipient
+/// - `PgError::CurrencyMintMismatch` if a token account isn't for `currency_mint`
+#[allow(clippy::too_many_arguments)] // Transfer source, authority, mint and program are all required
+pub fn transfer_royalty_split<'info>(
+    split: &RoyaltySplit,
+    shares: &[u64; MAX_ROYALTY_RECIPIENTS],
+    from: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    primary_token_account: &AccountInfo<'info>,
+    currency_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let count = usize::from(split.recipient_count);
+    require!(
+        accounts.len() >= count.saturating_sub(1),
+        PgError::InvalidRoyaltySplit
+    );
+
+    let targets = core::iter::once(primary_token_account).chain(accounts.iter());
+    for ((recipient, share), target) in split
+        .recipients
+        .iter()
+        .zip(shares.iter())
+        .zip(targets)
+        .take(count)
+    {
+        require!(target.owner == &token_program.key(), PgError::Unauthorized);
+        {
+            let data = target.try_borrow_data()?;
+            let token_account = TokenAccount::try_deserialize(&mut &data[..])?;
+            require!(token_account.owner == *recipient, PgError::Unauthorized);
+            require!(
+                token_account.mint == currency_mint.key(),
+                PgError::CurrencyMintMismatch
+            );
+        }
+        if *share == 0 {
+            continue;
+        }
+
+        let cpi_accounts = token_interface::TransferChecked {
+            from: from.clone(),
+            mint: currency_mint.to_account_info(),
+            to: target.clone(),
+            authority: authority.clone(),
+        };
+        let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, *share, currency_mint.decimals)?;
+    }
+
+    Ok(())
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+#[instruction(split_id: u64)]
+pub struct CreateRoyaltySplit<'info> {
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [
+            ROYALTY_SPLIT_SEED,
+            game.key().as_ref(),
+            authority.key().as_ref(),
+            &split_id.to_le_bytes()
+        ],
+        bump,
+        space = 8 + std::mem::size_of::<RoyaltySplit>()
+    )]
+    pub split: Box<Account<'info, RoyaltySplit>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_player_ledger_checks_the_pda_and_skips_existing_ledgers() {
+        let (game, recipient, payer_key) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (ledger_key, _) = Pubkey::find_program_address(
+            &[LEDGER_SEED, game.as_ref(), recipient.as_ref()],
+            &crate::ID,
+        );
+        let system = anchor_lang::system_program::ID;
+        let (mut ledger_lamports, mut payer_lamports, mut system_lamports) = (1u64, 0u64, 0u64);
+        let mut ledger_data = vec![1u8; 8 + std::mem::size_of::<PlayerLedger>()];
+        let (mut payer_data, mut system_data) = (Vec::new(), Vec::new());
+        let ledger = AccountInfo::new(
+            &ledger_key,
+            false,
+            true,
+            &mut ledger_lamports,
+            &mut ledger_data,
+            &crate::ID,
+            false,
+            0,
+        );
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system,
+            false,
+            0,
+        );
+        let system_program = AccountInfo::new(
+            &system,
+            false,
+            false,
+            &mut system_lamports,
+            &mut system_data,
+            &system,
+            true,
+            0,
+        );
+
+        // Existing ledger: left untouched
+        init_player_ledger(
+            &ledger,
+            &game,
+            &recipient,
+            &payer,
+            &system_program,
+            &crate::ID,
+        )
+        .unwrap();
+        assert!(ledger.try_borrow_data().unwrap().iter().all(|b| *b == 1));
+
+        // Another wallet's ledger can't stand in for the recipient's
+        assert_eq!(
+            init_player_ledger(
+                &ledger,
+                &game,
+                &Pubkey::new_unique(),
+                &payer,
+                &system_program,
+                &crate::ID,
+            ),
+            Err(error!(PgError::Unauthorized))
+        );
+    }
+}
@@ -0,0 +1,501 @@
+use anchor_lang::prelude::*;
+use solana_program::keccak;
+
+use crate::{
+    enter_execution_game, exit_execution_game, GameConfig, GlobalConfig, Listing, ListingKind,
+    ListingStatus, PgError, PlayerLedger, SealedBidCommitment, SealedBidCommitted,
+    SealedBidDepositClaimed, SealedBidRevealed, BPS_DENOM, CONFIG_SEED, GAME_SEED, LEDGER_SEED,
+    SEALED_BID_FORFEIT_BPS, SEALED_BID_REVEAL_WINDOW_SECS, SEALED_BID_SEED,
+    SEALED_BID_SETTLEMENT_WINDOW_SECS,
+};
+
+// ======================================================================
+// SEALED-BID (COMMIT-REVEAL) AUCTION INSTRUCTIONS
+// ======================================================================
+//
+// Flow:
+// 1. commit_sealed_bid   - before end_time: bidder posts keccak commitment + deposit (locked)
+// 2. reveal_sealed_bid   - [end_time, end_time + reveal window): bidder reveals amount + salt
+// 3. finalize_auction_settlement - after reveal window: highest valid reveal wins and pays its
+//                                  own bid, or the runner-up bid in SecondPrice mode
+// 4. claim_sealed_bid_deposit    - losers get full refund, non-revealers forfeit a share to seller,
+//                                  winner gets back deposit minus the amount paid
+
+/// Compute the sealed-bid commitment hash.
+/// Binds listing and bidder so a commitment cannot be copied by another bidder.
+pub fn sealed_bid_commitment(
+    listing: &Pubkey,
+    bidder: &Pubkey,
+    amount: u64,
+    salt: &[u8; 32],
+) -> [u8; 32] {
+    keccak::hashv(&[
+        listing.as_ref(),
+        bidder.as_ref(),
+        &amount.to_le_bytes(),
+        salt.as_ref(),
+    ])
+    .to_bytes()
+}
+
+/// Commit a sealed bid with a deposit locked in the bidder's ledger.
+///
+/// # Errors
+/// - `PgError::InvalidListingKind` if listing is not `SealedBid`
+/// - `PgError::InvalidListingStatus` if listing is not Active
+/// - `PgError::InvalidTime` if outside `[start_time, end_time)`
+/// - `PgError::InvalidAmount` if deposit is 0 or cannot cover the reserve for the whole lot
+/// - `PgError::InsufficientCredits` if bidder doesn't have enough available credits
+/// - `PgError::SelfExcluded` if the bidder is in a self-exclusion cool-off
+/// - `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired` if the bidder's
+///   spending limits don't allow it
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded`
+///   if the deposit exceeds the bidder's KYC tier limits
+/// - `PgError::BuyerNotAllowed` / `PgError::InvalidMerkleProof` if the listing is private
+///   and the bidder isn't allowed
+pub fn commit_sealed_bid(
+    ctx: Context<CommitSealedBid>,
+    commitment: [u8; 32],
+    deposit: u64,
+    allowlist_proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(deposit > 0, PgError::InvalidAmount);
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let listing = &ctx.accounts.listing;
+
+    require!(listing.game == game.key(), PgError::Unauthorized);
+    require!(
+        listing.currency_mint == game.currency_mint,
+        PgError::CurrencyMintMismatch
+    );
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+
+    require!(
+        listing.kind == ListingKind::SealedBid,
+        PgError::InvalidListingKind
+    );
+    require!(
+        listing.status == ListingStatus::Active,
+        PgError::InvalidListingStatus
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= listing.start_time, PgError::InvalidTime);
+    require!(now < listing.end_time, PgError::InvalidTime);
+
+    // A valid reveal must be >= reserve for every unit, so the deposit must cover that at least
+    let min_deposit = listing
+        .reserve_price
+        .checked_mul(listing.quantity_total)
+        .ok_or(PgError::Overflow)?;
+    require!(deposit >= min_deposit, PgError::InvalidAmount);
+
+    let bidder_ledger = &ctx.accounts.bidder_ledger;
+    require!(
+        bidder_ledger.authority == ctx.accounts.bidder.key(),
+        PgError::Unauthorized
+    );
+    listing.check_buyer(&bidder_ledger.authority, &allowlist_proof)?;
+    require!(
+        bidder_ledger.available >= deposit,
+        PgError::InsufficientCredits
+    );
+    require!(
+        !bidder_ledger.limits.is_excluded(now),
+        PgError::SelfExcluded
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let listing = &mut ctx.accounts.listing;
+    let bidder_ledger = &mut ctx.accounts.bidder_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        // CRITICAL: Enforce KYC requirement and tier limits (the deposit counts toward daily volume)
+        bidder_ledger.record_kyc_trade(cfg, game.kyc_required, deposit, now)?;
+
+        // Spending limits: the locked deposit counts as spent
+        let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+        bidder_ledger.limits.record_spend(deposit, now, approver)?;
+
+        // Lock deposit (available -> locked)
+        bidder_ledger.available = bidder_ledger
+            .available
+            .checked_sub(deposit)
+            .ok_or(PgError::Overflow)?;
+        bidder_ledger.locked = bidder_ledger
+            .locked
+            .checked_add(deposit)
+            .ok_or(PgError::Overflow)?;
+
+        let bid = &mut ctx.accounts.commitment;
+        bid.listing = listing.key();
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.commitment = commitment;
+        bid.deposit = deposit;
+        bid.revealed = false;
+        bid.revealed_amount = 0;
+        bid.committed_at = now;
+        bid.bump = ctx.bumps.commitment;
+
+        listing.has_interest = true;
+        listing.updated_at = now;
+
+        emit!(SealedBidCommitted {
+            listing: listing.key(),
+            game: game.key(),
+            bidder: bid.bidder,
+            deposit,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Reveal a sealed bid during the reveal window.
+///
+/// Reveals below `reserve_price` are recorded but cannot win.
+///
+/// # Errors
+/// - `PgError::InvalidTime` if outside `[end_time, end_time + SEALED_BID_REVEAL_WINDOW_SECS)`
+/// - `PgError::BidAlreadyRevealed` if the commitment was already revealed
+/// - `PgError::InvalidBidCommitment` if `amount`/`salt` don't match the commitment
+/// - `PgError::InvalidAmount` if `amount * quantity_total` exceeds the deposit
+pub fn reveal_sealed_bid(
+    ctx: Context<RevealSealedBid>,
+    amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    require!(amount > 0, PgError::InvalidAmount);
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let game = &ctx.accounts.game;
+    let listing = &ctx.accounts.listing;
+    let bid = &ctx.accounts.commitment;
+
+    require!(listing.game == game.key(), PgError::Unauthorized);
+    require!(
+        listing.kind == ListingKind::SealedBid,
+        PgError::InvalidListingKind
+    );
+    require!(
+        listing.status == ListingStatus::Active,
+        PgError::InvalidListingStatus
+    );
+    require!(bid.listing == listing.key(), PgError::Unauthorized);
+    require!(bid.bidder == ctx.accounts.bidder.key(), PgError::Unauthorized);
+    require!(!bid.revealed, PgError::BidAlreadyRevealed);
+
+    let now = Clock::get()?.unix_timestamp;
+    let reveal_end = listing
+        .end_time
+        .checked_add(SEALED_BID_REVEAL_WINDOW_SECS)
+        .ok_or(PgError::Overflow)?;
+    require!(now >= listing.end_time, PgError::InvalidTime);
+    require!(now < reveal_end, PgError::InvalidTime);
+
+    let expected = sealed_bid_commitment(&listing.key(), &bid.bidder, amount, &salt);
+    require!(expected == bid.commitment, PgError::InvalidBidCommitment);
+
+    // Deposit must back the full bid for the whole lot
+    let bid_total = amount
+        .checked_mul(listing.quantity_total)
+        .ok_or(PgError::Overflow)?;
+    require!(bid_total <= bid.deposit, PgError::InvalidAmount);
+
+    // NOW safe to enter reentrancy guard - all validation passed (no fallible ops below)
+    let game = &mut ctx.accounts.game;
+    let listing = &mut ctx.accounts.listing;
+    let bid = &mut ctx.accounts.commitment;
+    enter_execution_game(game)?;
+
+    bid.revealed = true;
+    bid.revealed_amount = amount;
+
+    // Highest valid reveal wins; ties go to the earlier reveal
+    let meets_reserve = listing.reserve_price == 0 || amount >= listing.reserve_price;
+    let is_highest = meets_reserve && amount > listing.highest_bid;
+    if is_highest {
+        // Previous leader becomes the runner-up (second-price settlement)
+        listing.second_highest_bid = listing.highest_bid;
+        listing.highest_bid = amount;
+        listing.highest_bidder = bid.bidder;
+    } else if meets_reserve && amount > listing.second_highest_bid {
+        listing.second_highest_bid = amount;
+    }
+    listing.updated_at = now;
+
+    emit!(SealedBidRevealed {
+        listing: listing.key(),
+        game: game.key(),
+        bidder: bid.bidder,
+        amount,
+        is_highest,
+    });
+
+    exit_execution_game(game);
+    Ok(())
+}
+
+/// Release a sealed-bid deposit and close the commitment.
+///
+/// - Cancelled listing: full refund
+/// - Not revealed: `SEALED_BID_FORFEIT_BPS` of the deposit goes to the seller, rest refunded
+/// - Revealed, not winner: full refund
+/// - Winner: deposit minus the settled amount, once the listing is Settled; full refund if the
+///   listing is still unsettled `SEALED_BID_SETTLEMENT_WINDOW_SECS` after the reveal window
+///
+/// # Errors
+/// - `PgError::InvalidTime` if the reveal window has not closed (non-cancelled listings)
+/// - `PgError::InvalidListingStatus` if the winner claims before settlement and before the
+///   settlement deadline
+/// - `PgError::InsufficientCredits` if locked credits don't cover the release
+pub fn claim_sealed_bid_deposit(ctx: Context<ClaimSealedBidDeposit>) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let game = &ctx.accounts.game;
+    let listing = &ctx.accounts.listing;
+    let bid = &ctx.accounts.commitment;
+
+    require!(listing.game == game.key(), PgError::Unauthorized);
+    require!(bid.listing == listing.key(), PgError::Unauthorized);
+    require!(bid.bidder == ctx.accounts.bidder.key(), PgError::Unauthorized);
+    require!(
+        ctx.accounts.bidder_ledger.authority == bid.bidder,
+        PgError::Unauthorized
+    );
+    require!(
+        ctx.accounts.seller_ledger.authority == listing.seller,
+        PgError::Unauthorized
+    );
+
+    let (refund, forfeited) = if listing.status == ListingStatus::Cancelled {
+        (bid.deposit, 0u64)
+    } else {
+        let now = Clock::get()?.unix_timestamp;
+        let reveal_end = listing
+            .end_time
+            .checked_add(SEALED_BID_REVEAL_WINDOW_SECS)
+            .ok_or(PgError::Overflow)?;
+        require!(now >= reveal_end, PgError::InvalidTime);
+
+        if !bid.revealed {
+            let forfeited = bid
+                .deposit
+                .checked_mul(u64::from(SEALED_BID_FORFEIT_BPS))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+            let refund = bid
+                .deposit
+                .checked_sub(forfeited)
+                .ok_or(PgError::Overflow)?;
+            (refund, forfeited)
+        } else if listing.highest_bidder == bid.bidder && listing.highest_bid > 0 {
+            if listing.status == ListingStatus::Settled {
+                // Winner: settlement already consumed highest_bid * quantity_total from locked
+                let paid = listing
+                    .highest_bid
+                    .checked_mul(listing.quantity_total)
+                    .ok_or(PgError::Overflow)?;
+                let refund = bid.deposit.checked_sub(paid).ok_or(PgError::Overflow)?;
+                (refund, 0u64)
+            } else {
+                // Never settled: past the deadline finalize_auction_settlement rejects the
+                // listing, so the whole deposit goes back
+                let settle_deadline = reveal_end
+                    .checked_add(SEALED_BID_SETTLEMENT_WINDOW_SECS)
+                    .ok_or(PgError::Overflow)?;
+                require!(now >= settle_deadline, PgError::InvalidListingStatus);
+                (bid.deposit, 0u64)
+            }
+        } else {
+            (bid.deposit, 0u64)
+        }
+    };
+
+    let release = refund.checked_add(forfeited).ok_or(PgError::Overflow)?;
+    require!(
+        ctx.accounts.bidder_ledger.locked >= release,
+        PgError::InsufficientCredits
+    );
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let bidder_ledger = &mut ctx.accounts.bidder_ledger;
+    let seller_ledger = &mut ctx.accounts.seller_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        bidder_ledger.locked = bidder_ledger
+            .locked
+            .checked_sub(release)
+            .ok_or(PgError::Overflow)?;
+        bidder_ledger.available = bidder_ledger
+            .available
+            .checked_add(refund)
+            .ok_or(PgError::Overflow)?;
+
+        if forfeited > 0 {
+            seller_ledger.available = seller_ledger
+                .available
+                .checked_add(forfeited)
+                .ok_or(PgError::Overflow)?;
+        }
+
+        emit!(SealedBidDepositClaimed {
+            listing: ctx.accounts.listing.key(),
+            game: game.key(),
+            bidder: bidder_ledger.authority,
+            refunded: refund,
+            forfeited,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct CommitSealedBid<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_ledger: Account<'info, PlayerLedger>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + std::mem::size_of::<SealedBidCommitment>(),
+        seeds = [SEALED_BID_SEED, listing.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub commitment: Account<'info, SealedBidCommitment>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// Guardian co-signature for spends above the bidder's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealSealedBid<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        seeds = [SEALED_BID_SEED, listing.key().as_ref(), bidder.key().as_ref()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, SealedBidCommitment>,
+
+    pub bidder: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSealedBidDeposit<'info> {
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameConfig>,
+
+    pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [SEALED_BID_SEED, listing.key().as_ref(), bidder.key().as_ref()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, SealedBidCommitment>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_ledger: Account<'info, PlayerLedger>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), listing.seller.as_ref()],
+        bump
+    )]
+    pub seller_ledger: Account<'info, PlayerLedger>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_bid_commitment_binds_every_input() {
+        let listing = Pubkey::new_unique();
+        let bidder = Pubkey::new_unique();
+        let salt = [7u8; 32];
+        let commitment = sealed_bid_commitment(&listing, &bidder, 1_500, &salt);
+
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(listing.as_ref());
+        preimage.extend_from_slice(bidder.as_ref());
+        preimage.extend_from_slice(&1_500u64.to_le_bytes());
+        preimage.extend_from_slice(&salt);
+        assert_eq!(commitment, keccak::hash(&preimage).to_bytes());
+
+        // Another bidder can't replay the commitment, and amount/salt must match exactly
+        let other = Pubkey::new_unique();
+        for tampered in [
+            sealed_bid_commitment(&other, &bidder, 1_500, &salt),
+            sealed_bid_commitment(&listing, &other, 1_500, &salt),
+            sealed_bid_commitment(&listing, &bidder, 1_501, &salt),
+            sealed_bid_commitment(&listing, &bidder, 1_500, &[8u8; 32]),
+        ] {
+            assert_ne!(commitment, tampered);
+        }
+    }
+}
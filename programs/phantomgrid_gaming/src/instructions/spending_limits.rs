@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    GameConfig, PgError, PlayerLedger, SelfExclusionSet, SpendingLimitsUpdated, GAME_SEED,
+    LEDGER_SEED, MAX_SELF_EXCLUSION_SECS,
+};
+
+// ======================================================================
+// SPENDING LIMIT INSTRUCTIONS
+// ======================================================================
+
+/// Set the ledger's spending caps and guardian.
+///
+/// Once a guardian is set, every change (including removing the guardian) must be co-signed
+/// by the current guardian. Rolling-window history is kept across changes.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if the signer doesn't own the ledger
+/// - `PgError::GuardianApprovalRequired` if a guardian is set and hasn't co-signed
+/// - `PgError::InvalidAmount` if a weekly cap is set below the daily cap
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn set_spending_limits(
+    ctx: Context<SetSpendingLimits>,
+    daily_cap: u64,
+    weekly_cap: u64,
+    guardian: Pubkey,
+    guardian_threshold: u64,
+) -> Result<()> {
+    let ledger = &mut ctx.accounts.player_ledger;
+    require!(
+        ledger.authority == ctx.accounts.player.key(),
+        PgError::Unauthorized
+    );
+
+    let current_guardian = ledger.limits.guardian;
+    if current_guardian != Pubkey::default() {
+        require!(
+            ctx.accounts.guardian.as_ref().map(Signer::key) == Some(current_guardian),
+            PgError::GuardianApprovalRequired
+        );
+    }
+    if daily_cap > 0 && weekly_cap > 0 {
+        require!(weekly_cap >= daily_cap, PgError::InvalidAmount);
+    }
+
+    ledger.limits.daily_cap = daily_cap;
+    ledger.limits.weekly_cap = weekly_cap;
+    ledger.limits.guardian = guardian;
+    ledger.limits.guardian_threshold = guardian_threshold;
+
+    emit!(SpendingLimitsUpdated {
+        ledger: ledger.key(),
+        game: ledger.game,
+        player: ledger.authority,
+        daily_cap,
+        weekly_cap,
+        guardian,
+        guardian_threshold,
+    });
+
+    Ok(())
+}
+
+/// Start (or extend) a self-exclusion cool-off: no buying or bidding for `duration_secs`.
+///
+/// A cool-off can only be extended, never shortened. Withdrawals stay available.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if the signer doesn't own the ledger
+/// - `PgError::InvalidTime` if `duration_secs` is not in `1..=MAX_SELF_EXCLUSION_SECS`
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+pub fn self_exclude(ctx: Context<SelfExclude>, duration_secs: i64) -> Result<()> {
+    require!(
+        duration_secs > 0 && duration_secs <= MAX_SELF_EXCLUSION_SECS,
+        PgError::InvalidTime
+    );
+
+    let ledger = &mut ctx.accounts.player_ledger;
+    require!(
+        ledger.authority == ctx.accounts.player.key(),
+        PgError::Unauthorized
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let until = now.checked_add(duration_secs).ok_or(PgError::Overflow)?;
+    ledger.limits.excluded_until = ledger.limits.excluded_until.max(until);
+
+    emit!(SelfExclusionSet {
+        ledger: ledger.key(),
+        game: ledger.game,
+        player: ledger.authority,
+        excluded_until: ledger.limits.excluded_until,
+    });
+
+    Ok(())
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct SetSpendingLimits<'info> {
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_ledger: Box<Account<'info, PlayerLedger>>,
+
+    pub player: Signer<'info>,
+
+    /// Current guardian (required when the ledger has one)
+    pub guardian: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SelfExclude<'info> {
+    #[account(
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub player_ledger: Box<Account<'info, PlayerLedger>>,
+
+    pub player: Signer<'info>,
+}
@@ -0,0 +1,1152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::bundle::{
+    close_component_escrow, create_component_escrow, load_bundle_component, BundleComponent,
+    BUNDLE_ACCOUNTS_PER_ITEM,
+};
+use crate::instructions::game_roles::is_owner_or_role;
+use crate::{
+    enter_execution_game, exit_execution_game, instructions, metadata, GameConfig, GameRole,
+    GameRoles, GlobalConfig, ListingStatus, PgError, PlayerLedger, RoyaltyPaid, RoyaltySplit,
+    SwapOffer, SwapOfferAccepted, SwapOfferCancelled, SwapOfferCreated, SwapParty, BPS_DENOM,
+    CONFIG_SEED, GAME_ROLES_SEED, GAME_SEED, LEDGER_SEED, MAX_BULK_QTY, MAX_LISTING_DURATION_SECS,
+    MAX_SWAP_ITEMS, SWAP_OFFER_SEED,
+};
+
+// ======================================================================
+// ITEM SWAP / BARTER INSTRUCTIONS
+// ======================================================================
+
+/// Accounts passed per taker item in `accept_swap_offer` `remaining_accounts`:
+/// `[item_mint, taker_item_ata, maker_receive_ata]`
+pub const SWAP_TAKER_ACCOUNTS_PER_ITEM: usize = 3;
+
+/// One taker item leg taken from `remaining_accounts`
+struct SwapTakerLeg<'info> {
+    item_mint: InterfaceAccount<'info, Mint>,
+    taker_item_ata: InterfaceAccount<'info, TokenAccount>,
+    maker_receive_ata: InterfaceAccount<'info, TokenAccount>,
+}
+
+/// Load taker leg `index` and check the mint program and both token accounts.
+fn load_taker_leg<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    index: usize,
+    taker: &Pubkey,
+    maker: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<SwapTakerLeg<'info>> {
+    let base = index
+        .checked_mul(SWAP_TAKER_ACCOUNTS_PER_ITEM)
+        .ok_or(PgError::Overflow)?;
+    let item_mint = InterfaceAccount::<Mint>::try_from(&accounts[base])?;
+    require!(
+        item_mint.to_account_info().owner == token_program,
+        PgError::Unauthorized
+    );
+
+    let taker_item_ata = InterfaceAccount::<TokenAccount>::try_from(&accounts[base + 1])?;
+    let maker_receive_ata = InterfaceAccount::<TokenAccount>::try_from(&accounts[base + 2])?;
+    require!(
+        taker_item_ata.mint == item_mint.key() && maker_receive_ata.mint == item_mint.key(),
+        PgError::ItemMintMismatch
+    );
+    require!(taker_item_ata.owner == *taker, PgError::Unauthorized);
+    require!(maker_receive_ata.owner == *maker, PgError::Unauthorized);
+
+    Ok(SwapTakerLeg {
+        item_mint,
+        taker_item_ata,
+        maker_receive_ata,
+    })
+}
+
+/// Load the escrowed maker items of `offer` from `accounts`, in offer order
+/// (`[item_mint, escrow_item_ata, party_item_ata]` per item).
+fn load_maker_items<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    offer: &SwapOffer,
+    offer_key: &Pubkey,
+    party: &Pubkey,
+    token_program: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Vec<BundleComponent<'info>>> {
+    let count = usize::from(offer.maker_item_count);
+    require!(
+        accounts.len() >= count * BUNDLE_ACCOUNTS_PER_ITEM,
+        PgError::ItemMintMismatch
+    );
+    (0..count)
+        .map(|index| {
+            let item = load_bundle_component(
+                accounts,
+                index,
+                offer_key,
+                party,
+                token_program,
+                program_id,
+            )?;
+            require!(
+                item.item_mint.key() == offer.maker_mints[index],
+                PgError::ItemMintMismatch
+            );
+            Ok(item)
+        })
+        .collect()
+}
+
+/// Move escrowed maker items to their party token accounts and close the drained escrows to
+/// `maker`, who paid their rent (game PDA signs).
+fn release_maker_items<'info>(
+    items: &[BundleComponent<'info>],
+    quantities: &[u64; MAX_SWAP_ITEMS],
+    maker: &AccountInfo<'info>,
+    game: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    for (item, quantity) in items.iter().zip(quantities.iter()) {
+        let cpi_accounts = token_interface::TransferChecked {
+            from: item.escrow_item_ata.clone(),
+            mint: item.item_mint.to_account_info(),
+            to: item.party_item_ata.to_account_info(),
+            authority: game.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, *quantity, item.item_mint.decimals)?;
+        close_component_escrow(item.escrow_item_ata, maker, game, token_program, signer)?;
+    }
+    Ok(())
+}
+
+/// Create a swap offer and escrow the maker's items.
+///
+/// `remaining_accounts` holds `[item_mint, escrow_item_ata, maker_item_ata]` per maker item,
+/// in the same order as `maker_quantities`; each item gets its own escrow PDA. `taker ==
+/// Pubkey::default()` opens the offer to any player. A top-up paid by the maker is locked in
+/// the maker's ledger until the offer settles or is cancelled. To counter an existing offer,
+/// pass it as `parent_offer`; the counter is then addressed to the original maker.
+///
+/// A top-up buys the other party's items, so it carries their creator royalty: with a
+/// top-up, the Metaplex metadata PDAs of the items it pays for (maker items when the taker
+/// pays, `taker_mints` when the maker pays) follow the item accounts, in side order, and are
+/// resolved under the game's `RoyaltyEnforcement` policy. Swaps carry no maker-chosen royalty.
+///
+/// # Errors
+/// - `PgError::ListingsPaused` if new listings are paused
+/// - `PgError::InvalidAmount` if a side has no or more than `MAX_SWAP_ITEMS` items, or a
+///   quantity is 0 or exceeds `MAX_BULK_QTY`
+/// - `PgError::ItemMintMismatch` if a mint is repeated on one side or a token account has the wrong mint
+/// - `PgError::InsufficientQuantity` if the maker doesn't hold an item's quantity
+/// - `PgError::InsufficientCredits` if the maker doesn't have enough available credits for its top-up
+/// - `PgError::SelfExcluded` / `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired`
+///   if the maker's spending limits don't allow its top-up
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded`
+///   if the top-up exceeds the maker's KYC tier limits
+/// - `PgError::InvalidTime` if `expires_at` is in the past or beyond `MAX_LISTING_DURATION_SECS`
+/// - `PgError::InvalidListingStatus` if the countered offer is no longer active
+/// - `PgError::Unauthorized` if the maker may not counter the given offer
+/// - `PgError::MixedItemRoyalties` if the paid-for items resolve to different creator royalties
+/// - `PgError::FeeTooHigh` if royalty plus fees exceed 100%
+#[allow(clippy::too_many_arguments)] // Required for swap offer parameters
+#[allow(clippy::too_many_lines)] // Per-item escrow creation
+pub fn create_swap_offer<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateSwapOffer<'info>>,
+    offer_id: u64,
+    taker: Pubkey,
+    maker_quantities: Vec<u64>,
+    taker_mints: Vec<Pubkey>,
+    taker_quantities: Vec<u64>,
+    top_up: u64,
+    top_up_payer: SwapParty,
+    expires_at: i64,
+) -> Result<()> {
+    let maker_count = maker_quantities.len();
+    let taker_count = taker_mints.len();
+    require!(
+        (1..=MAX_SWAP_ITEMS).contains(&maker_count),
+        PgError::InvalidAmount
+    );
+    require!(
+        (1..=MAX_SWAP_ITEMS).contains(&taker_count),
+        PgError::InvalidAmount
+    );
+    require!(
+        taker_quantities.len() == taker_count,
+        PgError::InvalidAmount
+    );
+    let item_accounts = maker_count * BUNDLE_ACCOUNTS_PER_ITEM;
+    require!(
+        ctx.remaining_accounts.len() >= item_accounts,
+        PgError::InvalidAmount
+    );
+    for quantity in maker_quantities.iter().chain(taker_quantities.iter()) {
+        require!(
+            (1..=MAX_BULK_QTY).contains(quantity),
+            PgError::InvalidAmount
+        );
+    }
+    for (index, mint) in taker_mints.iter().enumerate() {
+        require!(
+            *mint != Pubkey::default() && !taker_mints[..index].contains(mint),
+            PgError::ItemMintMismatch
+        );
+    }
+
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    require!(!cfg.paused_new, PgError::ListingsPaused);
+    require!(!game.paused_new, PgError::ListingsPaused);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at > now, PgError::InvalidTime);
+    let duration = expires_at.checked_sub(now).ok_or(PgError::Overflow)?;
+    require!(duration <= MAX_LISTING_DURATION_SECS, PgError::InvalidTime);
+
+    let maker = ctx.accounts.maker.key();
+    require!(taker != maker, PgError::Unauthorized);
+    let maker_ledger = &ctx.accounts.maker_ledger;
+    require!(maker_ledger.authority == maker, PgError::Unauthorized);
+    maker_ledger.check_kyc_trade(cfg, game.kyc_required, top_up, now)?;
+    if top_up_payer == SwapParty::Maker {
+        require!(
+            maker_ledger.available >= top_up,
+            PgError::InsufficientCredits
+        );
+        require!(!maker_ledger.limits.is_excluded(now), PgError::SelfExcluded);
+    }
+
+    // Counter-offer: must answer a live offer the maker was allowed to take
+    let counter_to = match ctx.accounts.parent_offer.as_deref() {
+        Some(parent) => {
+            require!(parent.game == game.key(), PgError::Unauthorized);
+            require!(
+                parent.status == ListingStatus::Active,
+                PgError::InvalidListingStatus
+            );
+            require!(now < parent.expires_at, PgError::InvalidTime);
+            require!(
+                parent.taker == Pubkey::default() || parent.taker == maker,
+                PgError::Unauthorized
+            );
+            require!(taker == parent.maker, PgError::Unauthorized);
+            parent.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    // Validate every maker item up front (distinct mints, maker holds the quantity)
+    let offer_key = ctx.accounts.offer.key();
+    let token_program_id = ctx.accounts.token_program.key();
+    let mut items: Vec<BundleComponent<'info>> = Vec::with_capacity(maker_count);
+    for (index, quantity) in maker_quantities.iter().enumerate() {
+        let item = load_bundle_component(
+            ctx.remaining_accounts,
+            index,
+            &offer_key,
+            &maker,
+            &token_program_id,
+            ctx.program_id,
+        )?;
+        require!(
+            items
+                .iter()
+                .all(|i| i.item_mint.key() != item.item_mint.key()),
+            PgError::ItemMintMismatch
+        );
+        require!(
+            item.party_item_ata.amount >= *quantity,
+            PgError::InsufficientQuantity
+        );
+        items.push(item);
+    }
+
+    // Creator royalties on the top-up come from the items it pays for (per-game policy)
+    let royalty_split = ctx.accounts.royalty_split.as_deref().map(|split| &**split);
+    let (royalty_recipient, royalty_bps) = if top_up > 0 {
+        let paid_mints: Vec<Pubkey> = match top_up_payer {
+            SwapParty::Taker => items.iter().map(|item| item.item_mint.key()).collect(),
+            SwapParty::Maker => taker_mints.clone(),
+        };
+        let metadata_accounts = &ctx.remaining_accounts[item_accounts..];
+        require!(
+            metadata_accounts.len() <= paid_mints.len(),
+            PgError::InvalidAmount
+        );
+        let royalty_items: Vec<(Pubkey, Option<&AccountInfo>)> = paid_mints
+            .iter()
+            .enumerate()
+            .map(|(index, mint)| (*mint, metadata_accounts.get(index)))
+            .collect();
+        metadata::resolve_items_royalty(
+            game.royalty_enforcement,
+            &royalty_items,
+            royalty_split,
+            Pubkey::default(),
+            0,
+        )?
+    } else {
+        require!(
+            ctx.remaining_accounts.len() == item_accounts,
+            PgError::InvalidAmount
+        );
+        (Pubkey::default(), 0)
+    };
+    let total_fees = u64::from(royalty_bps)
+        .checked_add(u64::from(game.fee_bps))
+        .ok_or(PgError::Overflow)?
+        .checked_add(u64::from(cfg.protocol_fee_bps))
+        .ok_or(PgError::Overflow)?;
+    require!(total_fees <= BPS_DENOM, PgError::FeeTooHigh);
+    let royalty_split = match &ctx.accounts.royalty_split {
+        Some(split) => {
+            require!(split.game == game.key(), PgError::RoyaltySplitMismatch);
+            require!(royalty_bps > 0, PgError::InvalidRoyalty);
+            require!(
+                split.recipients[0] == royalty_recipient,
+                PgError::RoyaltySplitMismatch
+            );
+            split.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let maker_ledger = &mut ctx.accounts.maker_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        if top_up_payer == SwapParty::Maker {
+            // Spending limits: the locked top-up counts as spent
+            let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+            maker_ledger.limits.record_spend(top_up, now, approver)?;
+            maker_ledger.record_kyc_trade(cfg, game.kyc_required, top_up, now)?;
+
+            maker_ledger.available = maker_ledger
+                .available
+                .checked_sub(top_up)
+                .ok_or(PgError::Overflow)?;
+            maker_ledger.locked = maker_ledger
+                .locked
+                .checked_add(top_up)
+                .ok_or(PgError::Overflow)?;
+        }
+
+        let offer = &mut ctx.accounts.offer;
+        offer.game = game.key();
+        offer.maker = maker;
+        offer.offer_id = offer_id;
+        offer.taker = taker;
+        offer.counter_to = counter_to;
+        offer.status = ListingStatus::Active;
+        offer.maker_item_count = u8::try_from(maker_count).map_err(|_| PgError::Overflow)?;
+        offer.taker_item_count = u8::try_from(taker_count).map_err(|_| PgError::Overflow)?;
+        offer.taker_mints[..taker_count].copy_from_slice(&taker_mints);
+        offer.taker_quantities[..taker_count].copy_from_slice(&taker_quantities);
+        offer.top_up = top_up;
+        offer.top_up_payer = top_up_payer;
+        offer.royalty_recipient = royalty_recipient;
+        offer.royalty_bps = royalty_bps;
+        offer.royalty_split = royalty_split;
+        offer.expires_at = expires_at;
+        offer.created_at = now;
+        offer.updated_at = now;
+        offer.bump = ctx.bumps.offer;
+
+        for (index, (item, quantity)) in items.iter().zip(maker_quantities.iter()).enumerate() {
+            create_component_escrow(
+                item,
+                &offer_key,
+                &ctx.accounts.maker.to_account_info(),
+                &game.to_account_info(),
+                &ctx.accounts.token_program,
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+
+            // Escrow items: maker -> escrow
+            let cpi_accounts = token_interface::TransferChecked {
+                from: item.party_item_ata.to_account_info(),
+                mint: item.item_mint.to_account_info(),
+                to: item.escrow_item_ata.clone(),
+                authority: ctx.accounts.maker.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, *quantity, item.item_mint.decimals)?;
+
+            offer.maker_mints[index] = item.item_mint.key();
+            offer.maker_quantities[index] = *quantity;
+        }
+
+        emit!(SwapOfferCreated {
+            offer: offer_key,
+            game: game.key(),
+            maker,
+            taker,
+            counter_to,
+            maker_mints: offer.maker_mints[..maker_count].to_vec(),
+            maker_quantities,
+            taker_mints,
+            taker_quantities,
+            top_up,
+            top_up_payer,
+            expires_at,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Accept a swap offer.
+///
+/// `remaining_accounts` holds `[item_mint, escrow_item_ata, taker_receive_ata]` per maker
+/// item, then `[item_mint, taker_item_ata, maker_receive_ata]` per taker item, both in offer
+/// order. Taker items go straight to the maker, escrowed maker items go to the taker and the
+/// drained escrows are closed to the maker. The `top_up` moves from `top_up_payer` to the
+/// other party; game/protocol fees and the offer's creator royalty are charged on the top-up
+/// only.
+///
+/// Accepting a counter-offer cancels the offer it countered (made by this taker): pass it
+/// next, followed by `[item_mint, escrow_item_ata, taker_item_ata]` per item of that offer
+/// while it is still Active. Its items and any locked top-up go back to the taker. The other
+/// royalty split recipients' ledgers come last when the offer has a split.
+///
+/// # Errors
+/// - `PgError::InvalidListingStatus` if the offer is not Active
+/// - `PgError::InvalidTime` if the offer has expired
+/// - `PgError::Unauthorized` if the offer is addressed to a different taker
+/// - `PgError::ItemMintMismatch` if item accounts are missing, reordered or use the wrong mint
+/// - `PgError::InsufficientCredits` if the top-up payer doesn't have enough credits
+/// - `PgError::SelfExcluded` / `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired`
+///   if the top-up payer's spending limits don't allow it
+/// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded`
+///   if the top-up exceeds the taker's KYC tier limits
+/// - `PgError::RoyaltySplitMismatch` if the offer's royalty split is missing
+/// - `PgError::FeeTooHigh` if fees exceed 50% of the top-up
+/// - `PgError::InvalidSellerAmount` if the top-up recipient would receive nothing after fees
+#[allow(clippy::too_many_lines)] // Two item legs, currency leg and counter cancellation
+pub fn accept_swap_offer<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptSwapOffer<'info>>,
+) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let offer = &ctx.accounts.offer;
+
+    require!(offer.game == game.key(), PgError::Unauthorized);
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+    require!(
+        offer.status == ListingStatus::Active,
+        PgError::InvalidListingStatus
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now < offer.expires_at, PgError::InvalidTime);
+
+    let taker = ctx.accounts.taker.key();
+    require!(taker != offer.maker, PgError::Unauthorized);
+    require!(
+        offer.taker == Pubkey::default() || offer.taker == taker,
+        PgError::Unauthorized
+    );
+
+    let taker_ledger = &ctx.accounts.taker_ledger;
+    let maker_ledger = &ctx.accounts.maker_ledger;
+    require!(taker_ledger.authority == taker, PgError::Unauthorized);
+    taker_ledger.check_kyc_trade(cfg, game.kyc_required, offer.top_up, now)?;
+    require!(maker_ledger.authority == offer.maker, PgError::Unauthorized);
+    match offer.top_up_payer {
+        SwapParty::Taker => {
+            require!(
+                taker_ledger.available >= offer.top_up,
+                PgError::InsufficientCredits
+            );
+            if offer.top_up > 0 {
+                require!(!taker_ledger.limits.is_excluded(now), PgError::SelfExcluded);
+            }
+        }
+        SwapParty::Maker => {
+            require!(
+                maker_ledger.locked >= offer.top_up,
+                PgError::InsufficientCredits
+            );
+            // Counted as spent when locked; settlement stops while the maker is excluded
+            if offer.top_up > 0 {
+                require!(!maker_ledger.limits.is_excluded(now), PgError::SelfExcluded);
+            }
+        }
+    }
+
+    // Item legs from remaining_accounts: maker items, then taker items, then the countered offer
+    let offer_key = offer.key();
+    let token_program_id = ctx.accounts.token_program.key();
+    let maker_len = usize::from(offer.maker_item_count) * BUNDLE_ACCOUNTS_PER_ITEM;
+    let taker_count = usize::from(offer.taker_item_count);
+    let legs_len = maker_len + taker_count * SWAP_TAKER_ACCOUNTS_PER_ITEM;
+    require!(
+        ctx.remaining_accounts.len() >= legs_len,
+        PgError::ItemMintMismatch
+    );
+    let (leg_accounts, parent_accounts) = ctx.remaining_accounts.split_at(legs_len);
+    let (maker_accounts, taker_accounts) = leg_accounts.split_at(maker_len);
+
+    let maker_items = load_maker_items(
+        maker_accounts,
+        offer,
+        &offer_key,
+        &taker,
+        &token_program_id,
+        ctx.program_id,
+    )?;
+    let mut taker_legs: Vec<SwapTakerLeg<'info>> = Vec::with_capacity(taker_count);
+    for index in 0..taker_count {
+        let leg = load_taker_leg(
+            taker_accounts,
+            index,
+            &taker,
+            &offer.maker,
+            &token_program_id,
+        )?;
+        require!(
+            leg.item_mint.key() == offer.taker_mints[index],
+            PgError::ItemMintMismatch
+        );
+        require!(
+            leg.taker_item_ata.amount >= offer.taker_quantities[index],
+            PgError::InsufficientQuantity
+        );
+        taker_legs.push(leg);
+    }
+
+    // Countered offer (made by this taker): cancelled with this acceptance while still Active
+    let mut parent = None;
+    let mut split_accounts = parent_accounts;
+    if offer.counter_to != Pubkey::default() {
+        let (parent_info, rest) = parent_accounts.split_first().ok_or(PgError::Unauthorized)?;
+        require!(parent_info.key() == offer.counter_to, PgError::Unauthorized);
+        let parent_offer = Account::<SwapOffer>::try_from(parent_info)?;
+        require!(parent_offer.game == game.key(), PgError::Unauthorized);
+        require!(parent_offer.maker == taker, PgError::Unauthorized);
+        split_accounts = rest;
+        if parent_offer.status == ListingStatus::Active {
+            let parent_len = usize::from(parent_offer.maker_item_count) * BUNDLE_ACCOUNTS_PER_ITEM;
+            require!(rest.len() >= parent_len, PgError::ItemMintMismatch);
+            let (parent_item_accounts, rest) = rest.split_at(parent_len);
+            split_accounts = rest;
+            if parent_offer.top_up_payer == SwapParty::Maker {
+                require!(
+                    taker_ledger.locked >= parent_offer.top_up,
+                    PgError::InsufficientCredits
+                );
+            }
+            let parent_items = load_maker_items(
+                parent_item_accounts,
+                &parent_offer,
+                &parent_info.key(),
+                &taker,
+                &token_program_id,
+                ctx.program_id,
+            )?;
+            parent = Some((parent_offer, parent_items));
+        }
+    }
+
+    // Multi-recipient royalties: the split must be the one recorded on the offer
+    if offer.royalty_split != Pubkey::default() {
+        let split = ctx
+            .accounts
+            .royalty_split
+            .as_ref()
+            .ok_or(PgError::RoyaltySplitMismatch)?;
+        require!(
+            split.key() == offer.royalty_split,
+            PgError::RoyaltySplitMismatch
+        );
+    }
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let offer = &mut ctx.accounts.offer;
+    let taker_ledger = &mut ctx.accounts.taker_ledger;
+    let maker_ledger = &mut ctx.accounts.maker_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+
+        // Countered offer: return its items and unlock its top-up before settling this one
+        if let Some((parent_offer, parent_items)) = parent.as_mut() {
+            release_maker_items(
+                parent_items,
+                &parent_offer.maker_quantities,
+                &ctx.accounts.taker.to_account_info(),
+                &game.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                signer,
+            )?;
+            let refunded = if parent_offer.top_up_payer == SwapParty::Maker {
+                parent_offer.top_up
+            } else {
+                0
+            };
+            taker_ledger.locked = taker_ledger
+                .locked
+                .checked_sub(refunded)
+                .ok_or(PgError::Overflow)?;
+            taker_ledger.available = taker_ledger
+                .available
+                .checked_add(refunded)
+                .ok_or(PgError::Overflow)?;
+
+            parent_offer.status = ListingStatus::Cancelled;
+            parent_offer.updated_at = now;
+            parent_offer.exit(ctx.program_id)?;
+
+            emit!(SwapOfferCancelled {
+                offer: parent_offer.key(),
+                game: game.key(),
+                maker: taker,
+                top_up_refunded: refunded,
+            });
+        }
+
+        // Currency leg: fees apply to the top-up only (item legs are fee-free)
+        let top_up = offer.top_up;
+        let mut game_fee = 0u64;
+        let mut protocol_fee = 0u64;
+        let mut royalty_amount = 0u64;
+        if top_up > 0 {
+            game_fee = top_up
+                .checked_mul(u64::from(game.fee_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+            protocol_fee = top_up
+                .checked_mul(u64::from(cfg.protocol_fee_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+
+            royalty_amount = top_up
+                .checked_mul(u64::from(offer.royalty_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+
+            // CRITICAL: Ensure total fees don't exceed 50% of the top-up
+            let total_fees = game_fee
+                .checked_add(protocol_fee)
+                .ok_or(PgError::Overflow)?
+                .checked_add(royalty_amount)
+                .ok_or(PgError::Overflow)?;
+            let max_allowed_fees = top_up.checked_div(2).ok_or(PgError::Overflow)?; // 50% max
+            require!(total_fees <= max_allowed_fees, PgError::FeeTooHigh);
+
+            let payee_amount = top_up.checked_sub(total_fees).ok_or(PgError::Overflow)?;
+            require!(payee_amount > 0, PgError::InvalidSellerAmount);
+
+            match offer.top_up_payer {
+                SwapParty::Taker => {
+                    // Spending limits: the top-up counts as spent
+                    let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+                    taker_ledger.limits.record_spend(top_up, now, approver)?;
+                    taker_ledger.record_kyc_trade(cfg, game.kyc_required, top_up, now)?;
+
+                    taker_ledger.available = taker_ledger
+                        .available
+                        .checked_sub(top_up)
+                        .ok_or(PgError::Overflow)?;
+                    maker_ledger.available = maker_ledger
+                        .available
+                        .checked_add(payee_amount)
+                        .ok_or(PgError::Overflow)?;
+                }
+                SwapParty::Maker => {
+                    maker_ledger.locked = maker_ledger
+                        .locked
+                        .checked_sub(top_up)
+                        .ok_or(PgError::Overflow)?;
+                    taker_ledger.available = taker_ledger
+                        .available
+                        .checked_add(payee_amount)
+                        .ok_or(PgError::Overflow)?;
+                }
+            }
+
+            // CRITICAL: Track protocol and game fees per-game
+            game.protocol_fees_accumulated = game
+                .protocol_fees_accumulated
+                .checked_add(protocol_fee)
+                .ok_or(PgError::Overflow)?;
+            game.accumulated_game_fees = game
+                .accumulated_game_fees
+                .checked_add(game_fee)
+                .ok_or(PgError::Overflow)?;
+        }
+
+        // CRITICAL: Only process royalties when royalty_amount > 0 (prevents griefing via init_if_needed)
+        if royalty_amount > 0 {
+            let royalty_ledger = &mut ctx.accounts.royalty_recipient_ledger;
+            if royalty_ledger.authority == Pubkey::default() {
+                // Initialize new ledger
+                royalty_ledger.game = game.key();
+                royalty_ledger.authority = offer.royalty_recipient;
+                royalty_ledger.available = 0;
+                royalty_ledger.locked = 0;
+                royalty_ledger.kyc_verified = false;
+                royalty_ledger.kyc_provider = Pubkey::default();
+                royalty_ledger.kyc_verified_at = 0;
+                royalty_ledger.kyc_proof_hash = [0u8; 32];
+            }
+            require!(
+                royalty_ledger.authority == offer.royalty_recipient,
+                PgError::Unauthorized
+            );
+
+            // Split royalties: recipient 0 takes its share plus rounding dust
+            let royalty_shares = match &ctx.accounts.royalty_split {
+                Some(split) if offer.royalty_split != Pubkey::default() => {
+                    Some(split.shares(royalty_amount)?)
+                }
+                _ => None,
+            };
+            let primary_royalty = royalty_shares.map_or(royalty_amount, |shares| shares[0]);
+            royalty_ledger.available = royalty_ledger
+                .available
+                .checked_add(primary_royalty)
+                .ok_or(PgError::Overflow)?;
+
+            emit!(RoyaltyPaid {
+                listing: offer.key(),
+                game: game.key(),
+                recipient: offer.royalty_recipient,
+                amount: primary_royalty,
+            });
+
+            if let (Some(split), Some(shares)) = (&ctx.accounts.royalty_split, &royalty_shares) {
+                instructions::royalty_split::credit_royalty_split(
+                    split,
+                    shares,
+                    offer.key(),
+                    &mut [&mut **maker_ledger, &mut **taker_ledger],
+                    split_accounts,
+                    ctx.program_id,
+                )?;
+            }
+        }
+
+        // Taker legs: taker ATA -> maker ATA (taker signs)
+        for (leg, quantity) in taker_legs.iter().zip(offer.taker_quantities.iter()) {
+            let cpi_accounts = token_interface::TransferChecked {
+                from: leg.taker_item_ata.to_account_info(),
+                mint: leg.item_mint.to_account_info(),
+                to: leg.maker_receive_ata.to_account_info(),
+                authority: ctx.accounts.taker.to_account_info(),
+            };
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, *quantity, leg.item_mint.decimals)?;
+        }
+
+        // Maker legs: escrow -> taker ATA, escrows closed to the maker (game PDA signs)
+        release_maker_items(
+            &maker_items,
+            &offer.maker_quantities,
+            &ctx.accounts.maker.to_account_info(),
+            &game.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer,
+        )?;
+
+        offer.status = ListingStatus::Settled;
+        offer.taker = taker;
+        offer.updated_at = now;
+
+        emit!(SwapOfferAccepted {
+            offer: offer.key(),
+            game: game.key(),
+            maker: offer.maker,
+            taker,
+            top_up,
+            top_up_payer: offer.top_up_payer,
+            protocol_fee,
+            game_fee,
+            royalty_amount,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+/// Cancel a swap offer and return the escrowed items and any locked top-up to the maker.
+///
+/// `remaining_accounts` holds `[item_mint, escrow_item_ata, maker_item_ata]` per maker item,
+/// in offer order. Items ALWAYS go back to the maker, even if admin/governance calls this,
+/// and the drained escrows are closed to the maker.
+///
+/// # Errors
+/// - `PgError::InvalidListingStatus` if the offer is not Active
+/// - `PgError::Unauthorized` if caller is not the maker, game owner, listing moderator, admin,
+///   governance or server
+/// - `PgError::ItemMintMismatch` if item accounts are missing, reordered or use the wrong mint
+pub fn cancel_swap_offer<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelSwapOffer<'info>>,
+) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    let offer = &ctx.accounts.offer;
+
+    require!(offer.game == game.key(), PgError::Unauthorized);
+    require!(
+        offer.status == ListingStatus::Active,
+        PgError::InvalidListingStatus
+    );
+
+    // Only maker, game owner / listing moderator, admin, governance or server can cancel
+    let caller = ctx.accounts.caller.key();
+    let maker = offer.maker;
+    let allowed = caller == maker
+        || is_owner_or_role(
+            game,
+            ctx.accounts.roles.as_deref(),
+            &caller,
+            GameRole::ListingModerator,
+        )
+        || caller == cfg.admin
+        || caller == cfg.governance
+        || caller == cfg.server_authority;
+    require!(allowed, PgError::Unauthorized);
+
+    require!(
+        ctx.accounts.maker_ledger.authority == maker,
+        PgError::Unauthorized
+    );
+    let refunded = if offer.top_up_payer == SwapParty::Maker {
+        offer.top_up
+    } else {
+        0
+    };
+    require!(
+        ctx.accounts.maker_ledger.locked >= refunded,
+        PgError::InsufficientCredits
+    );
+
+    require!(
+        ctx.remaining_accounts.len()
+            == usize::from(offer.maker_item_count) * BUNDLE_ACCOUNTS_PER_ITEM,
+        PgError::ItemMintMismatch
+    );
+    let items = load_maker_items(
+        ctx.remaining_accounts,
+        offer,
+        &offer.key(),
+        &maker,
+        &ctx.accounts.token_program.key(),
+        ctx.program_id,
+    )?;
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let offer = &mut ctx.accounts.offer;
+    let maker_ledger = &mut ctx.accounts.maker_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        offer.status = ListingStatus::Cancelled;
+        offer.updated_at = now;
+
+        // Return escrowed items to maker
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+        let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+        let signer = &[seeds];
+        release_maker_items(
+            &items,
+            &offer.maker_quantities,
+            &ctx.accounts.maker.to_account_info(),
+            &game.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            signer,
+        )?;
+
+        // Unlock a maker top-up
+        maker_ledger.locked = maker_ledger
+            .locked
+            .checked_sub(refunded)
+            .ok_or(PgError::Overflow)?;
+        maker_ledger.available = maker_ledger
+            .available
+            .checked_add(refunded)
+            .ok_or(PgError::Overflow)?;
+
+        emit!(SwapOfferCancelled {
+            offer: offer.key(),
+            game: game.key(),
+            maker,
+            top_up_refunded: refunded,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct CreateSwapOffer<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), maker.key().as_ref()],
+        bump
+    )]
+    pub maker_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        init,
+        payer = maker,
+        seeds = [
+            SWAP_OFFER_SEED,
+            game.key().as_ref(),
+            maker.key().as_ref(),
+            &offer_id.to_le_bytes()
+        ],
+        bump,
+        space = 8 + std::mem::size_of::<SwapOffer>()
+    )]
+    pub offer: Box<Account<'info, SwapOffer>>,
+
+    /// Offer being countered (the counter is addressed to its maker)
+    pub parent_offer: Option<Box<Account<'info, SwapOffer>>>,
+
+    /// Multi-recipient royalty split, required when the paid-for items have several creators
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// Guardian co-signature for a top-up above the maker's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// NOTE: Box<> used to reduce stack frame size (Solana 4KB limit)
+#[derive(Accounts)]
+pub struct AcceptSwapOffer<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SWAP_OFFER_SEED,
+            game.key().as_ref(),
+            offer.maker.as_ref(),
+            &offer.offer_id.to_le_bytes()
+        ],
+        bump = offer.bump
+    )]
+    pub offer: Box<Account<'info, SwapOffer>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), offer.maker.as_ref()],
+        bump
+    )]
+    pub maker_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), taker.key().as_ref()],
+        bump
+    )]
+    pub taker_ledger: Box<Account<'info, PlayerLedger>>,
+
+    /// CHECK: Offer maker (receives the escrow rent); validated against offer.maker
+    #[account(mut, address = offer.maker)]
+    pub maker: UncheckedAccount<'info>,
+
+    /// CHECK: Royalty recipient ledger (only credited when the top-up carries a royalty)
+    #[account(
+        init_if_needed,
+        payer = taker,
+        seeds = [LEDGER_SEED, game.key().as_ref(), offer.royalty_recipient.as_ref()],
+        bump,
+        space = 8 + std::mem::size_of::<PlayerLedger>()
+    )]
+    pub royalty_recipient_ledger: Box<Account<'info, PlayerLedger>>,
+
+    /// Required when `offer.royalty_split` is set; other recipients' ledgers go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// Guardian co-signature for a top-up above the taker's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSwapOffer<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            SWAP_OFFER_SEED,
+            game.key().as_ref(),
+            offer.maker.as_ref(),
+            &offer.offer_id.to_le_bytes()
+        ],
+        bump = offer.bump
+    )]
+    pub offer: Box<Account<'info, SwapOffer>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), offer.maker.as_ref()],
+        bump
+    )]
+    pub maker_ledger: Box<Account<'info, PlayerLedger>>,
+
+    /// CHECK: Offer maker (receives the escrow rent); validated against offer.maker
+    #[account(mut, address = offer.maker)]
+    pub maker: UncheckedAccount<'info>,
+
+    pub caller: Signer<'info>,
+
+    /// Game roles (required when the caller acts as a `ListingModerator`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_pda_instructions;
+    use crate::instructions::bundle::bundle_escrow_address;
+    use crate::tests::leaked_account;
+    use anchor_lang::solana_program::system_instruction;
+    use anchor_spl::token_2022::spl_token_2022::state::{
+        Account as TokenAccountState, AccountState, Mint as MintState,
+    };
+    use solana_program::program_pack::Pack;
+
+    #[test]
+    fn maker_escrow_at_a_pre_funded_pda_is_adopted() {
+        let token_program = anchor_spl::token::ID;
+        let (offer, maker, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (escrow, _) = bundle_escrow_address(&offer, &mint, &crate::ID);
+
+        let mut mint_data = vec![0u8; MintState::LEN];
+        MintState {
+            is_initialized: true,
+            ..MintState::default()
+        }
+        .pack_into_slice(&mut mint_data);
+        let mut maker_ata_data = vec![0u8; TokenAccountState::LEN];
+        TokenAccountState {
+            mint,
+            owner: maker,
+            amount: 5,
+            state: AccountState::Initialized,
+            ..TokenAccountState::default()
+        }
+        .pack_into_slice(&mut maker_ata_data);
+
+        // Someone sent lamports to the predictable escrow address before the offer was made
+        let accounts: &'static [AccountInfo<'static>] = Box::leak(Box::new([
+            leaked_account(mint, 0, mint_data, token_program),
+            leaked_account(escrow, 1_000, Vec::new(), anchor_lang::system_program::ID),
+            leaked_account(Pubkey::new_unique(), 0, maker_ata_data, token_program),
+        ]));
+        let item =
+            load_bundle_component(accounts, 0, &offer, &maker, &token_program, &crate::ID).unwrap();
+        assert_eq!(item.escrow_item_ata.key(), escrow);
+
+        // The escrow is topped up, allocated and assigned instead of failing create_account
+        let rent = 2_039_280;
+        let space = TokenAccountState::LEN as u64;
+        assert_eq!(
+            create_pda_instructions(
+                &maker,
+                &escrow,
+                item.escrow_item_ata.lamports(),
+                rent,
+                space,
+                &token_program,
+            ),
+            vec![
+                system_instruction::transfer(&maker, &escrow, rent - 1_000),
+                system_instruction::allocate(&escrow, space),
+                system_instruction::assign(&escrow, &token_program),
+            ]
+        );
+    }
+}
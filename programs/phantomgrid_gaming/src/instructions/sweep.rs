@@ -0,0 +1,613 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+use crate::instructions::royalty_split::init_player_ledger;
+use crate::{
+    enter_execution_game, exit_execution_game, GameConfig, GlobalConfig, Listing, ListingKind,
+    ListingStatus, PgError, PlayerLedger, SweepExecuted, BPS_DENOM, CONFIG_SEED, ESCROW_SEED,
+    GAME_SEED, LEDGER_SEED, MAX_SWEEP_ITEMS,
+};
+
+/// Accounts per sweep item in `remaining_accounts`:
+/// `[listing, seller_ledger, royalty_recipient_ledger, item_mint, escrow_item_ata, buyer_item_ata]`
+pub const SWEEP_ACCOUNTS_PER_ITEM: usize = 6;
+
+/// One entry of a sweep: buy up to `quantity` units as long as the unit price is at most `max_price`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepItem {
+    pub quantity: u64,
+    pub max_price: u64, // Per unit
+}
+
+/// A validated fill, with its accounts loaded from `remaining_accounts`
+struct SweepFill<'info> {
+    listing: Box<Account<'info, Listing>>,
+    seller_ledger: Box<Account<'info, PlayerLedger>>,
+    royalty_ledger_info: &'info AccountInfo<'info>,
+    item_mint: InterfaceAccount<'info, Mint>,
+    escrow_item_ata: &'info AccountInfo<'info>,
+    buyer_item_ata: &'info AccountInfo<'info>,
+    quantity: u64,
+    total_price: u64,
+    game_fee: u64,
+    protocol_fee: u64,
+    royalty_amount: u64,
+    seller_amount: u64,
+}
+
+// ======================================================================
+// SWEEP INSTRUCTIONS
+// ======================================================================
+
+/// Load and validate one sweep item (same checks as `buy_fixed`). Fees are the game fee plus
+/// the flat `protocol_fee_bps`: sweeps skip maker/taker tiers and don't count toward tier volume.
+///
+/// In best-effort mode the fill is clamped to the listing's remaining quantity.
+#[allow(clippy::too_many_arguments)] // Per-item accounts plus buyer/game state
+#[allow(clippy::too_many_lines)] // Mirrors buy_fixed validation plus the flat fee math
+fn load_sweep_fill<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    item: &SweepItem,
+    all_or_nothing: bool,
+    cfg: &GlobalConfig,
+    game: &GameConfig,
+    game_key: Pubkey,
+    buyer: Pubkey,
+    available: u64,
+    now: i64,
+    program_id: &Pubkey,
+) -> Result<SweepFill<'info>> {
+    require!(item.quantity > 0, PgError::InvalidAmount);
+
+    let listing = Box::new(Account::<Listing>::try_from(&accounts[0])?);
+    require!(listing.game == game_key, PgError::Unauthorized);
+    require!(
+        listing.currency_mint == game.currency_mint,
+        PgError::CurrencyMintMismatch
+    );
+    require!(
+        listing.kind == ListingKind::Fixed,
+        PgError::InvalidListingKind
+    );
+    require!(
+        listing.status == ListingStatus::Active || listing.status == ListingStatus::PartiallyFilled,
+        PgError::InvalidListingStatus
+    );
+    require!(now >= listing.start_time, PgError::InvalidTime);
+    require!(now <= listing.end_time, PgError::InvalidTime);
+    require!(listing.seller != buyer, PgError::SelfTrade);
+    // No proofs in a sweep: private listings are only sweepable by their named buyer
+    listing.check_buyer(&buyer, &[])?;
+    // Split royalties need a variable number of ledgers; buy those listings with buy_fixed
+    require!(
+        listing.royalty_split == Pubkey::default(),
+        PgError::RoyaltySplitMismatch
+    );
+
+    let quantity = if all_or_nothing {
+        item.quantity
+    } else {
+        item.quantity.min(listing.quantity_remaining)
+    };
+    require!(
+        quantity > 0 && listing.quantity_remaining >= quantity,
+        PgError::InsufficientQuantity
+    );
+
+    // Price is fully determined on-chain; `max_price` only protects the buyer
+    let unit_price = listing.start_price;
+    require!(unit_price <= item.max_price, PgError::SweepPriceExceeded);
+    if listing.buy_now_price > 0 {
+        require!(unit_price <= listing.buy_now_price, PgError::InvalidAmount);
+    }
+    let total_price = unit_price.checked_mul(quantity).ok_or(PgError::Overflow)?;
+    require!(available >= total_price, PgError::InsufficientCredits);
+
+    let seller_ledger = Box::new(Account::<PlayerLedger>::try_from(&accounts[1])?);
+    require!(seller_ledger.game == game_key, PgError::Unauthorized);
+    require!(
+        seller_ledger.authority == listing.seller,
+        PgError::Unauthorized
+    );
+
+    let item_mint = InterfaceAccount::<Mint>::try_from(&accounts[3])?;
+    require!(item_mint.key() == listing.item_mint, PgError::Unauthorized);
+
+    let (escrow_key, _) =
+        Pubkey::find_program_address(&[ESCROW_SEED, listing.key().as_ref()], program_id);
+    require!(accounts[4].key() == escrow_key, PgError::Unauthorized);
+
+    let buyer_item_ata = InterfaceAccount::<TokenAccount>::try_from(&accounts[5])?;
+    require!(buyer_item_ata.owner == buyer, PgError::Unauthorized);
+    require!(
+        buyer_item_ata.mint == listing.item_mint,
+        PgError::Unauthorized
+    );
+
+    // Fees (flat protocol_fee_bps; see above)
+    let game_fee = total_price
+        .checked_mul(u64::from(game.fee_bps))
+        .ok_or(PgError::Overflow)?
+        .checked_div(BPS_DENOM)
+        .ok_or(PgError::Overflow)?;
+    let protocol_fee = total_price
+        .checked_mul(u64::from(cfg.protocol_fee_bps))
+        .ok_or(PgError::Overflow)?
+        .checked_div(BPS_DENOM)
+        .ok_or(PgError::Overflow)?;
+    let royalty_amount =
+        if listing.royalty_bps > 0 && listing.royalty_recipient != Pubkey::default() {
+            total_price
+                .checked_mul(u64::from(listing.royalty_bps))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?
+        } else {
+            0u64
+        };
+
+    // CRITICAL: Same 50% fee cap as buy_fixed
+    let total_fees = game_fee
+        .checked_add(protocol_fee)
+        .ok_or(PgError::Overflow)?
+        .checked_add(royalty_amount)
+        .ok_or(PgError::Overflow)?;
+    let max_allowed_fees = total_price.checked_div(2).ok_or(PgError::Overflow)?;
+    require!(total_fees <= max_allowed_fees, PgError::FeeTooHigh);
+
+    let seller_amount = total_price
+        .checked_sub(total_fees)
+        .ok_or(PgError::Overflow)?;
+    require!(seller_amount > 0, PgError::InvalidSellerAmount);
+
+    if royalty_amount > 0 {
+        let (royalty_ledger_key, _) = Pubkey::find_program_address(
+            &[
+                LEDGER_SEED,
+                game_key.as_ref(),
+                listing.royalty_recipient.as_ref(),
+            ],
+            program_id,
+        );
+        require!(
+            accounts[2].key() == royalty_ledger_key,
+            PgError::Unauthorized
+        );
+    }
+
+    Ok(SweepFill {
+        listing,
+        seller_ledger,
+        royalty_ledger_info: &accounts[2],
+        item_mint,
+        escrow_item_ata: &accounts[4],
+        buyer_item_ata: &accounts[5],
+        quantity,
+        total_price,
+        game_fee,
+        protocol_fee,
+        royalty_amount,
+        seller_amount,
+    })
+}
+
+/// Credit `amount` to a royalty recipient's ledger loaded from `remaining_accounts`,
+/// creating the ledger (paid by the buyer) if it doesn't exist yet.
+fn credit_sweep_royalty<'info>(
+    ledger_info: &'info AccountInfo<'info>,
+    recipient: Pubkey,
+    amount: u64,
+    game_key: Pubkey,
+    buyer: &AccountInfo<'info>,
+    system_program_info: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    init_player_ledger(
+        ledger_info,
+        &game_key,
+        &recipient,
+        buyer,
+        system_program_info,
+        program_id,
+    )?;
+
+    let mut ledger = Account::<PlayerLedger>::try_from(ledger_info)?;
+    require!(ledger.game == game_key, PgError::Unauthorized);
+    require!(ledger.authority == recipient, PgError::Unauthorized);
+    ledger.available = ledger
+        .available
+        .checked_add(amount)
+        .ok_or(PgError::Overflow)?;
+    ledger.exit(program_id)
+}
+
+/// Buy from several fixed-price listings in one instruction.
+///
+/// Each `items[i]` pairs with `SWEEP_ACCOUNTS_PER_ITEM` accounts in `remaining_accounts`
+/// (see `SWEEP_ACCOUNTS_PER_ITEM`). With `all_or_nothing` any invalid item fails the whole
+/// sweep; otherwise invalid items are skipped and fills are clamped to what is left.
+/// Listings using a royalty split, and private listings not naming the buyer as
+/// `allowed_buyer`, are not sweepable. Emits a single `SweepExecuted`.
+///
+/// # Errors
+/// - `PgError::InvalidSweep` if items are empty, exceed `MAX_SWEEP_ITEMS` or accounts don't match
+/// - `PgError::SettlementsPaused` if settlements are paused
+/// - `PgError::KycRequired` if the game requires KYC and the buyer isn't verified
+/// - `PgError::JurisdictionBlocked` if the game blocks the buyer's KYC-attested country
+/// - `PgError::SelfExcluded` if the buyer is in a self-exclusion cool-off
+/// - `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded` if an item exceeds the
+///   buyer's KYC tier limits (all-or-nothing)
+/// - `PgError::SweepPriceExceeded` if a listing's unit price is above `max_price` (all-or-nothing)
+/// - `PgError::SweepNothingFilled` if no item could be filled (best-effort)
+/// - Any `buy_fixed` validation error for an invalid item (all-or-nothing)
+#[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+#[allow(clippy::too_many_lines)] // Per-item settlement plus aggregation
+pub fn sweep_buy<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SweepBuy<'info>>,
+    items: Vec<SweepItem>,
+    all_or_nothing: bool,
+) -> Result<()> {
+    // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+    require!(
+        !items.is_empty() && items.len() <= MAX_SWEEP_ITEMS,
+        PgError::InvalidSweep
+    );
+    require!(
+        ctx.remaining_accounts.len() == items.len() * SWEEP_ACCOUNTS_PER_ITEM,
+        PgError::InvalidSweep
+    );
+
+    let cfg = &ctx.accounts.config;
+    let game = &ctx.accounts.game;
+    require!(!cfg.paused_settlements, PgError::SettlementsPaused);
+    require!(!game.paused_settlements, PgError::SettlementsPaused);
+
+    let buyer = ctx.accounts.buyer_signer.key();
+    let buyer_ledger = &ctx.accounts.buyer_ledger;
+    let now = Clock::get()?.unix_timestamp;
+    if game.kyc_required {
+        require!(buyer_ledger.has_valid_kyc(now), PgError::KycRequired);
+    }
+    game.jurisdictions.check(buyer_ledger, now)?;
+    require!(buyer_ledger.authority == buyer, PgError::Unauthorized);
+
+    require!(!buyer_ledger.limits.is_excluded(now), PgError::SelfExcluded);
+    let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+
+    // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+    let game = &mut ctx.accounts.game;
+    let buyer_ledger = &mut ctx.accounts.buyer_ledger;
+    enter_execution_game(game)?;
+    let res = (|| -> Result<()> {
+        let game_key = game.key();
+        let game_id = game.game_id;
+        let game_bump = game.bump;
+
+        let mut listings_filled = 0u8;
+        let mut listings_skipped = 0u8;
+        let mut total_quantity = 0u64;
+        let mut total_price = 0u64;
+        let mut total_protocol_fee = 0u64;
+        let mut total_game_fee = 0u64;
+        let mut total_royalty = 0u64;
+
+        for (item, accounts) in items
+            .iter()
+            .zip(ctx.remaining_accounts.chunks_exact(SWEEP_ACCOUNTS_PER_ITEM))
+        {
+            let fill = match load_sweep_fill(
+                accounts,
+                item,
+                all_or_nothing,
+                cfg,
+                game,
+                game_key,
+                buyer,
+                buyer_ledger.available,
+                now,
+                ctx.program_id,
+            )
+            .and_then(|fill| {
+                // Spending and KYC tier limits apply per item (a capped item is skipped in
+                // best-effort mode without consuming either limit)
+                buyer_ledger.record_trade_limits(
+                    cfg,
+                    game.kyc_required,
+                    fill.total_price,
+                    now,
+                    approver,
+                )?;
+                Ok(fill)
+            }) {
+                Ok(fill) => fill,
+                Err(err) if all_or_nothing => return Err(err),
+                Err(_) => {
+                    listings_skipped = listings_skipped.checked_add(1).ok_or(PgError::Overflow)?;
+                    continue;
+                }
+            };
+            let SweepFill {
+                mut listing,
+                mut seller_ledger,
+                royalty_ledger_info,
+                item_mint,
+                escrow_item_ata,
+                buyer_item_ata,
+                quantity,
+                total_price: price,
+                game_fee,
+                protocol_fee,
+                royalty_amount,
+                seller_amount,
+            } = fill;
+
+            // Move credits
+            buyer_ledger.available = buyer_ledger
+                .available
+                .checked_sub(price)
+                .ok_or(PgError::Overflow)?;
+            seller_ledger.available = seller_ledger
+                .available
+                .checked_add(seller_amount)
+                .ok_or(PgError::Overflow)?;
+
+            // Royalties: a recipient who is also the seller is credited on the loaded copy
+            // so its exit below doesn't overwrite the share
+            if royalty_amount > 0 {
+                if listing.royalty_recipient == listing.seller {
+                    seller_ledger.available = seller_ledger
+                        .available
+                        .checked_add(royalty_amount)
+                        .ok_or(PgError::Overflow)?;
+                } else if listing.royalty_recipient == buyer {
+                    buyer_ledger.available = buyer_ledger
+                        .available
+                        .checked_add(royalty_amount)
+                        .ok_or(PgError::Overflow)?;
+                } else {
+                    credit_sweep_royalty(
+                        royalty_ledger_info,
+                        listing.royalty_recipient,
+                        royalty_amount,
+                        game_key,
+                        &ctx.accounts.buyer_signer.to_account_info(),
+                        &ctx.accounts.system_program.to_account_info(),
+                        ctx.program_id,
+                    )?;
+                }
+            }
+
+            // Deliver items: escrow -> buyer ATA
+            let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+            let signer = &[seeds];
+            let cpi_accounts = token_interface::TransferChecked {
+                from: escrow_item_ata.clone(),
+                mint: item_mint.to_account_info(),
+                to: buyer_item_ata.clone(),
+                authority: game.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, quantity, item_mint.decimals)?;
+
+            // Update listing
+            listing.quantity_remaining = listing
+                .quantity_remaining
+                .checked_sub(quantity)
+                .ok_or(PgError::Overflow)?;
+            listing.updated_at = now;
+            listing.has_interest = true;
+            listing.status = if listing.quantity_remaining == 0 {
+                ListingStatus::Settled
+            } else {
+                ListingStatus::PartiallyFilled
+            };
+
+            // Persist before the next item so a seller appearing twice sees its updated ledger
+            listing.exit(ctx.program_id)?;
+            seller_ledger.exit(ctx.program_id)?;
+
+            listings_filled = listings_filled.checked_add(1).ok_or(PgError::Overflow)?;
+            total_quantity = total_quantity
+                .checked_add(quantity)
+                .ok_or(PgError::Overflow)?;
+            total_price = total_price.checked_add(price).ok_or(PgError::Overflow)?;
+            total_protocol_fee = total_protocol_fee
+                .checked_add(protocol_fee)
+                .ok_or(PgError::Overflow)?;
+            total_game_fee = total_game_fee
+                .checked_add(game_fee)
+                .ok_or(PgError::Overflow)?;
+            total_royalty = total_royalty
+                .checked_add(royalty_amount)
+                .ok_or(PgError::Overflow)?;
+        }
+
+        require!(listings_filled > 0, PgError::SweepNothingFilled);
+
+        // CRITICAL: Track protocol fees per-game (see buy_fixed)
+        game.protocol_fees_accumulated = game
+            .protocol_fees_accumulated
+            .checked_add(total_protocol_fee)
+            .ok_or(PgError::Overflow)?;
+        game.accumulated_game_fees = game
+            .accumulated_game_fees
+            .checked_add(total_game_fee)
+            .ok_or(PgError::Overflow)?;
+
+        emit!(SweepExecuted {
+            game: game_key,
+            buyer,
+            all_or_nothing,
+            listings_filled,
+            listings_skipped,
+            total_quantity,
+            total_price,
+            total_protocol_fee,
+            total_game_fee,
+            total_royalty,
+        });
+
+        Ok(())
+    })();
+
+    exit_execution_game(game);
+    res
+}
+
+// ======================================================================
+// CONTEXTS
+// ======================================================================
+
+#[derive(Accounts)]
+pub struct SweepBuy<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), buyer_signer.key().as_ref()],
+        bump
+    )]
+    pub buyer_ledger: Box<Account<'info, PlayerLedger>>,
+
+    #[account(mut)]
+    pub buyer_signer: Signer<'info>,
+
+    /// Guardian co-signature for spends above the buyer's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{leaked_account, zeroed};
+    use anchor_spl::token_2022::spl_token_2022::state::{
+        Account as TokenAccountState, AccountState, Mint as MintState,
+    };
+    use solana_program::program_pack::Pack;
+
+    fn serialized<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    /// Sweep accounts for `listing` (quantity 10 left at 100 per unit), bought by `buyer`
+    fn sweep_accounts(listing: &Listing, buyer: Pubkey) -> &'static [AccountInfo<'static>] {
+        let token_program = anchor_spl::token::ID;
+        let listing_key = Pubkey::new_unique();
+        let mut seller_ledger = zeroed::<PlayerLedger>();
+        seller_ledger.game = listing.game;
+        seller_ledger.authority = listing.seller;
+
+        let mut mint_data = vec![0u8; MintState::LEN];
+        MintState {
+            is_initialized: true,
+            ..MintState::default()
+        }
+        .pack_into_slice(&mut mint_data);
+        let mut buyer_ata_data = vec![0u8; TokenAccountState::LEN];
+        TokenAccountState {
+            mint: listing.item_mint,
+            owner: buyer,
+            state: AccountState::Initialized,
+            ..TokenAccountState::default()
+        }
+        .pack_into_slice(&mut buyer_ata_data);
+        let (escrow, _) =
+            Pubkey::find_program_address(&[ESCROW_SEED, listing_key.as_ref()], &crate::ID);
+
+        Box::leak(Box::new([
+            leaked_account(listing_key, 0, serialized(listing), crate::ID),
+            leaked_account(
+                Pubkey::new_unique(),
+                0,
+                serialized(&seller_ledger),
+                crate::ID,
+            ),
+            leaked_account(Pubkey::new_unique(), 0, Vec::new(), Pubkey::default()),
+            leaked_account(listing.item_mint, 0, mint_data, token_program),
+            leaked_account(escrow, 0, Vec::new(), token_program),
+            leaked_account(Pubkey::new_unique(), 0, buyer_ata_data, token_program),
+        ]))
+    }
+
+    #[test]
+    fn sweep_fill_clamps_best_effort_and_checks_price_and_buyer() {
+        let mut cfg = zeroed::<GlobalConfig>();
+        cfg.protocol_fee_bps = 200;
+        let mut game = zeroed::<GameConfig>();
+        game.fee_bps = 300;
+        let game_key = Pubkey::new_unique();
+        let buyer = Pubkey::new_unique();
+
+        let mut listing = zeroed::<Listing>();
+        listing.game = game_key;
+        listing.seller = Pubkey::new_unique();
+        listing.item_mint = Pubkey::new_unique();
+        listing.kind = ListingKind::Fixed;
+        listing.status = ListingStatus::Active;
+        listing.end_time = 1_000;
+        listing.start_price = 100;
+        listing.quantity_remaining = 10;
+
+        let load = |listing: &Listing, quantity: u64, max_price: u64, all_or_nothing: bool| {
+            let item = SweepItem {
+                quantity,
+                max_price,
+            };
+            load_sweep_fill(
+                sweep_accounts(listing, buyer),
+                &item,
+                all_or_nothing,
+                &cfg,
+                &game,
+                game_key,
+                buyer,
+                u64::MAX,
+                500,
+                &crate::ID,
+            )
+        };
+
+        // Best effort: 15 asked, 10 left
+        let fill = load(&listing, 15, 100, false).unwrap();
+        assert_eq!((fill.quantity, fill.total_price), (10, 1_000));
+        assert_eq!((fill.game_fee, fill.protocol_fee), (30, 20));
+        assert_eq!(fill.seller_amount, 950);
+        assert_eq!(
+            load(&listing, 15, 100, true).err(),
+            Some(error!(PgError::InsufficientQuantity))
+        );
+        assert_eq!(
+            load(&listing, 1, 99, false).err(),
+            Some(error!(PgError::SweepPriceExceeded))
+        );
+
+        // Private listings are only sweepable by their named buyer
+        listing.allowed_buyer = Pubkey::new_unique();
+        assert_eq!(
+            load(&listing, 1, 100, false).err(),
+            Some(error!(PgError::BuyerNotAllowed))
+        );
+        listing.allowed_buyer = buyer;
+        assert!(load(&listing, 1, 100, false).is_ok());
+    }
+}
@@ -48,6 +48,8 @@ pub mod state;
 pub mod zk; // ZK Module
 
 pub mod instructions;
+pub mod metadata; // Metaplex Token Metadata royalty parsing
+pub mod stake; // pdox_stake AgentAccount parsing (protocol fee discounts)
 
 // Re-export instructions for Anchor macro code generation
 // Note: ambiguous_glob_reexports is allowed above to support Anchor pattern
@@ -91,12 +93,39 @@ pub const BPS_DENOM: u64 = 10_000;
 // - Tensor: 0.5-1% (similar range, but no netting benefits)
 // - PhantomGrid: 0.5% justified by netting (99.9% tx fee savings), ZK privacy, agent marketplace
 pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000; // 10% maximum (governance can adjust up to this)
+pub const MAX_REFERRAL_BPS: u16 = 5_000; // Referrers get at most half of the protocol fee
 pub const MAX_GAME_FEE_BPS: u16 = 2_000; // 20% maximum
 pub const MAX_CANCEL_PENALTY_BPS: u16 = 2_000;
 pub const MAX_ROYALTY_BPS: u16 = 2_500; // 25% maximum - prevents zombie listings when combined with fees
 pub const MIN_ROYALTY_BPS: u16 = 50; // 0.50% minimum - prevents griefing via dust royalties
 pub const MAX_LISTING_DURATION_SECS: i64 = 60 * 60 * 24 * 30; // 30 days
 pub const MAX_BULK_QTY: u64 = 100_000;
+pub const MAX_BUNDLE_ITEMS: usize = 8; // Max distinct mints per bundle listing (CU + account limit)
+pub const MAX_SWAP_ITEMS: usize = 4; // Max distinct mints per side of a swap offer (account limit)
+pub const MAX_BOOK_ORDERS: usize = 16; // Resting orders per side of an order book (stack limit)
+pub const MAX_ROYALTY_RECIPIENTS: usize = 5; // Recipients per royalty split (artist, studio, guild, ...)
+pub const KEEPER_POOL_MATCH_MULTIPLE: u64 = 1; // Keeper pool pays at most 1x the listing deposit drawn per crank
+pub const MAX_SWEEP_ITEMS: usize = 8; // Listings per sweep_buy (6 accounts each - account limit)
+pub const MAX_LAZY_NAME_LEN: usize = 32; // Lazy-minted item metadata limits (Metaplex-compatible)
+pub const MAX_LAZY_SYMBOL_LEN: usize = 10;
+pub const MAX_LAZY_URI_LEN: usize = 200;
+pub const SPEND_DAY_BUCKET_SECS: i64 = 60 * 60 * 4; // 6 buckets -> rolling 24h spend window
+pub const SPEND_WEEK_BUCKET_SECS: i64 = 60 * 60 * 24; // 7 buckets -> rolling 7-day spend window
+pub const MAX_SELF_EXCLUSION_SECS: i64 = 60 * 60 * 24 * 365 * 5; // 5 years
+pub const MAX_KYC_PROVIDERS: usize = 4; // Approved KYC attestors in GlobalConfig
+pub const KYC_TIER_COUNT: usize = 3; // KycTier::None / Basic / Full
+pub const KYC_VOLUME_DAY_SECS: i64 = 60 * 60 * 24; // KYC daily volume resets on UTC day boundaries
+pub const MAX_JURISDICTIONS: usize = 16; // Country codes per game allow/deny list
+pub const MAX_FEE_TIERS: usize = 5; // Maker/taker volume tiers in GlobalConfig
+pub const FEE_VOLUME_BUCKET_SECS: i64 = 60 * 60 * 24 * 3; // 10 buckets -> rolling 30-day volume window
+pub const MAX_STAKE_DISCOUNT_TIERS: usize = 4; // PDOX staker discount tiers in GlobalConfig
+pub const MAX_STAKE_DISCOUNT_BPS: u16 = 5_000; // Stakers save at most half of the protocol fee
+pub const MAX_PDOX_FEE_DISCOUNT_BPS: u16 = 5_000; // Paying fees in PDOX saves at most half
+pub const PDOX_PRICE_SCALE: u64 = 1_000_000_000; // PDOX reference price: PDOX base units per 1e9 currency units
+pub const MAX_PDOX_PRICE_DEVIATION_BPS: u16 = 5_000; // Pool price may stray at most 50% from the reference
+pub const MAX_GAME_ROLE_MEMBERS: usize = 8; // Role holders per game besides the owner
+pub const MAX_SOFT_CLOSE_SECS: u32 = 60 * 60; // 1 hour max soft-close window / per-bid extension
+pub const MAX_SOFT_CLOSE_TOTAL_EXTENSION_SECS: u32 = 60 * 60 * 24; // 24 hours max cumulative extension
 
 // seeds
 pub const CONFIG_SEED: &[u8] = b"config";
@@ -121,6 +150,24 @@ pub const LP_GROWTH_SEED: &[u8] = b"lp_growth";
 pub const JOB_SEED: &[u8] = b"job";
 pub const JOB_ASSIGNMENT_SEED: &[u8] = b"job_assignment";
 pub const JOB_BUDGET_SEED: &[u8] = b"job_budget";
+pub const SEALED_BID_SEED: &[u8] = b"sealed_bid";
+pub const COLLECTION_OFFER_SEED: &[u8] = b"collection_offer";
+pub const BUNDLE_LISTING_SEED: &[u8] = b"bundle_listing";
+pub const SWAP_OFFER_SEED: &[u8] = b"swap_offer";
+pub const ORDER_BOOK_SEED: &[u8] = b"order_book";
+pub const RENTAL_SEED: &[u8] = b"rental";
+pub const ROYALTY_SPLIT_SEED: &[u8] = b"royalty_split";
+pub const REFERRAL_SEED: &[u8] = b"referral";
+pub const KEEPER_POOL_SEED: &[u8] = b"keeper_pool";
+pub const LAZY_MINT_SEED: &[u8] = b"lazy_mint";
+pub const LAZY_ITEM_SEED: &[u8] = b"lazy_item";
+pub const PDOX_PRICE_SEED: &[u8] = b"pdox_price";
+pub const GAME_ROLES_SEED: &[u8] = b"game_roles";
+
+// Sealed-bid (commit-reveal) auction parameters
+pub const SEALED_BID_REVEAL_WINDOW_SECS: i64 = 60 * 60 * 24; // Reveals accepted for 24h after end_time
+pub const SEALED_BID_FORFEIT_BPS: u16 = 2_000; // 20% of deposit forfeited to seller if never revealed
+pub const SEALED_BID_SETTLEMENT_WINDOW_SECS: i64 = 60 * 60 * 24 * 7; // Winner settles within 7d of reveals closing, else refunded
 
 // ======================================================================
 // HYPERSCALE / NET ENGINE CONSTANTS
@@ -157,6 +204,86 @@ pub enum ListingKind {
     Fixed,
     EnglishAuction,
     DutchAuction,
+    SealedBid, // Commit-reveal: bids hidden until end_time, revealed during reveal window
+}
+
+/// Price decay curve for Dutch auctions (price falls from `start_price` to `dutch_min_price`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DutchCurve {
+    Linear,      // Continuous linear decay over [start_time, end_time]
+    Exponential, // Price multiplied by (1 - dutch_decay_bps) every dutch_step_secs
+    Stepped,     // Linear decay applied in discrete drops every dutch_step_secs
+}
+
+/// How the clearing price of an English / sealed-bid auction is determined
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SettlementMode {
+    FirstPrice,  // Winner pays their own bid
+    SecondPrice, // Vickrey: winner pays runner-up bid (or reserve/start floor), computed on-chain
+}
+
+/// What a standing collection offer matches. Both scopes commit to a Merkle root of
+/// `sha256(item_mint)` leaves; the scope tells indexers how the root was built.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OfferScope {
+    MintSet, // Root over a game's item mints (single mint: root = leaf, empty proof)
+    Trait,   // Root over the item mints carrying a given trait
+}
+
+/// Per-game policy for creator royalties from Metaplex Token Metadata
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoyaltyEnforcement {
+    #[default]
+    Ignored, // Seller-supplied royalty_recipient / royalty_bps are used as-is
+    Optional, // Metadata overrides seller values when the metadata account is passed
+    Enforced, // Metadata account must be passed; overrides seller values when the mint has one
+}
+
+/// KYC level attested for a player. Each tier has its own trade limits in `GlobalConfig`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KycTier {
+    #[default]
+    None, // Unverified (or attestation expired)
+    Basic, // Light checks (e.g. email / phone / sanctions screening)
+    Full,  // Full identity verification
+}
+
+/// How a game's jurisdiction list is applied to the player's KYC-attested country
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JurisdictionMode {
+    #[default]
+    Disabled, // No country gating
+    AllowList, // Only listed countries may trade
+    DenyList,  // Listed countries may not trade
+}
+
+/// Per-game role held through `GameRoles`, stored as bit `1 << role` of a member's `roles`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GameRole {
+    Pauser,           // paused_new / paused_settlements in update_game
+    FeeWithdrawer,    // withdraw_game_fees (always paid to payout_wallet)
+    KycOperator,      // Revoke / downgrade KYC via set_player_kyc for this game
+    ListingModerator, // Activate / cancel / close any listing of the game
+    ConfigManager,    // Every other update_game field except the payout wallet
+}
+
+impl GameRole {
+    /// All defined role bits
+    pub const ALL: u8 = 0b1_1111;
+
+    pub const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Where protocol fees paid in PDOX are routed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PdoxFeeSink {
+    #[default]
+    Disabled, // Fees can only be paid in the game currency
+    DaoTreasury, // DAO treasury vault's PDOX account
+    LpGrowth,    // LP growth manager's PDOX account
+    Burn,        // Burned from the payer's account
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -166,6 +293,14 @@ pub enum ListingStatus {
     PartiallyFilled, // Added for partial fills visibility
     Settled,
     Cancelled,
+    Expired, // Ended unsold; escrow returned by the expiry crank
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RentalStatus {
+    Available, // Item in escrow, can be rented
+    Rented,    // Item with renter (game PDA is delegate), collateral locked
+    Closed,    // Cancelled by owner or item lost (collateral forfeited)
 }
 
 // ======================================================================
@@ -222,6 +357,51 @@ pub struct GlobalConfig {
     /// Timestamp when the last state root was settled
     pub last_state_timestamp: i64,
 
+    /// Share of the protocol fee paid to referrers (bps of the protocol fee, <= `MAX_REFERRAL_BPS`)
+    pub referral_bps: u16,
+
+    // ======================================================================
+    // KYC TIERS
+    // ======================================================================
+    /// Approved KYC attestors allowed to call `set_player_kyc` (Pubkey::default() = empty slot)
+    pub kyc_providers: [Pubkey; MAX_KYC_PROVIDERS],
+    /// Trade limits per `KycTier`, indexed by tier
+    pub kyc_tier_limits: [KycTierLimits; KYC_TIER_COUNT],
+    /// Default attestation lifetime when `set_player_kyc` gets no explicit expiry (0 = never expires)
+    pub kyc_attestation_ttl_secs: i64,
+
+    // ======================================================================
+    // MAKER / TAKER FEE TIERS
+    // ======================================================================
+    /// Volume tiers sorted by `min_volume`; replace `protocol_fee_bps` when `fee_tier_count > 0`
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    pub fee_tier_count: u8,
+
+    // ======================================================================
+    // PDOX STAKER DISCOUNTS
+    // ======================================================================
+    /// pdox_stake program owning staker `AgentAccount`s (Pubkey::default() = discounts off)
+    pub pdox_stake_program: Pubkey,
+    /// Discount tiers sorted by `min_stake`
+    pub stake_discount_tiers: [StakeDiscountTier; MAX_STAKE_DISCOUNT_TIERS],
+    pub stake_discount_tier_count: u8,
+
+    // ======================================================================
+    // PDOX FEE PAYMENTS
+    // ======================================================================
+    /// Sink for protocol fees paid in PDOX (`Disabled` = PDOX payments off)
+    pub pdox_fee_sink: PdoxFeeSink,
+    /// PDOX token account of the sink (unused for `Burn`)
+    pub pdox_fee_sink_account: Pubkey,
+    /// Discount on the protocol fee when paid in PDOX (bps of the fee)
+    pub pdox_fee_discount_bps: u16,
+
+    // ======================================================================
+    // PROTOCOL FEE ROUTER
+    // ======================================================================
+    /// Treasury / LP growth / burn split applied by `distribute_protocol_fees`
+    pub fee_router: FeeRouterSplits,
+
     pub reserved: [u8; 0], // Reserved space consumed by compressed settlement fields
 }
 
@@ -251,7 +431,18 @@ pub struct GameConfig {
     // CRITICAL: Per-game reentrancy guard (replaces global lock for better parallelism)
     pub in_execution: bool,
 
-    pub reserved: [u8; 23], // Reduced to make room for in_execution
+    // Default anti-sniping soft-close for English auctions (listings may override)
+    pub soft_close: SoftCloseConfig,
+
+    // Creator royalties from Metaplex metadata (Ignored = seller-supplied values)
+    pub royalty_enforcement: RoyaltyEnforcement,
+
+    // Country allow/deny list for paid trading (KYC-attested country)
+    pub jurisdictions: JurisdictionPolicy,
+
+    // Reduced from 23 to make room for soft_close, royalty_enforcement. `jurisdictions` was
+    // appended after launch; older games are resized (zero-filled) by migrate_game_config
+    pub reserved: [u8; 10],
 }
 
 #[account]
@@ -270,10 +461,340 @@ pub struct PlayerLedger {
     pub kyc_provider: Pubkey, // KYC provider program/authority (Pubkey::default() = none)
     pub kyc_verified_at: i64, // Unix timestamp of KYC verification
     pub kyc_proof_hash: [u8; 32], // Hash of KYC proof (for future ZK proofs)
+    pub kyc_tier: KycTier,        // Attested tier (legacy verified ledgers count as Basic)
+    pub kyc_expires_at: i64,      // Attestation expiry (0 = never)
+    pub kyc_country: [u8; 2],     // Provider-attested ISO 3166-1 alpha-2 country ([0, 0] = none)
+    pub kyc_volume_day: i64,      // UTC day index of `kyc_day_volume`
+    pub kyc_day_volume: u64,      // Traded volume counted against the tier's daily limit
 
+    pub limits: SpendingLimits, // Optional player protection controls (all zero = disabled)
+    pub fee_volume: VolumeTracker, // 30-day buy_fixed/auction/net-batch volume (fee tier)
+
+    // Fields from kyc_tier on were appended after launch; older ledgers are resized
+    // (zero-filled) by migrate_player_ledger
     pub reserved: [u8; 16],
 }
 
+/// Optional spend controls on a `PlayerLedger` (minors / responsible-play requirements).
+///
+/// Caps are tracked over rolling windows: 24h in `SPEND_DAY_BUCKET_SECS` buckets and 7 days in
+/// day buckets. Enforced on `buy_fixed`, `sweep_buy`, `place_bid` (locked amount) and
+/// `withdraw_credits`; self-exclusion never blocks withdrawals.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpendingLimits {
+    pub daily_cap: u64,  // 0 = no cap
+    pub weekly_cap: u64, // 0 = no cap
+
+    pub guardian: Pubkey,         // Pubkey::default() = no guardian
+    pub guardian_threshold: u64,  // Single spends above this need the guardian's signature (0 = disabled)
+
+    pub excluded_until: i64, // Self-exclusion cool-off: no buying/bidding before this
+
+    // Rolling window state
+    pub day_buckets: [u64; 6],  // Spend per SPEND_DAY_BUCKET_SECS slot (24h window)
+    pub day_slot: i64,          // Latest day-bucket slot written
+    pub week_buckets: [u64; 7], // Spend per day (7-day window)
+    pub week_slot: i64,         // Latest week-bucket slot written
+}
+
+/// Advance a ring of buckets to `slot`, zeroing buckets that fell out of the window
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // rem_euclid < len
+fn roll_buckets(buckets: &mut [u64], last_slot: &mut i64, slot: i64) {
+    if slot <= *last_slot {
+        return;
+    }
+    let len = buckets.len() as i64;
+    let first = (*last_slot + 1).max(slot - len + 1);
+    for stale in first..=slot {
+        buckets[stale.rem_euclid(len) as usize] = 0;
+    }
+    *last_slot = slot;
+}
+
+/// Add `amount` to the bucket for `slot` (after `roll_buckets`)
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // rem_euclid < len
+fn add_to_bucket(buckets: &mut [u64], slot: i64, amount: u64) -> Result<()> {
+    let index = slot.rem_euclid(buckets.len() as i64) as usize;
+    buckets[index] = buckets[index]
+        .checked_add(amount)
+        .ok_or(PgError::Overflow)?;
+    Ok(())
+}
+
+impl SpendingLimits {
+    /// Whether the ledger is in a self-exclusion cool-off at `now`
+    pub const fn is_excluded(&self, now: i64) -> bool {
+        now < self.excluded_until
+    }
+
+    /// Check caps and guardian approval for spending `amount` at `now`, then record it.
+    ///
+    /// # Errors
+    /// - `PgError::GuardianApprovalRequired` if `amount` exceeds `guardian_threshold` and
+    ///   `approver` is not the guardian
+    /// - `PgError::SpendingCapExceeded` if the rolling daily or weekly cap would be exceeded
+    pub fn record_spend(&mut self, amount: u64, now: i64, approver: Option<Pubkey>) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        if self.guardian != Pubkey::default()
+            && self.guardian_threshold > 0
+            && amount > self.guardian_threshold
+        {
+            require!(
+                approver == Some(self.guardian),
+                PgError::GuardianApprovalRequired
+            );
+        }
+
+        let day_slot = now.div_euclid(SPEND_DAY_BUCKET_SECS);
+        let week_slot = now.div_euclid(SPEND_WEEK_BUCKET_SECS);
+        roll_buckets(&mut self.day_buckets, &mut self.day_slot, day_slot);
+        roll_buckets(&mut self.week_buckets, &mut self.week_slot, week_slot);
+
+        let day_total = self
+            .day_buckets
+            .iter()
+            .try_fold(amount, |acc, spent| acc.checked_add(*spent))
+            .ok_or(PgError::Overflow)?;
+        let week_total = self
+            .week_buckets
+            .iter()
+            .try_fold(amount, |acc, spent| acc.checked_add(*spent))
+            .ok_or(PgError::Overflow)?;
+        if self.daily_cap > 0 {
+            require!(day_total <= self.daily_cap, PgError::SpendingCapExceeded);
+        }
+        if self.weekly_cap > 0 {
+            require!(week_total <= self.weekly_cap, PgError::SpendingCapExceeded);
+        }
+
+        add_to_bucket(&mut self.day_buckets, day_slot, amount)?;
+        add_to_bucket(&mut self.week_buckets, week_slot, amount)?;
+        Ok(())
+    }
+}
+
+/// Rolling 30-day traded volume on a `PlayerLedger`, used to pick the maker/taker fee tier.
+/// Both sides of a trade are credited with its full price.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VolumeTracker {
+    pub buckets: [u64; 10], // Volume per FEE_VOLUME_BUCKET_SECS slot (30-day window)
+    pub slot: i64,          // Latest bucket slot written
+}
+
+impl VolumeTracker {
+    /// Traded volume over the rolling window ending at `now`
+    ///
+    /// # Errors
+    /// - `PgError::Overflow` on arithmetic overflow
+    pub fn volume(&mut self, now: i64) -> Result<u64> {
+        roll_buckets(
+            &mut self.buckets,
+            &mut self.slot,
+            now.div_euclid(FEE_VOLUME_BUCKET_SECS),
+        );
+        Ok(self
+            .buckets
+            .iter()
+            .try_fold(0u64, |acc, traded| acc.checked_add(*traded))
+            .ok_or(PgError::Overflow)?)
+    }
+
+    /// Add `amount` of traded volume at `now`
+    ///
+    /// # Errors
+    /// - `PgError::Overflow` on arithmetic overflow
+    pub fn record(&mut self, amount: u64, now: i64) -> Result<()> {
+        let slot = now.div_euclid(FEE_VOLUME_BUCKET_SECS);
+        roll_buckets(&mut self.buckets, &mut self.slot, slot);
+        add_to_bucket(&mut self.buckets, slot, amount)
+    }
+}
+
+/// One row of the governance maker/taker fee table: applies from `min_volume` of 30-day volume
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeTier {
+    pub min_volume: u64,
+    pub maker_fee_bps: u16, // Deducted from the seller's proceeds
+    pub taker_fee_bps: u16, // Charged to the buyer on top of the price
+}
+
+/// One row of the governance PDOX staker discount table
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StakeDiscountTier {
+    pub min_stake: u64,    // Active pdox_stake stake (raw PDOX units)
+    pub discount_bps: u16, // Discount on the protocol fee (bps of the fee, <= MAX_STAKE_DISCOUNT_BPS)
+}
+
+/// Governance split of protocol fees swept by `distribute_protocol_fees` (bps, sum to
+/// `BPS_DENOM`; all zero = router off)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeRouterSplits {
+    pub treasury_bps: u16,  // DAO treasury vault (also receives rounding dust)
+    pub lp_growth_bps: u16, // LP growth manager fee account
+    pub burn_bps: u16,      // Burned from the game vault
+}
+
+impl FeeRouterSplits {
+    /// Whether governance has configured the router
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Check the splits are all zero or sum to exactly 100%
+    ///
+    /// # Errors
+    /// - `PgError::InvalidFeeRouter` if the splits don't sum to `BPS_DENOM`
+    pub fn validate(&self) -> Result<()> {
+        let total = u64::from(self.treasury_bps)
+            + u64::from(self.lp_growth_bps)
+            + u64::from(self.burn_bps);
+        require!(
+            !self.is_enabled() || total == BPS_DENOM,
+            PgError::InvalidFeeRouter
+        );
+        Ok(())
+    }
+}
+
+impl GlobalConfig {
+    /// Protocol fee discount for `stake` PDOX staked (highest tier reached, 0 below the first)
+    pub fn stake_discount_bps(&self, stake: u64) -> u16 {
+        self.stake_discount_tiers
+            [..usize::from(self.stake_discount_tier_count).min(MAX_STAKE_DISCOUNT_TIERS)]
+            .iter()
+            .rev()
+            .find(|tier| stake >= tier.min_stake)
+            .map_or(0, |tier| tier.discount_bps)
+    }
+
+    /// Maker and taker protocol fee rates for the given 30-day volumes.
+    /// Without a tier table the maker pays `protocol_fee_bps` and the taker pays nothing.
+    pub fn fee_rates(&self, maker_volume: u64, taker_volume: u64) -> (u16, u16) {
+        let tiers = &self.fee_tiers[..usize::from(self.fee_tier_count).min(MAX_FEE_TIERS)];
+        if tiers.is_empty() {
+            return (self.protocol_fee_bps, 0);
+        }
+        // Tiers are sorted by min_volume and tiers[0].min_volume == 0
+        let tier_for = |volume: u64| {
+            tiers
+                .iter()
+                .rev()
+                .find(|tier| volume >= tier.min_volume)
+                .unwrap_or(&tiers[0])
+        };
+        (
+            tier_for(maker_volume).maker_fee_bps,
+            tier_for(taker_volume).taker_fee_bps,
+        )
+    }
+}
+
+/// Per-tier KYC trade limits (0 = unlimited)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KycTierLimits {
+    pub max_trade_size: u64, // Largest single trade (price * quantity)
+    pub daily_volume: u64,   // Traded volume per UTC day
+}
+
+impl PlayerLedger {
+    /// KYC tier in force at `now`; an expired or revoked attestation counts as `KycTier::None`
+    pub fn kyc_tier_at(&self, now: i64) -> KycTier {
+        if !self.kyc_verified || (self.kyc_expires_at > 0 && now >= self.kyc_expires_at) {
+            return KycTier::None;
+        }
+        // Ledgers verified before tiers existed carry the default tier
+        self.kyc_tier.max(KycTier::Basic)
+    }
+
+    /// Whether the ledger holds a valid (non-expired) KYC attestation at `now`
+    pub fn has_valid_kyc(&self, now: i64) -> bool {
+        self.kyc_tier_at(now) != KycTier::None
+    }
+
+    /// Check a trade of `amount` against the game's KYC requirement and the tier's max trade size.
+    ///
+    /// # Errors
+    /// - `PgError::KycRequired` if the game requires KYC and the attestation is missing or expired
+    /// - `PgError::KycTradeSizeExceeded` if `amount` exceeds the tier's `max_trade_size`
+    pub fn check_kyc_trade(
+        &self,
+        cfg: &GlobalConfig,
+        kyc_required: bool,
+        amount: u64,
+        now: i64,
+    ) -> Result<()> {
+        let tier = self.kyc_tier_at(now);
+        if kyc_required {
+            require!(tier != KycTier::None, PgError::KycRequired);
+        }
+        let limits = cfg.kyc_tier_limits[tier as usize];
+        if limits.max_trade_size > 0 {
+            require!(
+                amount <= limits.max_trade_size,
+                PgError::KycTradeSizeExceeded
+            );
+        }
+        Ok(())
+    }
+
+    /// `check_kyc_trade`, then count `amount` against the tier's daily volume.
+    ///
+    /// # Errors
+    /// - Any `check_kyc_trade` error
+    /// - `PgError::KycDailyVolumeExceeded` if the UTC-day volume would exceed the tier's `daily_volume`
+    pub fn record_kyc_trade(
+        &mut self,
+        cfg: &GlobalConfig,
+        kyc_required: bool,
+        amount: u64,
+        now: i64,
+    ) -> Result<()> {
+        self.check_kyc_trade(cfg, kyc_required, amount, now)?;
+
+        let day = now.div_euclid(KYC_VOLUME_DAY_SECS);
+        let traded = if day == self.kyc_volume_day {
+            self.kyc_day_volume
+        } else {
+            0
+        };
+        let volume = traded.checked_add(amount).ok_or(PgError::Overflow)?;
+        let limits = cfg.kyc_tier_limits[self.kyc_tier_at(now) as usize];
+        if limits.daily_volume > 0 {
+            require!(
+                volume <= limits.daily_volume,
+                PgError::KycDailyVolumeExceeded
+            );
+        }
+
+        self.kyc_volume_day = day;
+        self.kyc_day_volume = volume;
+        Ok(())
+    }
+
+    /// `limits.record_spend` and `record_kyc_trade` for one trade; neither is recorded unless
+    /// both pass, so a trade skipped on either limit consumes nothing.
+    ///
+    /// # Errors
+    /// - Any `record_spend` or `record_kyc_trade` error
+    pub fn record_trade_limits(
+        &mut self,
+        cfg: &GlobalConfig,
+        kyc_required: bool,
+        amount: u64,
+        now: i64,
+        approver: Option<Pubkey>,
+    ) -> Result<()> {
+        let mut limits = self.limits;
+        limits.record_spend(amount, now, approver)?;
+        // record_kyc_trade only writes once all its checks pass
+        self.record_kyc_trade(cfg, kyc_required, amount, now)?;
+        self.limits = limits;
+        Ok(())
+    }
+}
+
 #[account]
 pub struct Listing {
     pub game: Pubkey,
@@ -309,609 +830,973 @@ pub struct Listing {
     pub highest_bid: u64,       // Current highest bid amount (0 if no bids)
     pub highest_bidder: Pubkey, // Current highest bidder (Pubkey::default() if no bids)
 
-    pub reserved_u16: u16,
-    pub reserved: [u8; 16], // Reduced from 32 to make room for bid tracking
-}
-
-// ======================================================================
-// HYPERSCALE COMPRESSED AUCTION ACCOUNTS
-// ======================================================================
+    // Fields from second_highest_bid on were appended after launch (over the zeroed reserved
+    // bytes); older listings are resized (zero-filled) by migrate_listing
+    pub second_highest_bid: u64, // Runner-up bid from a different bidder (0 if none)
+    pub settlement_mode: SettlementMode,
 
-/// Configuration for an auction tree (one per game/house).
-/// This account stores metadata about the Merkle tree used for
-/// compressed auction state. The actual tree is managed by
-/// spl-account-compression.
-#[account]
-pub struct AuctionTreeConfig {
-    /// Game this tree belongs to
-    pub game: Pubkey,
+    // Dutch auction price curve (ignored for other kinds)
+    pub dutch_curve: DutchCurve,
+    pub dutch_step_secs: u32, // Step interval for Stepped/Exponential curves
+    pub dutch_decay_bps: u16, // Per-step decay for Exponential curve
 
-    /// Merkle tree address (managed by spl-account-compression)
-    pub merkle_tree: Pubkey,
+    // Anti-sniping soft-close (English only). window_secs == 0 -> use GameConfig default
+    pub soft_close: SoftCloseConfig,
+    pub soft_close_extended_secs: u32, // Cumulative extension applied so far
 
-    /// Tree depth (max number of leaves = 2^depth)
-    pub max_depth: u8,
+    pub royalty_split: Pubkey, // RoyaltySplit PDA (Pubkey::default() = royalty_recipient takes all)
 
-    /// Current number of leaves in the tree
-    pub leaf_count: u64,
+    pub version: u64, // Bumped on every update_listing (expected-version guard)
 
-    /// Authority that can modify the tree (usually game PDA)
-    pub authority: Pubkey,
+    pub keeper_deposit: u64, // Seller-posted crank bounty credits (used before the keeper pool)
 
-    /// Bump seed for PDA derivation
-    pub bump: u8,
+    // Private (OTC) listings - Pubkey::default() / zeroed root = open to everyone
+    pub allowed_buyer: Pubkey,    // Single wallet allowed to buy/bid
+    pub allowlist_root: [u8; 32], // Merkle root of allowed wallets (leaf = hash(wallet))
 
-    /// Reserved for future use
-    pub reserved: [u8; 31],
+    pub reserved_u16: u16,
+    pub reserved: [u8; 0], // Reserved space consumed by soft-close fields
 }
 
-/// Compressed auction root account - stores Merkle roots for batch commits.
-/// Each root represents a batch of auctions (e.g., 1,000 auctions).
-/// This enables committing thousands of auctions in a single transaction.
-#[account]
-pub struct CompressedAuctionRoot {
-    /// Game this root belongs to
-    pub game: Pubkey,
+impl Listing {
+    /// `(start_price, buy_now_price, reserve_price)` after an update (`None` keeps the current)
+    ///
+    /// # Errors
+    /// - `PgError::InvalidAmount` if the start price is 0 or below a Dutch floor, or a non-zero
+    ///   buy-now or reserve price is below it
+    pub fn updated_prices(
+        &self,
+        start_price: Option<u64>,
+        buy_now_price: Option<u64>,
+        reserve_price: Option<u64>,
+    ) -> Result<(u64, u64, u64)> {
+        let start_price = start_price.unwrap_or(self.start_price);
+        require!(start_price > 0, PgError::InvalidAmount);
+        // A buy-now cap or reserve below the start price would make buys/settlement always fail
+        let buy_now_price = buy_now_price.unwrap_or(self.buy_now_price);
+        require!(
+            buy_now_price == 0 || buy_now_price >= start_price,
+            PgError::InvalidAmount
+        );
+        let reserve_price = reserve_price.unwrap_or(self.reserve_price);
+        require!(
+            reserve_price == 0 || reserve_price >= start_price,
+            PgError::InvalidAmount
+        );
+        if self.kind == ListingKind::DutchAuction {
+            require!(self.dutch_min_price <= start_price, PgError::InvalidAmount);
+        }
+        Ok((start_price, buy_now_price, reserve_price))
+    }
 
-    /// Batch identifier (unique per batch)
-    pub batch_id: u64,
+    /// Allowlist leaf for `wallet` (sorted-pair SHA256 tree, see `verify_merkle_proof`)
+    pub fn allowlist_leaf(wallet: &Pubkey) -> [u8; 32] {
+        solana_program::hash::hashv(&[wallet.as_ref()]).to_bytes()
+    }
 
-    /// Merkle root hash representing all auctions in this batch
-    pub root: [u8; 32],
+    /// Whether only `allowed_buyer` or allowlisted wallets may buy/bid
+    pub fn is_private(&self) -> bool {
+        self.allowed_buyer != Pubkey::default() || self.allowlist_root != [0u8; 32]
+    }
 
-    /// Number of auctions in this batch
-    pub auction_count: u32,
+    /// Check that `buyer` may buy/bid. `proof` is only used for allowlist membership.
+    ///
+    /// # Errors
+    /// - `PgError::BuyerNotAllowed` if the listing is private and `buyer` is neither
+    ///   `allowed_buyer` nor covered by an allowlist
+    /// - `PgError::InvalidMerkleProof` if the allowlist proof doesn't verify
+    pub fn check_buyer(&self, buyer: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        if !self.is_private() || self.allowed_buyer == *buyer {
+            return Ok(());
+        }
+        require!(self.allowlist_root != [0u8; 32], PgError::BuyerNotAllowed);
+        verify_merkle_proof(&Self::allowlist_leaf(buyer), proof, &self.allowlist_root)
+    }
+}
 
-    /// Timestamp when this batch was committed
-    pub committed_at: i64,
+/// Anti-sniping soft-close parameters for English auctions.
+/// A bid placed within the final `window_secs` pushes `end_time` out by `extension_secs`,
+/// until the cumulative extension reaches `max_extension_secs`. `window_secs == 0` disables.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SoftCloseConfig {
+    pub window_secs: u32,
+    pub extension_secs: u32,
+    pub max_extension_secs: u32,
+}
 
-    /// Starting leaf index for this batch (for proof generation)
-    pub start_leaf_index: u64,
+impl SoftCloseConfig {
+    /// Validate soft-close bounds (disabled config is always valid)
+    pub fn validate(&self) -> Result<()> {
+        if self.window_secs == 0 {
+            return Ok(());
+        }
+        require!(self.window_secs <= MAX_SOFT_CLOSE_SECS, PgError::InvalidTime);
+        require!(
+            self.extension_secs > 0 && self.extension_secs <= MAX_SOFT_CLOSE_SECS,
+            PgError::InvalidTime
+        );
+        require!(
+            self.max_extension_secs <= MAX_SOFT_CLOSE_TOTAL_EXTENSION_SECS,
+            PgError::InvalidTime
+        );
+        Ok(())
+    }
 
-    /// Ending leaf index for this batch (exclusive)
-    pub end_leaf_index: u64,
+    /// Seconds to push `end_time` out for a bid landing `remaining` secs before close,
+    /// given `extended_secs` already applied (0 = outside the window or cap reached)
+    pub fn extension_for(&self, remaining: i64, extended_secs: u32) -> u32 {
+        if self.window_secs == 0 || remaining > i64::from(self.window_secs) {
+            return 0;
+        }
+        let room = self.max_extension_secs.saturating_sub(extended_secs);
+        self.extension_secs.min(room)
+    }
+}
 
-    /// Authority that committed this root (usually server authority)
-    pub committed_by: Pubkey,
+/// Per-game country gating for paid trading, matched against `PlayerLedger.kyc_country`.
+/// When enabled, players without a valid attestation carrying a country are blocked.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JurisdictionPolicy {
+    pub mode: JurisdictionMode,
+    pub countries: [[u8; 2]; MAX_JURISDICTIONS], // ISO 3166-1 alpha-2; [0, 0] = empty slot
+}
 
-    /// Bump seed for PDA derivation
-    pub bump: u8,
+impl JurisdictionPolicy {
+    /// Whether `code` is an uppercase ISO 3166-1 alpha-2 code
+    pub fn is_valid_country(code: [u8; 2]) -> bool {
+        code.iter().all(u8::is_ascii_uppercase)
+    }
 
-    /// Reserved for future use
-    pub reserved: [u8; 23],
-}
+    /// Validate list entries (empty slots are allowed anywhere)
+    pub fn validate(&self) -> Result<()> {
+        for code in &self.countries {
+            require!(
+                *code == [0u8; 2] || Self::is_valid_country(*code),
+                PgError::InvalidCountryCode
+            );
+        }
+        Ok(())
+    }
 
-// ======================================================================
-// NET ENGINE ACCOUNTS
-// ======================================================================
+    /// Check that `ledger`'s attested jurisdiction may trade in this game at `now`.
+    ///
+    /// # Errors
+    /// - `PgError::JurisdictionBlocked` if gating is enabled and the country is blocked,
+    ///   missing or its attestation expired
+    pub fn check(&self, ledger: &PlayerLedger, now: i64) -> Result<()> {
+        if self.mode == JurisdictionMode::Disabled {
+            return Ok(());
+        }
+        let country = ledger.kyc_country;
+        require!(
+            ledger.has_valid_kyc(now) && Self::is_valid_country(country),
+            PgError::JurisdictionBlocked
+        );
+        let listed = self.countries.contains(&country);
+        let permitted = match self.mode {
+            JurisdictionMode::AllowList => listed,
+            JurisdictionMode::DenyList | JurisdictionMode::Disabled => !listed,
+        };
+        require!(permitted, PgError::JurisdictionBlocked);
+        Ok(())
+    }
+}
 
-/// Net engine configuration - controls who can submit net windows
+/// Sealed-bid commitment (one per bidder per listing).
+/// `commitment = keccak(listing || bidder || amount_le || salt)`; the deposit is held in
+/// the bidder's `PlayerLedger.locked` and must cover `amount * quantity_total` at reveal.
 #[account]
-pub struct NetEngineConfig {
-    /// Authority that controls engine parameters (multisig/admin)
-    pub authority: Pubkey,
+pub struct SealedBidCommitment {
+    pub listing: Pubkey,
+    pub bidder: Pubkey,
 
-    /// Engine signer - PDA or dedicated engine wallet (off-chain daemon)
-    pub engine_signer: Pubkey,
+    pub commitment: [u8; 32],
+    pub deposit: u64, // Locked in bidder's PlayerLedger
 
-    /// Monotonic window counter to prevent replay
-    pub last_window_id: u64,
+    pub revealed: bool,
+    pub revealed_amount: u64, // Per-unit bid amount (valid only if revealed)
 
-    /// Bump seed for PDA derivation
+    pub committed_at: i64,
     pub bump: u8,
 
-    /// Reserved for future use
-    pub reserved: [u8; 7],
+    pub reserved: [u8; 16],
 }
 
-/// Net window - represents a single netting window with committed root
+/// Standing offer to buy any item matching `criteria_root` at `price_per_unit`.
+/// Credits for the full remaining quantity are held in the bidder's `PlayerLedger.locked`.
 #[account]
-pub struct NetWindow {
-    /// Unique window identifier per engine
-    pub window_id: u64,
+pub struct CollectionOffer {
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub offer_id: u64,
 
-    /// Net engine this window belongs to
-    pub engine: Pubkey,
+    pub scope: OfferScope,
+    pub criteria_root: [u8; 32], // Merkle root of sha256(item_mint) leaves
 
-    /// Window start timestamp
-    pub start_ts: i64,
+    pub price_per_unit: u64,
+    pub quantity_total: u64,
+    pub quantity_remaining: u64,
 
-    /// Window end timestamp
-    pub end_ts: i64,
+    // Royalties are resolved per accepted item (see accept_collection_offer)
+    pub expires_at: i64,
+    pub created_at: i64,
+    pub bump: u8,
 
-    /// Committed root - merkle/transcript root from off-chain engine
-    pub committed_root: [u8; 32],
+    pub reserved: [u8; 16],
+}
 
-    /// Number of trades folded into this window
-    pub trade_count: u64,
+/// Fixed-price lot of up to `MAX_BUNDLE_ITEMS` different mints, sold atomically.
+/// Each component sits in its own escrow PDA: `[ESCROW_SEED, bundle, item_mint]`.
+#[account]
+pub struct BundleListing {
+    pub game: Pubkey,
+    pub bundle_id: u64,
 
-    /// Total volume in lamports, for invariants/analytics
-    pub volume_lamports: u64,
+    pub seller: Pubkey,
+    pub status: ListingStatus,
+    pub currency_mint: Pubkey,
 
-    /// Whether this window has been settled
-    pub settled: bool,
+    pub item_count: u8,
+    pub item_mints: [Pubkey; MAX_BUNDLE_ITEMS],
+    pub item_quantities: [u64; MAX_BUNDLE_ITEMS],
 
-    /// Bump seed for PDA derivation
+    pub price: u64, // Price for the whole bundle
+
+    pub start_time: i64,
+    pub end_time: i64,
+
+    pub royalty_recipient: Pubkey,
+    pub royalty_bps: u16,
+    pub royalty_split: Pubkey, // RoyaltySplit PDA (Pubkey::default() = royalty_recipient takes all)
+
+    pub created_at: i64,
+    pub updated_at: i64,
     pub bump: u8,
 
-    /// Reserved for future use
-    pub reserved: [u8; 7],
+    pub reserved: [u8; 16],
 }
 
-/// Session Key account - enables off-chain intent signing with on-chain constraints
+/// Item-for-item swap of up to `MAX_SWAP_ITEMS` mints per side. Each maker item sits in its
+/// own escrow `[ESCROW_SEED, offer, item_mint]`; the taker supplies `taker_mints` and an
+/// optional currency `top_up` moves from `top_up_payer` to the other party (fees apply to it only).
 #[account]
-pub struct SessionKey {
-    /// Owner's public key (the player who owns this session key)
-    pub owner: Pubkey,
+pub struct SwapOffer {
+    pub game: Pubkey,
+    pub maker: Pubkey,
+    pub offer_id: u64,
 
-    /// Session key's public key (the key used to sign intents)
-    pub session_key: Pubkey,
+    pub taker: Pubkey,      // Pubkey::default() = open to any player
+    pub counter_to: Pubkey, // Offer this one counters (Pubkey::default() if original)
+    pub status: ListingStatus,
 
-    /// Net engine this session key belongs to
-    pub engine: Pubkey,
+    pub maker_item_count: u8,
+    pub maker_mints: [Pubkey; MAX_SWAP_ITEMS],
+    pub maker_quantities: [u64; MAX_SWAP_ITEMS],
+    pub taker_item_count: u8,
+    pub taker_mints: [Pubkey; MAX_SWAP_ITEMS],
+    pub taker_quantities: [u64; MAX_SWAP_ITEMS],
 
-    /// Maximum volume in lamports this session key can spend
-    pub max_volume_lamports: u64,
+    pub top_up: u64, // Credits paid by `top_up_payer` from PlayerLedger
+    pub top_up_payer: SwapParty, // A maker top-up is locked in the maker's ledger until settled
 
-    /// Current used volume in lamports during session
-    pub used_volume_lamports: u64,
+    // Creator royalty on the top-up, resolved from the items it pays for
+    pub royalty_recipient: Pubkey,
+    pub royalty_bps: u16,
+    pub royalty_split: Pubkey, // RoyaltySplit PDA (Pubkey::default() = royalty_recipient takes all)
 
-    /// Unix timestamp when this session key expires
     pub expires_at: i64,
-
-    /// Whether this session key is frozen (engine or user can freeze if suspicious)
-    pub frozen: bool,
-
-    /// Bump seed for PDA derivation
+    pub created_at: i64,
+    pub updated_at: i64,
     pub bump: u8,
 
-    /// Reserved for future use
-    pub reserved: [u8; 7],
+    pub reserved: [u8; 16],
 }
 
-/// Settled item data - final ownership after netting
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct SettledItemData {
-    /// Item identifier (can be listing_id or item_id depending on system)
-    pub item_id: u64,
-    /// Final owner after netting
-    pub final_owner: Pubkey,
+/// Side of a swap offer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SwapParty {
+    #[default]
+    Taker,
+    Maker,
 }
 
-/// Net delta data - net cash movement per wallet
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct NetDeltaData {
-    /// Wallet public key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+/// Resting order slot (`quantity == 0` marks an empty slot)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BookOrder {
     pub owner: Pubkey,
-    /// Net delta in lamports (can be positive or negative)
-    pub delta_lamports: i64,
+    pub order_id: u64, // Monotonic per book - lower id = earlier (time priority)
+    pub price: u64,    // Per unit
+    pub quantity: u64, // Remaining
+    pub placed_at: i64,
 }
 
-/// Royalty distribution data - agent ID and trade volume for fee calculation
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct RoyaltyDistributionData {
-    /// Agent public key
-    pub agent_id: Pubkey,
-    /// Trade volume in lamports for this agent
-    pub trade_volume: u64,
+/// Per-game, per-item-mint central limit order book for fungible items.
+/// Bids lock `price * quantity` in `PlayerLedger.locked`; asks escrow items in
+/// `[ESCROW_SEED, order_book]`. Crossed orders rest until `match_orders` is cranked.
+/// A full side evicts its worst-priced order for a strictly better one.
+#[account]
+pub struct OrderBook {
+    pub game: Pubkey,
+    pub item_mint: Pubkey,
+
+    pub next_order_id: u64,
+    pub bids: [BookOrder; MAX_BOOK_ORDERS],
+    pub asks: [BookOrder; MAX_BOOK_ORDERS],
+
+    pub last_trade_price: u64,
+    pub total_volume: u64, // Units traded
+
+    pub bump: u8,
+    pub min_notional: u64, // Minimum `price * quantity` of a new order (0 = none)
+
+    // Creator royalty resolved from the item's metadata when the book was created
+    pub royalty_recipient: Pubkey,
+    pub royalty_bps: u16,
+    pub royalty_split: Pubkey, // RoyaltySplit PDA (Pubkey::default() = royalty_recipient takes all)
+
+    pub reserved: [u8; 8],
 }
 
-/// Batch create item data - used for batch compressed listing creation
-#[cfg(feature = "compression")]
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct BatchCreateItem {
-    pub listing_id: u64,
-    pub kind: u8,
+impl OrderBook {
+    /// Orders on one side of the book
+    pub fn side(&self, side: OrderSide) -> &[BookOrder; MAX_BOOK_ORDERS] {
+        match side {
+            OrderSide::Bid => &self.bids,
+            OrderSide::Ask => &self.asks,
+        }
+    }
+
+    /// Mutable orders on one side of the book
+    pub fn side_mut(&mut self, side: OrderSide) -> &mut [BookOrder; MAX_BOOK_ORDERS] {
+        match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        }
+    }
+
+    /// Best order by price-time priority (highest bid / lowest ask, then earliest)
+    pub fn best(&self, side: OrderSide) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        for (i, o) in self.side(side).iter().enumerate() {
+            if o.quantity == 0 {
+                continue;
+            }
+            let better = match best {
+                None => true,
+                Some(b) => {
+                    let cur = &self.side(side)[b];
+                    let price_better = match side {
+                        OrderSide::Bid => o.price > cur.price,
+                        OrderSide::Ask => o.price < cur.price,
+                    };
+                    price_better || (o.price == cur.price && o.order_id < cur.order_id)
+                }
+            };
+            if better {
+                best = Some(i);
+            }
+        }
+        best
+    }
+
+    /// Worst order by price-time priority (lowest bid / highest ask, then latest)
+    pub fn worst(&self, side: OrderSide) -> Option<usize> {
+        let mut worst: Option<usize> = None;
+        for (i, o) in self.side(side).iter().enumerate() {
+            if o.quantity == 0 {
+                continue;
+            }
+            let worse = match worst {
+                None => true,
+                Some(w) => {
+                    let cur = &self.side(side)[w];
+                    let price_worse = match side {
+                        OrderSide::Bid => o.price < cur.price,
+                        OrderSide::Ask => o.price > cur.price,
+                    };
+                    price_worse || (o.price == cur.price && o.order_id > cur.order_id)
+                }
+            };
+            if worse {
+                worst = Some(i);
+            }
+        }
+        worst
+    }
+
+    /// True if `owner` has a resting order on the opposite side that `price` would cross
+    pub fn crosses_own(&self, side: OrderSide, owner: &Pubkey, price: u64) -> bool {
+        let opposite = match side {
+            OrderSide::Bid => OrderSide::Ask,
+            OrderSide::Ask => OrderSide::Bid,
+        };
+        self.side(opposite).iter().any(|o| {
+            o.quantity > 0
+                && o.owner == *owner
+                && match side {
+                    OrderSide::Bid => price >= o.price,
+                    OrderSide::Ask => price <= o.price,
+                }
+        })
+    }
+}
+
+/// Item rental: owner's items wait in escrow `[ESCROW_SEED, rental]`; while rented they sit in
+/// the renter's ATA with the game PDA as delegate, backed by collateral in the renter's ledger.
+#[account]
+pub struct RentalListing {
+    pub game: Pubkey,
+    pub owner: Pubkey,
+    pub rental_id: u64,
+
+    pub item_mint: Pubkey,
     pub quantity: u64,
-    pub price: u64,
-    pub end_time: i64,
-    pub creator: Pubkey,
-    pub royalty_bps: u16,
+
+    pub rent_price: u64,    // Per rental period, paid to owner (fees apply)
+    pub duration_secs: i64, // Rental period length
+    pub collateral: u64,    // Locked in renter's PlayerLedger while rented
+
+    pub status: RentalStatus,
+    pub renter: Pubkey, // Pubkey::default() when not rented
+    pub rented_at: i64,
+    pub expires_at: i64,
+
+    pub created_at: i64,
+    pub bump: u8,
+
+    pub reserved: [u8; 16],
 }
 
-// ======================================================================
-// VESTING & TREASURY ACCOUNTS (Harlock Design)
-// ======================================================================
+/// Reusable royalty split: the royalty is shared between up to `MAX_ROYALTY_RECIPIENTS`
+/// recipients by weight (bps of the royalty, summing to `BPS_DENOM`). Immutable once created.
+#[account]
+pub struct RoyaltySplit {
+    pub game: Pubkey,
+    pub authority: Pubkey,
+    pub split_id: u64,
 
-/// Dev Vesting Vault - Controls dev token allocation with whale-friendly unlock mechanics
-/// Enhanced with cliff period and progressive unlocks
+    pub recipient_count: u8,
+    pub recipients: [Pubkey; MAX_ROYALTY_RECIPIENTS], // recipients[0] is primary and receives dust
+    pub weights: [u16; MAX_ROYALTY_RECIPIENTS],
+
+    pub created_at: i64,
+    pub bump: u8,
+
+    pub reserved: [u8; 16],
+}
+
+impl RoyaltySplit {
+    /// Split `amount` by weight; rounding dust is rolled into recipient 0's share
+    ///
+    /// # Errors
+    /// - `PgError::Overflow` on arithmetic overflow
+    pub fn shares(&self, amount: u64) -> Result<[u64; MAX_ROYALTY_RECIPIENTS]> {
+        let mut shares = [0u64; MAX_ROYALTY_RECIPIENTS];
+        let mut distributed = 0u64;
+        for (share, weight) in shares
+            .iter_mut()
+            .zip(self.weights.iter())
+            .take(usize::from(self.recipient_count))
+            .skip(1)
+        {
+            *share = amount
+                .checked_mul(u64::from(*weight))
+                .ok_or(PgError::Overflow)?
+                .checked_div(BPS_DENOM)
+                .ok_or(PgError::Overflow)?;
+            distributed = distributed.checked_add(*share).ok_or(PgError::Overflow)?;
+        }
+        shares[0] = amount.checked_sub(distributed).ok_or(PgError::Overflow)?;
+        Ok(shares)
+    }
+}
+
+/// Per-game referrer (front-end) account; referral fees accrue here as vault-backed credits
+/// until claimed into the referrer's `PlayerLedger`.
 #[account]
-pub struct DevVestingVault {
-    /// Dev wallet that owns this vault
-    pub dev: Pubkey,
-    
-    /// Token mint being vested
-    pub mint: Pubkey,
-    
-    /// Total amount originally allocated (at TGE)
-    pub total_allocated: u64,
-    
-    /// Amount liquid at TGE (20% of total_allocated)
-    pub liquid_at_tge: u64,
-    
-    /// Total amount originally locked (80% of total_allocated)
-    pub total_locked: u64,
-    
-    /// Currently locked amount (decreases as unlocks happen)
-    pub locked_amount: u64,
-    
-    /// Amount pending unlock (in timelock)
-    pub pending_amount: u64,
-    
-    /// Timestamp when vault was initialized (for cliff calculation)
-    pub initialized_at: i64,
-    
-    /// Timestamp when last unlock request was made
-    pub last_request_time: i64,
-    
-    /// Timestamp when pending unlock becomes available
-    pub unlock_time: i64,
-    
-    /// Maximum unlock % per request (in bps, e.g. 1000 = 10% of locked)
-    pub max_unlock_bps_per_request: u16,
-    
-    /// Cooldown between unlock requests (in seconds, default 30 days)
-    pub cooldown_secs: i64,
-    
-    /// Timelock from request to availability (in seconds, default 30 days)
-    pub timelock_secs: i64,
-    
-    /// Cliff period before first unlock allowed (in seconds, default 6 months)
-    pub cliff_secs: i64,
-    
-    /// Current unlock rate (in bps, 500 = 5% for year 1, 1000 = 10% for year 2+)
-    pub current_unlock_rate_bps: u16,
-    
-    /// Bump seed for PDA derivation
+pub struct ReferralAccount {
+    pub game: Pubkey,
+    pub referrer: Pubkey,
+
+    pub claimable: u64,       // Accrued, not yet claimed
+    pub total_earned: u64,    // Lifetime referral fees
+    pub referred_volume: u64, // Lifetime purchase volume brought in
+
+    pub created_at: i64,
     pub bump: u8,
-    
-    pub reserved: [u8; 7],
+
+    pub reserved: [u8; 16],
 }
 
-/// DAO Treasury Vault - Controls DAO token allocation with governance + timelock
-/// Enhanced with progressive spending limits
+impl ReferralAccount {
+    /// Check the referral may be credited on a `buyer`/`seller` trade in `game`
+    ///
+    /// # Errors
+    /// - `PgError::Unauthorized` if the referral belongs to another game
+    /// - `PgError::SelfReferral` if the referrer is the buyer or the seller
+    pub fn check(&self, game: &Pubkey, buyer: &Pubkey, seller: &Pubkey) -> Result<()> {
+        require!(self.game == *game, PgError::Unauthorized);
+        require!(
+            self.referrer != *buyer && self.referrer != *seller,
+            PgError::SelfReferral
+        );
+        Ok(())
+    }
+}
+
+/// Per-game lazy-mint collection. The game PDA is mint authority for items minted from
+/// metadata-hash auction leaves (Token-2022 mint at `[LAZY_ITEM_SEED, game, metadata_hash]`).
 #[account]
-pub struct DaoTreasuryVault {
-    /// DAO governance program/multisig
-    pub governance: Pubkey,
-    
-    /// Token mint being held
-    pub mint: Pubkey,
-    
-    /// Total amount received (minted/received so far)
-    pub total_received: u64,
-    
-    /// Total treasury balance (current)
-    pub balance: u64,
-    
-    /// Total amount spent (lifetime)
-    pub total_spent: u64,
-    
-    /// Amount pending unlock (after DAO vote + timelock)
-    pub pending_amount: u64,
-    
-    /// Timestamp when pending unlock becomes available
-    pub unlock_time: i64,
-    
-    /// Proposal ID that authorized this unlock (for audit)
-    pub proposal_id: u64,
-    
-    /// Destination for pending unlock
-    pub pending_destination: Pubkey,
-    
-    /// Maximum % of balance that can be spent per period (in bps, progressive)
-    pub max_spend_bps_per_period: u16,
-    
-    /// Period duration in seconds (default 30 days)
-    pub period_secs: i64,
-    
-    /// Current period start timestamp
-    pub current_period_start_ts: i64,
-    
-    /// Amount spent in current period
-    pub spent_this_period: u64,
-    
-    /// Timestamp of last unlock (for period tracking)
-    pub last_unlock_time: i64,
-    
-    /// Timestamp when vault was initialized (for progressive limits)
-    pub initialized_at: i64,
-    
-    /// Bump seed for PDA derivation
+pub struct LazyMintCollection {
+    pub game: Pubkey,
+
+    pub enabled: bool,
+    pub decimals: u8,       // Decimals of minted items (0 = NFT-style)
+    pub minted_count: u64,  // Items minted so far
+
+    pub created_at: i64,
     pub bump: u8,
-    
-    pub reserved: [u8; 7],
+
+    pub reserved: [u8; 16],
 }
 
-// ======================================================================
-// BLACK LEDGER ACCOUNTS (Armageddon Transfer Hook)
-// ======================================================================
+/// Per-game keeper bounty pool for the permissionless activation / expiry cranks.
+/// `balance` is vault-backed credits funded from player ledgers.
+#[account]
+pub struct KeeperPool {
+    pub game: Pubkey,
 
-/// Black Ledger Configuration - Controls transfer hook behavior for PDOX
+    pub activation_bounty: u64, // Paid per crank_activate_listing
+    pub expiry_bounty: u64,     // Paid per crank_expire_listing
+
+    pub balance: u64,    // Credits available for bounties
+    pub total_paid: u64, // Lifetime bounties paid (pool + listing deposits)
+
+    pub bump: u8,
+
+    pub reserved: [u8; 16],
+}
+
+/// Governance-set PDOX price source for one game currency: a PDOX/currency pool read from
+/// its two reserve token accounts (`[PDOX_PRICE_SEED, quote_mint]`).
 #[account]
-pub struct BlackLedgerConfig {
-    /// Mint this config applies to (PDOX)
-    pub mint: Pubkey,
-    
-    /// Current risk score (0-255, 0 = safe, 255 = maximum risk)
-    /// Default: 0 (off by default)
-    pub risk_score: u8,
-    
-    /// Armageddon threshold (0-255, transfers blocked if risk_score >= threshold)
-    /// Default: 255 (effectively disabled)
-    pub armageddon_threshold: u8,
-    
-    /// Minimum amount to trigger quarantine (prevents dust attacks)
-    pub min_quarantine_amount: u64,
-    
-    /// Betrayal ratio threshold (in bps, e.g. 5000 = 50% of balance dumped)
-    pub betrayal_ratio_bps: u16,
-    
-    /// Lifeboat rule: % of balance that can always exit per epoch (in bps, e.g. 2000 = 20%)
-    pub lifeboat_percent_bps: u16,
-    
-    /// Epoch duration in seconds (for rate limiting)
-    pub epoch_duration_secs: i64,
-    
-    /// Authority that can update config (governance)
-    pub authority: Pubkey,
-    
-    /// Timestamp when armageddon_threshold change was proposed (for timelock)
-    pub threshold_change_proposed_at: i64,
-    
-    /// Proposed new armageddon_threshold (pending timelock)
-    pub proposed_threshold: Option<u8>,
-    
-    /// Minimum armageddon_threshold (hard limit, cannot go below this)
-    pub min_armageddon_threshold: u8,
-    
-    /// Bump seed for PDA derivation
+pub struct PdoxPriceSource {
+    pub quote_mint: Pubkey,
+
+    pub pdox_reserve: Pubkey,  // Pool token account holding PDOX
+    pub quote_reserve: Pubkey, // Pool token account holding `quote_mint`
+
+    pub min_quote_liquidity: u64, // Price is only used above this currency reserve
+
+    pub updated_at: i64,
     pub bump: u8,
-    
-    pub reserved: [u8; 7],
+
+    // Carved from reserved: pool price must stay within max_deviation_bps of reference_price
+    pub reference_price: u64,   // PDOX base units per PDOX_PRICE_SCALE currency units
+    pub max_deviation_bps: u16, // Allowed pool price deviation from reference_price
+
+    pub reserved: [u8; 6],
 }
 
-/// Wallet tracking for Black Ledger (per-wallet betrayal score)
+/// One role holder in `GameRoles`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GameRoleMember {
+    pub key: Pubkey, // Pubkey::default() = free slot
+    pub roles: u8,   // `GameRole` bits
+}
+
+/// Per-game role assignments (`[GAME_ROLES_SEED, game]`), managed by the game owner.
+/// The owner keeps its own powers; `KycOperator` must be granted explicitly, even to the owner.
 #[account]
-pub struct BlackLedgerWallet {
-    /// Wallet being tracked
-    pub wallet: Pubkey,
-    
-    /// Mint being tracked
-    pub mint: Pubkey,
-    
-    /// Current betrayal score (increases with dumps)
-    pub betrayal_score: u8,
-    
-    /// Last epoch when transfer occurred
-    pub last_epoch: i64,
-    
-    /// Amount transferred in current epoch (for rate limiting)
-    pub epoch_transfer_amount: u64,
-    
-    /// Bump seed for PDA derivation
+pub struct GameRoles {
+    pub game: Pubkey,
+    pub members: [GameRoleMember; MAX_GAME_ROLE_MEMBERS],
+
     pub bump: u8,
-    
-    pub reserved: [u8; 7],
+
+    pub reserved: [u8; 16],
+}
+
+impl GameRoles {
+    pub fn has_role(&self, key: &Pubkey, role: GameRole) -> bool {
+        *key != Pubkey::default()
+            && self
+                .members
+                .iter()
+                .any(|m| m.key == *key && m.roles & role.bit() != 0)
+    }
 }
 
 // ======================================================================
-// ARMAGEDDON POLICY (Dynamic Timelocks)
+// HYPERSCALE COMPRESSED AUCTION ACCOUNTS
 // ======================================================================
 
-/// Armageddon Policy - Risk-tiered timelock configuration
+/// Configuration for an auction tree (one per game/house).
+/// This account stores metadata about the Merkle tree used for
+/// compressed auction state. The actual tree is managed by
+/// spl-account-compression.
 #[account]
-pub struct ArmageddonPolicy {
-    /// GlobalConfig this policy applies to
-    pub config: Pubkey,
-    
-    /// Timelock for low risk (0-100): default 14 days
-    pub low_risk_timelock_secs: i64,
-    
-    /// Timelock for medium risk (101-200): default 7 days
-    pub medium_risk_timelock_secs: i64,
-    
-    /// Timelock for high risk (201-255): default 2 days
-    pub high_risk_timelock_secs: i64,
-    
-    /// Absolute minimum timelock (never below this): default 1 day
-    pub min_timelock_secs: i64,
-    
-    /// Authority that can update policy (governance)
+pub struct AuctionTreeConfig {
+    /// Game this tree belongs to
+    pub game: Pubkey,
+
+    /// Merkle tree address (managed by spl-account-compression)
+    pub merkle_tree: Pubkey,
+
+    /// Tree depth (max number of leaves = 2^depth)
+    pub max_depth: u8,
+
+    /// Current number of leaves in the tree
+    pub leaf_count: u64,
+
+    /// Authority that can modify the tree (usually game PDA)
     pub authority: Pubkey,
-    
+
     /// Bump seed for PDA derivation
     pub bump: u8,
-    
-    pub reserved: [u64; 8],
+
+    /// Reserved for future use
+    pub reserved: [u8; 31],
 }
 
-/// Config Change Proposal - Tracks Armageddon config changes with dynamic timelocks
+/// Compressed auction root account - stores Merkle roots for batch commits.
+/// Each root represents a batch of auctions (e.g., 1,000 auctions).
+/// This enables committing thousands of auctions in a single transaction.
 #[account]
-pub struct ConfigChangeProposal {
-    /// GlobalConfig this proposal applies to
-    pub config: Pubkey,
-    
-    /// Proposer (DAO governance)
-    pub proposer: Pubkey,
-    
-    /// Timestamp when proposal was created
-    pub created_ts: i64,
-    
-    /// Earliest execution timestamp (computed from risk at creation)
-    pub earliest_execution_ts: i64,
-    
-    /// Whether proposal has been executed
-    pub executed: bool,
-    
-    /// New armageddon threshold (if changing threshold)
-    pub new_armageddon_threshold: Option<u8>,
-    
-    /// Other config changes (stored as hash, details off-chain)
-    pub change_hash: [u8; 32],
-    
+pub struct CompressedAuctionRoot {
+    /// Game this root belongs to
+    pub game: Pubkey,
+
+    /// Batch identifier (unique per batch)
+    pub batch_id: u64,
+
+    /// Merkle root hash representing all auctions in this batch
+    pub root: [u8; 32],
+
+    /// Number of auctions in this batch
+    pub auction_count: u32,
+
+    /// Timestamp when this batch was committed
+    pub committed_at: i64,
+
+    /// Starting leaf index for this batch (for proof generation)
+    pub start_leaf_index: u64,
+
+    /// Ending leaf index for this batch (exclusive)
+    pub end_leaf_index: u64,
+
+    /// Authority that committed this root (usually server authority)
+    pub committed_by: Pubkey,
+
     /// Bump seed for PDA derivation
     pub bump: u8,
-    
-    pub reserved: [u64; 8],
+
+    /// Reserved for future use
+    pub reserved: [u8; 23],
 }
 
 // ======================================================================
-// BUNDLE GUARD (Anti-Dump Protection)
+// NET ENGINE ACCOUNTS
 // ======================================================================
 
-/// Bundle Guard Configuration - Proactive anti-dump mechanism
+/// Net engine configuration - controls who can submit net windows
 #[account]
-pub struct BundleGuardConfig {
-    /// GlobalConfig this guard applies to
-    pub config: Pubkey,
-    
-    /// Whether bundle guard is enabled
-    pub enabled: bool,
-    
-    /// Minimum risk score to enable guard (default 100)
-    pub min_risk_to_enable: u8,
-    
-    /// Maximum accounts per transaction before blocking (default 25)
-    pub max_accounts_per_tx: u16,
-    
-    /// Minimum bundle amount to trigger (default 10% of circulating supply)
-    pub min_bundle_amount: u64,
-    
-    /// Whitelisted programs (DEX routers, netting programs, etc.)
-    pub whitelist_programs: [Pubkey; 8],
-    
-    /// Authority that can update config (governance)
+pub struct NetEngineConfig {
+    /// Authority that controls engine parameters (multisig/admin)
     pub authority: Pubkey,
-    
+
+    /// Engine signer - PDA or dedicated engine wallet (off-chain daemon)
+    pub engine_signer: Pubkey,
+
+    /// Monotonic window counter to prevent replay
+    pub last_window_id: u64,
+
     /// Bump seed for PDA derivation
     pub bump: u8,
-    
-    pub reserved: [u64; 8],
+
+    /// Reserved for future use
+    pub reserved: [u8; 7],
+}
+
+/// Net window - represents a single netting window with committed root
+#[account]
+pub struct NetWindow {
+    /// Unique window identifier per engine
+    pub window_id: u64,
+
+    /// Net engine this window belongs to
+    pub engine: Pubkey,
+
+    /// Window start timestamp
+    pub start_ts: i64,
+
+    /// Window end timestamp
+    pub end_ts: i64,
+
+    /// Committed root - merkle/transcript root from off-chain engine
+    pub committed_root: [u8; 32],
+
+    /// Number of trades folded into this window
+    pub trade_count: u64,
+
+    /// Total volume in lamports, for invariants/analytics
+    pub volume_lamports: u64,
+
+    /// Whether this window has been settled
+    pub settled: bool,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+
+    /// Reserved for future use
+    pub reserved: [u8; 7],
+}
+
+/// Session Key account - enables off-chain intent signing with on-chain constraints
+#[account]
+pub struct SessionKey {
+    /// Owner's public key (the player who owns this session key)
+    pub owner: Pubkey,
+
+    /// Session key's public key (the key used to sign intents)
+    pub session_key: Pubkey,
+
+    /// Net engine this session key belongs to
+    pub engine: Pubkey,
+
+    /// Maximum volume in lamports this session key can spend
+    pub max_volume_lamports: u64,
+
+    /// Current used volume in lamports during session
+    pub used_volume_lamports: u64,
+
+    /// Unix timestamp when this session key expires
+    pub expires_at: i64,
+
+    /// Whether this session key is frozen (engine or user can freeze if suspicious)
+    pub frozen: bool,
+
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+
+    /// Reserved for future use
+    pub reserved: [u8; 7],
+}
+
+/// Settled item data - final ownership after netting
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SettledItemData {
+    /// Item identifier (can be listing_id or item_id depending on system)
+    pub item_id: u64,
+    /// Final owner after netting
+    pub final_owner: Pubkey,
+}
+
+/// Net delta data - net cash movement per wallet
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct NetDeltaData {
+    /// Wallet public key
+    pub owner: Pubkey,
+    /// Net delta in lamports (can be positive or negative)
+    pub delta_lamports: i64,
+}
+
+/// Net batch trade volume per wallet and game, charged tiered maker/taker fees on-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct NetTradeVolumeData {
+    /// Wallet public key
+    pub owner: Pubkey,
+    /// Game whose ledger is charged
+    pub game: Pubkey,
+    /// Volume the wallet traded as maker (resting side) in this batch
+    pub maker_volume: u64,
+    /// Volume the wallet traded as taker in this batch
+    pub taker_volume: u64,
+}
+
+/// Royalty distribution data - agent ID and trade volume for fee calculation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RoyaltyDistributionData {
+    /// Agent public key
+    pub agent_id: Pubkey,
+    /// Trade volume in lamports for this agent
+    pub trade_volume: u64,
+}
+
+/// Batch create item data - used for batch compressed listing creation
+#[cfg(feature = "compression")]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BatchCreateItem {
+    pub listing_id: u64,
+    pub kind: u8,
+    pub quantity: u64,
+    pub price: u64,
+    pub end_time: i64,
+    pub creator: Pubkey,
+    pub royalty_bps: u16,
 }
 
 // ======================================================================
-// LP GROWTH MANAGER (Unruggable LP System)
+// VESTING & TREASURY ACCOUNTS (Harlock Design)
 // ======================================================================
 
-/// LP Growth Manager - Self-guarding, unruggable LP growth mechanism
-/// Uses accumulated fees to grow LP without holder dilution
+/// Dev Vesting Vault - Controls dev token allocation with whale-friendly unlock mechanics
+/// Enhanced with cliff period and progressive unlocks
 #[account]
-pub struct LpGrowthManager {
-    /// PDOX mint
-    pub pdox_mint: Pubkey,
-    
-    /// SOL mint (native SOL)
-    pub sol_mint: Pubkey,
+pub struct DevVestingVault {
+    /// Dev wallet that owns this vault
+    pub dev: Pubkey,
     
-    /// Raydium LP token mint (or other DEX LP)
-    pub lp_mint: Pubkey,
+    /// Token mint being vested
+    pub mint: Pubkey,
     
-    /// LP token account (owned by PDA, holds LP tokens)
-    pub lp_token_account: Pubkey,
+    /// Total amount originally allocated (at TGE)
+    pub total_allocated: u64,
     
-    /// SOL fee accumulation account (accumulates protocol fees in SOL)
-    pub fee_accumulation_account: Pubkey,
+    /// Amount liquid at TGE (20% of total_allocated)
+    pub liquid_at_tge: u64,
     
-    /// Current LP value in SOL (tracked for ratio calculations)
-    pub current_lp_sol_value: u64,
+    /// Total amount originally locked (80% of total_allocated)
+    pub total_locked: u64,
     
-    /// Current LP value in PDOX (tracked for ratio calculations)
-    pub current_lp_pdox_value: u64,
+    /// Currently locked amount (decreases as unlocks happen)
+    pub locked_amount: u64,
     
-    /// Total fees accumulated (lifetime)
-    pub total_fees_accumulated: u64,
+    /// Amount pending unlock (in timelock)
+    pub pending_amount: u64,
     
-    /// Total fees used for LP growth (lifetime)
-    pub total_fees_used_for_growth: u64,
+    /// Timestamp when vault was initialized (for cliff calculation)
+    pub initialized_at: i64,
     
-    /// Minimum fee accumulation before LP growth (default 0.1 SOL)
-    pub min_fee_threshold: u64,
+    /// Timestamp when last unlock request was made
+    pub last_request_time: i64,
     
-    /// Whether LP growth is enabled
-    pub growth_enabled: bool,
+    /// Timestamp when pending unlock becomes available
+    pub unlock_time: i64,
     
-    /// Whether LP withdrawal is locked (emergency pause)
-    pub withdrawal_locked: bool,
+    /// Maximum unlock % per request (in bps, e.g. 1000 = 10% of locked)
+    pub max_unlock_bps_per_request: u16,
     
-    /// Timestamp when withdrawal lock expires (0 = no lock)
-    pub withdrawal_lock_expires_at: i64,
+    /// Cooldown between unlock requests (in seconds, default 30 days)
+    pub cooldown_secs: i64,
     
-    /// Maximum withdrawal per period (in SOL, default 10% of LP)
-    pub max_withdrawal_per_period: u64,
+    /// Timelock from request to availability (in seconds, default 30 days)
+    pub timelock_secs: i64,
     
-    /// Period duration for withdrawals (default 30 days)
-    pub withdrawal_period_secs: i64,
+    /// Cliff period before first unlock allowed (in seconds, default 6 months)
+    pub cliff_secs: i64,
     
-    /// Current period start for withdrawals
-    pub current_withdrawal_period_start: i64,
+    /// Current unlock rate (in bps, 500 = 5% for year 1, 1000 = 10% for year 2+)
+    pub current_unlock_rate_bps: u16,
     
-    /// Amount withdrawn in current period
-    pub withdrawn_this_period: u64,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
     
-    /// Authority that can update config (governance)
-    pub authority: Pubkey,
+    pub reserved: [u8; 7],
+}
+
+/// DAO Treasury Vault - Controls DAO token allocation with governance + timelock
+/// Enhanced with progressive spending limits
+#[account]
+pub struct DaoTreasuryVault {
+    /// DAO governance program/multisig
+    pub governance: Pubkey,
     
-    /// Emergency authority (multisig, can pause but not withdraw)
-    pub emergency_authority: Pubkey,
+    /// Token mint being held
+    pub mint: Pubkey,
     
-    /// Timestamp when LP was initialized
-    pub initialized_at: i64,
+    /// Total amount received (minted/received so far)
+    pub total_received: u64,
     
-    /// Last LP growth timestamp
-    pub last_growth_ts: i64,
+    /// Total treasury balance (current)
+    pub balance: u64,
     
-    /// Cooldown between LP growth operations (default 24 hours)
-    pub growth_cooldown_secs: i64,
+    /// Total amount spent (lifetime)
+    pub total_spent: u64,
     
-    /// AI Sentinel: Minimum liquidity depth threshold (in SOL, default 10 SOL)
-    /// Auto-pauses LP growth if liquidity drops below this threshold
-    pub min_liquidity_threshold: u64,
+    /// Amount pending unlock (after DAO vote + timelock)
+    pub pending_amount: u64,
     
-    /// AI Sentinel: Maximum impermanent loss percentage (in bps, default 500 = 5%)
-    /// Auto-pauses LP growth if IL exceeds this threshold
-    pub max_il_bps: u16,
+    /// Timestamp when pending unlock becomes available
+    pub unlock_time: i64,
     
-    /// AI Sentinel: Risk score (0-255, similar to Armageddon)
-    /// Updated by off-chain sentinel based on LP health metrics
-    pub risk_score: u8,
+    /// Proposal ID that authorized this unlock (for audit)
+    pub proposal_id: u64,
     
-    /// AI Sentinel: Last health check timestamp
-    pub last_health_check_ts: i64,
+    /// Destination for pending unlock
+    pub pending_destination: Pubkey,
     
-    /// Bump seed for PDA derivation
-    pub bump: u8,
+    /// Maximum % of balance that can be spent per period (in bps, progressive)
+    pub max_spend_bps_per_period: u16,
     
-    pub reserved: [u64; 8],
+    /// Period duration in seconds (default 30 days)
+    pub period_secs: i64,
+    
+    /// Current period start timestamp
+    pub current_period_start_ts: i64,
+    
+    /// Amount spent in current period
+    pub spent_this_period: u64,
+    
+    /// Timestamp of last unlock (for period tracking)
+    pub last_unlock_time: i64,
+    
+    /// Timestamp when vault was initialized (for progressive limits)
+    pub initialized_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    pub reserved: [u8; 7],
 }
 
 // ======================================================================
-// AGENT MARKETPLACE ACCOUNTS (Enhanced)
+// BLACK LEDGER ACCOUNTS (Armageddon Transfer Hook)
 // ======================================================================
 
-/// Agent Vault - User deposits for agent trading
+/// Black Ledger Configuration - Controls transfer hook behavior for PDOX
 #[account]
-pub struct AgentVault {
-    /// User who owns this vault
-    pub owner: Pubkey,
+pub struct BlackLedgerConfig {
+    /// Mint this config applies to (PDOX)
+    pub mint: Pubkey,
     
-    /// Agent this vault is for
-    pub agent: Pubkey,
+    /// Current risk score (0-255, 0 = safe, 255 = maximum risk)
+    /// Default: 0 (off by default)
+    pub risk_score: u8,
     
-    /// Token mint deposited
-    pub mint: Pubkey,
+    /// Armageddon threshold (0-255, transfers blocked if risk_score >= threshold)
+    /// Default: 255 (effectively disabled)
+    pub armageddon_threshold: u8,
     
-    /// Current balance
-    pub balance: u64,
+    /// Minimum amount to trigger quarantine (prevents dust attacks)
+    pub min_quarantine_amount: u64,
     
-    /// Maximum daily volume allowed (in lamports)
-    pub max_daily_volume: u64,
+    /// Betrayal ratio threshold (in bps, e.g. 5000 = 50% of balance dumped)
+    pub betrayal_ratio_bps: u16,
     
-    /// Maximum per-trade size (in lamports)
-    pub max_per_trade_size: u64,
+    /// Lifeboat rule: % of balance that can always exit per epoch (in bps, e.g. 2000 = 20%)
+    pub lifeboat_percent_bps: u16,
     
-    /// Whitelisted marketplaces/collections (Pubkey array, stored off-chain, hash here)
-    pub allowed_markets_hash: [u8; 32],
+    /// Epoch duration in seconds (for rate limiting)
+    pub epoch_duration_secs: i64,
+    
+    /// Authority that can update config (governance)
+    pub authority: Pubkey,
+    
+    /// Timestamp when armageddon_threshold change was proposed (for timelock)
+    pub threshold_change_proposed_at: i64,
+    
+    /// Proposed new armageddon_threshold (pending timelock)
+    pub proposed_threshold: Option<u8>,
+    
+    /// Minimum armageddon_threshold (hard limit, cannot go below this)
+    pub min_armageddon_threshold: u8,
     
     /// Bump seed for PDA derivation
     pub bump: u8,
@@ -919,26 +1804,23 @@ pub struct AgentVault {
     pub reserved: [u8; 7],
 }
 
-/// Agent Performance Metrics - Tracks agent performance for dashboards
+/// Wallet tracking for Black Ledger (per-wallet betrayal score)
 #[account]
-pub struct AgentPerformance {
-    /// Agent this metrics are for
-    pub agent: Pubkey,
-    
-    /// Total volume processed (in lamports)
-    pub total_volume: u64,
+pub struct BlackLedgerWallet {
+    /// Wallet being tracked
+    pub wallet: Pubkey,
     
-    /// Total PnL (in lamports, can be negative)
-    pub total_pnl: i64,
+    /// Mint being tracked
+    pub mint: Pubkey,
     
-    /// Maximum drawdown (in lamports)
-    pub max_drawdown: u64,
+    /// Current betrayal score (increases with dumps)
+    pub betrayal_score: u8,
     
-    /// Number of trades executed
-    pub trade_count: u64,
+    /// Last epoch when transfer occurred
+    pub last_epoch: i64,
     
-    /// Last update timestamp
-    pub last_update: i64,
+    /// Amount transferred in current epoch (for rate limiting)
+    pub epoch_transfer_amount: u64,
     
     /// Bump seed for PDA derivation
     pub bump: u8,
@@ -947,179 +1829,888 @@ pub struct AgentPerformance {
 }
 
 // ======================================================================
-// INTEGRATION CONFIG ACCOUNTS (Safe Upgrade Mechanism)
+// ARMAGEDDON POLICY (Dynamic Timelocks)
 // ======================================================================
 
-/// Integration Configuration - Stores integration endpoints that can be safely updated
-/// This allows updating mobile/oracle/notification integrations without relaunching the program
-/// 
-/// SECURITY MODEL:
-/// - Only integration endpoints can be updated (NOT core program logic)
-/// - Updates require DAO vote + 7-14 day timelock
-/// - No ability to change treasury, fees, or core functionality
-/// - Full audit trail via events
+/// Armageddon Policy - Risk-tiered timelock configuration
 #[account]
-pub struct IntegrationConfig {
-    /// Governance that controls integration updates
-    pub governance: Pubkey,
+pub struct ArmageddonPolicy {
+    /// GlobalConfig this policy applies to
+    pub config: Pubkey,
     
-    /// Version number (increments with each update)
-    pub version: u32,
+    /// Timelock for low risk (0-100): default 14 days
+    pub low_risk_timelock_secs: i64,
     
-    /// Firebase Cloud Messaging endpoint (for Android push notifications)
-    pub fcm_endpoint: String, // Max 200 chars
+    /// Timelock for medium risk (101-200): default 7 days
+    pub medium_risk_timelock_secs: i64,
     
-    /// Apple Push Notification Service endpoint (for iOS push notifications)
-    pub apns_endpoint: String, // Max 200 chars
+    /// Timelock for high risk (201-255): default 2 days
+    pub high_risk_timelock_secs: i64,
     
-    /// Oracle endpoint (for price feeds, randomness, etc.)
-    pub oracle_endpoint: String, // Max 200 chars
+    /// Absolute minimum timelock (never below this): default 1 day
+    pub min_timelock_secs: i64,
     
-    /// Notification service endpoint (email, SMS, etc.)
-    pub notification_endpoint: String, // Max 200 chars
+    /// Authority that can update policy (governance)
+    pub authority: Pubkey,
     
-    /// Data availability endpoint (IPFS, Arweave, etc.)
-    pub data_availability_endpoint: String, // Max 200 chars
+    /// Bump seed for PDA derivation
+    pub bump: u8,
     
-    /// Pending update (stored until timelock expires)
-    pub pending_update: Option<IntegrationConfigData>,
+    pub reserved: [u64; 8],
+}
+
+/// Config Change Proposal - Tracks Armageddon config changes with dynamic timelocks
+#[account]
+pub struct ConfigChangeProposal {
+    /// GlobalConfig this proposal applies to
+    pub config: Pubkey,
     
-    /// Timestamp when update was proposed
-    pub update_proposed_at: i64,
+    /// Proposer (DAO governance)
+    pub proposer: Pubkey,
     
-    /// Timestamp when update becomes executable
-    pub update_unlock_time: i64,
+    /// Timestamp when proposal was created
+    pub created_ts: i64,
+    
+    /// Earliest execution timestamp (computed from risk at creation)
+    pub earliest_execution_ts: i64,
+    
+    /// Whether proposal has been executed
+    pub executed: bool,
+    
+    /// New armageddon threshold (if changing threshold)
+    pub new_armageddon_threshold: Option<u8>,
+    
+    /// Other config changes (stored as hash, details off-chain)
+    pub change_hash: [u8; 32],
     
     /// Bump seed for PDA derivation
     pub bump: u8,
     
-    pub reserved: [u8; 7],
+    pub reserved: [u64; 8],
+}
+
+// ======================================================================
+// BUNDLE GUARD (Anti-Dump Protection)
+// ======================================================================
+
+/// Bundle Guard Configuration - Proactive anti-dump mechanism
+#[account]
+pub struct BundleGuardConfig {
+    /// GlobalConfig this guard applies to
+    pub config: Pubkey,
+    
+    /// Whether bundle guard is enabled
+    pub enabled: bool,
+    
+    /// Minimum risk score to enable guard (default 100)
+    pub min_risk_to_enable: u8,
+    
+    /// Maximum accounts per transaction before blocking (default 25)
+    pub max_accounts_per_tx: u16,
+    
+    /// Minimum bundle amount to trigger (default 10% of circulating supply)
+    pub min_bundle_amount: u64,
+    
+    /// Whitelisted programs (DEX routers, netting programs, etc.)
+    pub whitelist_programs: [Pubkey; 8],
+    
+    /// Authority that can update config (governance)
+    pub authority: Pubkey,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    pub reserved: [u64; 8],
+}
+
+// ======================================================================
+// LP GROWTH MANAGER (Unruggable LP System)
+// ======================================================================
+
+/// LP Growth Manager - Self-guarding, unruggable LP growth mechanism
+/// Uses accumulated fees to grow LP without holder dilution
+#[account]
+pub struct LpGrowthManager {
+    /// PDOX mint
+    pub pdox_mint: Pubkey,
+    
+    /// SOL mint (native SOL)
+    pub sol_mint: Pubkey,
+    
+    /// Raydium LP token mint (or other DEX LP)
+    pub lp_mint: Pubkey,
+    
+    /// LP token account (owned by PDA, holds LP tokens)
+    pub lp_token_account: Pubkey,
+    
+    /// SOL fee accumulation account (accumulates protocol fees in SOL)
+    pub fee_accumulation_account: Pubkey,
+    
+    /// Current LP value in SOL (tracked for ratio calculations)
+    pub current_lp_sol_value: u64,
+    
+    /// Current LP value in PDOX (tracked for ratio calculations)
+    pub current_lp_pdox_value: u64,
+    
+    /// Total fees accumulated (lifetime)
+    pub total_fees_accumulated: u64,
+    
+    /// Total fees used for LP growth (lifetime)
+    pub total_fees_used_for_growth: u64,
+    
+    /// Minimum fee accumulation before LP growth (default 0.1 SOL)
+    pub min_fee_threshold: u64,
+    
+    /// Whether LP growth is enabled
+    pub growth_enabled: bool,
+    
+    /// Whether LP withdrawal is locked (emergency pause)
+    pub withdrawal_locked: bool,
+    
+    /// Timestamp when withdrawal lock expires (0 = no lock)
+    pub withdrawal_lock_expires_at: i64,
+    
+    /// Maximum withdrawal per period (in SOL, default 10% of LP)
+    pub max_withdrawal_per_period: u64,
+    
+    /// Period duration for withdrawals (default 30 days)
+    pub withdrawal_period_secs: i64,
+    
+    /// Current period start for withdrawals
+    pub current_withdrawal_period_start: i64,
+    
+    /// Amount withdrawn in current period
+    pub withdrawn_this_period: u64,
+    
+    /// Authority that can update config (governance)
+    pub authority: Pubkey,
+    
+    /// Emergency authority (multisig, can pause but not withdraw)
+    pub emergency_authority: Pubkey,
+    
+    /// Timestamp when LP was initialized
+    pub initialized_at: i64,
+    
+    /// Last LP growth timestamp
+    pub last_growth_ts: i64,
+    
+    /// Cooldown between LP growth operations (default 24 hours)
+    pub growth_cooldown_secs: i64,
+    
+    /// AI Sentinel: Minimum liquidity depth threshold (in SOL, default 10 SOL)
+    /// Auto-pauses LP growth if liquidity drops below this threshold
+    pub min_liquidity_threshold: u64,
+    
+    /// AI Sentinel: Maximum impermanent loss percentage (in bps, default 500 = 5%)
+    /// Auto-pauses LP growth if IL exceeds this threshold
+    pub max_il_bps: u16,
+    
+    /// AI Sentinel: Risk score (0-255, similar to Armageddon)
+    /// Updated by off-chain sentinel based on LP health metrics
+    pub risk_score: u8,
+    
+    /// AI Sentinel: Last health check timestamp
+    pub last_health_check_ts: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    pub reserved: [u64; 8],
+}
+
+// ======================================================================
+// AGENT MARKETPLACE ACCOUNTS (Enhanced)
+// ======================================================================
+
+/// Agent Vault - User deposits for agent trading
+#[account]
+pub struct AgentVault {
+    /// User who owns this vault
+    pub owner: Pubkey,
+    
+    /// Agent this vault is for
+    pub agent: Pubkey,
+    
+    /// Token mint deposited
+    pub mint: Pubkey,
+    
+    /// Current balance
+    pub balance: u64,
+    
+    /// Maximum daily volume allowed (in lamports)
+    pub max_daily_volume: u64,
+    
+    /// Maximum per-trade size (in lamports)
+    pub max_per_trade_size: u64,
+    
+    /// Whitelisted marketplaces/collections (Pubkey array, stored off-chain, hash here)
+    pub allowed_markets_hash: [u8; 32],
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    pub reserved: [u8; 7],
+}
+
+/// Agent Performance Metrics - Tracks agent performance for dashboards
+#[account]
+pub struct AgentPerformance {
+    /// Agent this metrics are for
+    pub agent: Pubkey,
+    
+    /// Total volume processed (in lamports)
+    pub total_volume: u64,
+    
+    /// Total PnL (in lamports, can be negative)
+    pub total_pnl: i64,
+    
+    /// Maximum drawdown (in lamports)
+    pub max_drawdown: u64,
+    
+    /// Number of trades executed
+    pub trade_count: u64,
+    
+    /// Last update timestamp
+    pub last_update: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    pub reserved: [u8; 7],
+}
+
+// ======================================================================
+// INTEGRATION CONFIG ACCOUNTS (Safe Upgrade Mechanism)
+// ======================================================================
+
+/// Integration Configuration - Stores integration endpoints that can be safely updated
+/// This allows updating mobile/oracle/notification integrations without relaunching the program
+/// 
+/// SECURITY MODEL:
+/// - Only integration endpoints can be updated (NOT core program logic)
+/// - Updates require DAO vote + 7-14 day timelock
+/// - No ability to change treasury, fees, or core functionality
+/// - Full audit trail via events
+#[account]
+pub struct IntegrationConfig {
+    /// Governance that controls integration updates
+    pub governance: Pubkey,
+    
+    /// Version number (increments with each update)
+    pub version: u32,
+    
+    /// Firebase Cloud Messaging endpoint (for Android push notifications)
+    pub fcm_endpoint: String, // Max 200 chars
+    
+    /// Apple Push Notification Service endpoint (for iOS push notifications)
+    pub apns_endpoint: String, // Max 200 chars
+    
+    /// Oracle endpoint (for price feeds, randomness, etc.)
+    pub oracle_endpoint: String, // Max 200 chars
+    
+    /// Notification service endpoint (email, SMS, etc.)
+    pub notification_endpoint: String, // Max 200 chars
+    
+    /// Data availability endpoint (IPFS, Arweave, etc.)
+    pub data_availability_endpoint: String, // Max 200 chars
+    
+    /// Pending update (stored until timelock expires)
+    pub pending_update: Option<IntegrationConfigData>,
+    
+    /// Timestamp when update was proposed
+    pub update_proposed_at: i64,
+    
+    /// Timestamp when update becomes executable
+    pub update_unlock_time: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    pub reserved: [u8; 7],
+}
+
+/// Integration config data (used for updates)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct IntegrationConfigData {
+    pub fcm_endpoint: String,
+    pub apns_endpoint: String,
+    pub oracle_endpoint: String,
+    pub notification_endpoint: String,
+    pub data_availability_endpoint: String,
+}
+
+// ======================================================================
+// EVENTS
+// ======================================================================
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub governance: Pubkey,
+    pub server_authority: Pubkey,
+    pub protocol_fee_bps: u16,
+}
+
+#[event]
+pub struct GameCreated {
+    pub game: Pubkey,
+    pub game_id: u64,
+    pub owner: Pubkey,
+    pub currency_mint: Pubkey,
+}
+
+#[event]
+pub struct CreditsDeposited {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CreditsWithdrawn {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+/// Configuration update parameters for `update_config` instruction.
+/// This struct reduces the argument count and improves API ergonomics.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ConfigUpdateParams {
+    pub new_admin: Option<Pubkey>,
+    pub new_governance: Option<Pubkey>,
+    pub new_server: Option<Pubkey>,
+    pub new_protocol_fee_bps: Option<u16>,
+    pub paused_new: Option<bool>,
+    pub paused_settlements: Option<bool>,
+    pub new_features: Option<u64>,
+    pub new_protocol_treasury: Option<Pubkey>,
+    /// Update PDOX token mint address
+    pub new_pdox_mint: Option<Pubkey>,
+    /// Update WSOL mint address
+    pub new_wsol_mint: Option<Pubkey>,
+    /// Update USDC mint address
+    pub new_usdc_mint: Option<Pubkey>,
+    /// Update LP Pool address (Meteora/Raydium)
+    pub new_lp_pool: Option<Pubkey>,
+    /// Update referrer share of the protocol fee (bps of the protocol fee)
+    pub new_referral_bps: Option<u16>,
+    /// Update per-tier KYC trade limits (indexed by `KycTier`)
+    pub new_kyc_tier_limits: Option<[KycTierLimits; KYC_TIER_COUNT]>,
+    /// Update the default KYC attestation lifetime (0 = never expires)
+    pub new_kyc_attestation_ttl_secs: Option<i64>,
+    /// Replace the maker/taker fee tier table (empty = flat `protocol_fee_bps`)
+    pub new_fee_tiers: Option<Vec<FeeTier>>,
+    /// Update the pdox_stake program ID used to verify stake accounts
+    pub new_pdox_stake_program: Option<Pubkey>,
+    /// Replace the PDOX staker discount table (empty = no discounts)
+    pub new_stake_discount_tiers: Option<Vec<StakeDiscountTier>>,
+    /// Update the protocol fee router splits (all zero = router off)
+    pub new_fee_router: Option<FeeRouterSplits>,
+}
+
+/// Game update parameters for `update_game` instruction.
+/// This struct reduces the argument count and improves API ergonomics.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct GameUpdateParams {
+    pub new_currency_mint: Option<Pubkey>,
+    pub new_fee_bps: Option<u16>,
+    pub new_cancel_penalty_bps: Option<u16>,
+    pub kyc_required: Option<bool>,
+    pub use_token_2022: Option<bool>,
+    pub paused_new: Option<bool>,
+    pub paused_settlements: Option<bool>,
+    pub new_payout_wallet: Option<Pubkey>,
+    pub soft_close: Option<SoftCloseConfig>,
+    pub royalty_enforcement: Option<RoyaltyEnforcement>,
+    pub jurisdictions: Option<JurisdictionPolicy>,
+}
+
+impl GameUpdateParams {
+    /// Whether the pause flags change (`GameRole::Pauser`)
+    pub fn touches_pause(&self) -> bool {
+        self.paused_new.is_some() || self.paused_settlements.is_some()
+    }
+
+    /// Whether any other field except the payout wallet changes (`GameRole::ConfigManager`)
+    pub fn touches_config(&self) -> bool {
+        self.new_currency_mint.is_some()
+            || self.new_fee_bps.is_some()
+            || self.new_cancel_penalty_bps.is_some()
+            || self.kyc_required.is_some()
+            || self.use_token_2022.is_some()
+            || self.soft_close.is_some()
+            || self.royalty_enforcement.is_some()
+            || self.jurisdictions.is_some()
+    }
+}
+
+#[event]
+pub struct ListingCreated {
+    pub game: Pubkey,
+    pub listing: Pubkey,
+    pub listing_id: u64,
+    pub seller: Pubkey,
+    pub kind: ListingKind,
+    pub quantity_total: u64,
+    pub start_price: u64,
+}
+
+#[event]
+pub struct ListingActivated {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub at: i64,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub seller: Pubkey,
+    pub start_price: u64,
+    pub buy_now_price: u64,
+    pub reserve_price: u64,
+    pub quantity_total: u64,
+    pub quantity_remaining: u64,
+    pub version: u64,
+}
+
+#[event]
+pub struct ListingCancelled {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub seller: Pubkey,
+    pub penalty_charged: u64,
+}
+
+#[event]
+pub struct FixedSaleExecuted {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub buyer: Pubkey,
+    pub quantity: u64,
+    pub price_total: u64,
+    pub protocol_fee: u64,
+    pub game_fee: u64,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub bid_amount: u64,
+    pub previous_highest_bid: u64,
+}
+
+#[event]
+pub struct AuctionExtended {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub previous_end_time: i64,
+    pub new_end_time: i64,
+    pub total_extended_secs: u32,
+}
+
+#[event]
+pub struct SealedBidCommitted {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct SealedBidRevealed {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub is_highest: bool,
+}
+
+#[event]
+pub struct SealedBidDepositClaimed {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub refunded: u64,
+    pub forfeited: u64,
+}
+
+#[event]
+pub struct CollectionOfferCreated {
+    pub offer: Pubkey,
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub scope: OfferScope,
+    pub criteria_root: [u8; 32],
+    pub price_per_unit: u64,
+    pub quantity: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct CollectionOfferAccepted {
+    pub offer: Pubkey,
+    pub game: Pubkey,
+    pub seller: Pubkey,
+    pub bidder: Pubkey,
+    pub item_mint: Pubkey,
+    pub quantity: u64,
+    pub price_total: u64,
+    pub protocol_fee: u64,
+    pub game_fee: u64,
+}
+
+#[event]
+pub struct CollectionOfferCancelled {
+    pub offer: Pubkey,
+    pub game: Pubkey,
+    pub bidder: Pubkey,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct BundleListingCreated {
+    pub bundle: Pubkey,
+    pub game: Pubkey,
+    pub bundle_id: u64,
+    pub seller: Pubkey,
+    pub item_count: u8,
+    pub price: u64,
+}
+
+#[event]
+pub struct BundleSold {
+    pub bundle: Pubkey,
+    pub game: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+    pub protocol_fee: u64,
+    pub game_fee: u64,
+    pub royalty_amount: u64,
+}
+
+#[event]
+pub struct BundleCancelled {
+    pub bundle: Pubkey,
+    pub game: Pubkey,
+    pub seller: Pubkey,
+}
+
+#[event]
+pub struct SwapOfferCreated {
+    pub offer: Pubkey,
+    pub game: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub counter_to: Pubkey,
+    pub maker_mints: Vec<Pubkey>,
+    pub maker_quantities: Vec<u64>,
+    pub taker_mints: Vec<Pubkey>,
+    pub taker_quantities: Vec<u64>,
+    pub top_up: u64,
+    pub top_up_payer: SwapParty,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct SwapOfferAccepted {
+    pub offer: Pubkey,
+    pub game: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub top_up: u64,
+    pub top_up_payer: SwapParty,
+    pub protocol_fee: u64,
+    pub game_fee: u64,
+    pub royalty_amount: u64,
+}
+
+#[event]
+pub struct SwapOfferCancelled {
+    pub offer: Pubkey,
+    pub game: Pubkey,
+    pub maker: Pubkey,
+    pub top_up_refunded: u64,
+}
+
+#[event]
+pub struct OrderBookCreated {
+    pub order_book: Pubkey,
+    pub game: Pubkey,
+    pub item_mint: Pubkey,
+}
+
+#[event]
+pub struct OrderPlaced {
+    pub order_book: Pubkey,
+    pub game: Pubkey,
+    pub owner: Pubkey,
+    pub order_id: u64,
+    pub side: OrderSide,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+#[event]
+pub struct OrderCancelled {
+    pub order_book: Pubkey,
+    pub game: Pubkey,
+    pub owner: Pubkey,
+    pub order_id: u64,
+    pub side: OrderSide,
+    pub quantity_returned: u64,
+}
+
+#[event]
+pub struct OrdersMatched {
+    pub order_book: Pubkey,
+    pub game: Pubkey,
+    pub bid_order_id: u64,
+    pub ask_order_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub quantity: u64,
+    pub protocol_fee: u64,
+    pub game_fee: u64,
+    pub royalty_amount: u64,
+}
+
+#[event]
+pub struct RentalListed {
+    pub rental: Pubkey,
+    pub game: Pubkey,
+    pub owner: Pubkey,
+    pub item_mint: Pubkey,
+    pub quantity: u64,
+    pub rent_price: u64,
+    pub duration_secs: i64,
+    pub collateral: u64,
+}
+
+#[event]
+pub struct ItemRented {
+    pub rental: Pubkey,
+    pub game: Pubkey,
+    pub owner: Pubkey,
+    pub renter: Pubkey,
+    pub rent_price: u64,
+    pub collateral: u64,
+    pub expires_at: i64,
+    pub protocol_fee: u64,
+    pub game_fee: u64,
+}
+
+#[event]
+pub struct RentalEnded {
+    pub rental: Pubkey,
+    pub game: Pubkey,
+    pub renter: Pubkey,
+    pub returned: bool,
+    pub collateral_forfeited: u64,
+}
+
+#[event]
+pub struct RentalCancelled {
+    pub rental: Pubkey,
+    pub game: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ListingExpired {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub seller: Pubkey,
+    pub quantity_returned: u64,
+}
+
+#[event]
+pub struct KeeperBountyPaid {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub keeper: Pubkey,
+    pub amount: u64,
+    pub from_deposit: u64, // Part of `amount` drawn from the listing deposit; the rest came from the pool
+}
+
+#[event]
+pub struct KeeperPoolUpdated {
+    pub pool: Pubkey,
+    pub game: Pubkey,
+    pub activation_bounty: u64,
+    pub expiry_bounty: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct KeeperDepositUpdated {
+    pub listing: Pubkey,
+    pub game: Pubkey,
+    pub seller: Pubkey,
+    pub keeper_deposit: u64,
+}
+
+#[event]
+pub struct TradeFeeRates {
+    pub game: Pubkey,
+    pub listing: Pubkey,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub maker_volume_30d: u64, // Volumes the rates were picked from (before this trade)
+    pub taker_volume_30d: u64,
+    pub maker_fee_bps: u16, // Effective rates (after PDOX staker discounts)
+    pub taker_fee_bps: u16,
+    pub maker_discount_bps: u16, // Staker discounts applied (bps of the tier rate)
+    pub taker_discount_bps: u16,
+    pub maker_fee: u64,
+    pub taker_fee: u64,
 }
 
-/// Integration config data (used for updates)
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct IntegrationConfigData {
-    pub fcm_endpoint: String,
-    pub apns_endpoint: String,
-    pub oracle_endpoint: String,
-    pub notification_endpoint: String,
-    pub data_availability_endpoint: String,
+#[event]
+pub struct NetBatchFeesCharged {
+    pub batch_id: u64,
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub maker_volume: u64,
+    pub taker_volume: u64,
+    pub volume_30d: u64, // Volume the rates were picked from (before this batch)
+    pub maker_fee_bps: u16,
+    pub taker_fee_bps: u16,
+    pub fee: u64,
 }
 
-// ======================================================================
-// EVENTS
-// ======================================================================
+#[event]
+pub struct KycProviderUpdated {
+    pub provider: Pubkey,
+    pub approved: bool,
+}
 
 #[event]
-pub struct ConfigInitialized {
-    pub admin: Pubkey,
-    pub governance: Pubkey,
-    pub server_authority: Pubkey,
-    pub protocol_fee_bps: u16,
+pub struct PdoxFeeConfigUpdated {
+    pub sink: PdoxFeeSink,
+    pub sink_account: Pubkey,
+    pub discount_bps: u16,
 }
 
 #[event]
-pub struct GameCreated {
-    pub game: Pubkey,
-    pub game_id: u64,
-    pub owner: Pubkey,
-    pub currency_mint: Pubkey,
+pub struct PdoxPriceSourceUpdated {
+    pub price_source: Pubkey,
+    pub quote_mint: Pubkey,
+    pub pdox_reserve: Pubkey,
+    pub quote_reserve: Pubkey,
+    pub min_quote_liquidity: u64,
+    pub reference_price: u64,
+    pub max_deviation_bps: u16,
 }
 
 #[event]
-pub struct CreditsDeposited {
+pub struct ProtocolFeesDistributed {
     pub game: Pubkey,
-    pub player: Pubkey,
     pub amount: u64,
+    pub treasury_amount: u64,
+    pub lp_growth_amount: u64,
+    pub burned: u64,
 }
 
 #[event]
-pub struct CreditsWithdrawn {
+pub struct ProtocolFeePaidInPdox {
+    pub game: Pubkey,
+    pub payer: Pubkey,
+    pub fee: u64, // Protocol fee in the game currency (before the PDOX discount)
+    pub discount_bps: u16,
+    pub pdox_amount: u64, // PDOX received by the sink (or burned)
+    pub sink: PdoxFeeSink,
+}
+
+#[event]
+pub struct SpendingLimitsUpdated {
+    pub ledger: Pubkey,
     pub game: Pubkey,
     pub player: Pubkey,
-    pub amount: u64,
+    pub daily_cap: u64,
+    pub weekly_cap: u64,
+    pub guardian: Pubkey,
+    pub guardian_threshold: u64,
 }
 
-/// Configuration update parameters for `update_config` instruction.
-/// This struct reduces the argument count and improves API ergonomics.
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
-pub struct ConfigUpdateParams {
-    pub new_admin: Option<Pubkey>,
-    pub new_governance: Option<Pubkey>,
-    pub new_server: Option<Pubkey>,
-    pub new_protocol_fee_bps: Option<u16>,
-    pub paused_new: Option<bool>,
-    pub paused_settlements: Option<bool>,
-    pub new_features: Option<u64>,
-    pub new_protocol_treasury: Option<Pubkey>,
-    /// Update PDOX token mint address
-    pub new_pdox_mint: Option<Pubkey>,
-    /// Update WSOL mint address
-    pub new_wsol_mint: Option<Pubkey>,
-    /// Update USDC mint address
-    pub new_usdc_mint: Option<Pubkey>,
-    /// Update LP Pool address (Meteora/Raydium)
-    pub new_lp_pool: Option<Pubkey>,
+#[event]
+pub struct SelfExclusionSet {
+    pub ledger: Pubkey,
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub excluded_until: i64,
 }
 
-/// Game update parameters for `update_game` instruction.
-/// This struct reduces the argument count and improves API ergonomics.
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
-pub struct GameUpdateParams {
-    pub new_currency_mint: Option<Pubkey>,
-    pub new_fee_bps: Option<u16>,
-    pub new_cancel_penalty_bps: Option<u16>,
-    pub kyc_required: Option<bool>,
-    pub use_token_2022: Option<bool>,
-    pub paused_new: Option<bool>,
-    pub paused_settlements: Option<bool>,
-    pub new_payout_wallet: Option<Pubkey>,
+#[event]
+pub struct LazyMintConfigured {
+    pub collection: Pubkey,
+    pub game: Pubkey,
+    pub enabled: bool,
+    pub decimals: u8,
 }
 
 #[event]
-pub struct ListingCreated {
+pub struct LazyItemMinted {
     pub game: Pubkey,
-    pub listing: Pubkey,
-    pub listing_id: u64,
-    pub seller: Pubkey,
-    pub kind: ListingKind,
-    pub quantity_total: u64,
-    pub start_price: u64,
+    pub mint: Pubkey,
+    pub metadata_hash: [u8; 32],
+    pub recipient: Pubkey,
+    pub quantity: u64,
 }
 
 #[event]
-pub struct ListingActivated {
-    pub listing: Pubkey,
+pub struct SweepExecuted {
     pub game: Pubkey,
-    pub at: i64,
+    pub buyer: Pubkey,
+    pub all_or_nothing: bool,
+    pub listings_filled: u8,
+    pub listings_skipped: u8, // Best-effort only
+    pub total_quantity: u64,
+    pub total_price: u64,
+    pub total_protocol_fee: u64,
+    pub total_game_fee: u64,
+    pub total_royalty: u64,
 }
 
 #[event]
-pub struct ListingCancelled {
-    pub listing: Pubkey,
+pub struct ReferrerRegistered {
+    pub referral: Pubkey,
     pub game: Pubkey,
-    pub seller: Pubkey,
-    pub penalty_charged: u64,
+    pub referrer: Pubkey,
 }
 
 #[event]
-pub struct FixedSaleExecuted {
-    pub listing: Pubkey,
+pub struct ReferralPaid {
+    pub referral: Pubkey,
     pub game: Pubkey,
+    pub referrer: Pubkey,
+    pub listing: Pubkey,
     pub buyer: Pubkey,
-    pub quantity: u64,
-    pub price_total: u64,
-    pub protocol_fee: u64,
-    pub game_fee: u64,
+    pub amount: u64,
 }
 
 #[event]
-pub struct BidPlaced {
-    pub listing: Pubkey,
+pub struct ReferralFeesClaimed {
+    pub referral: Pubkey,
     pub game: Pubkey,
-    pub bidder: Pubkey,
-    pub bid_amount: u64,
-    pub previous_highest_bid: u64,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RoyaltySplitCreated {
+    pub split: Pubkey,
+    pub game: Pubkey,
+    pub authority: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    pub weights: Vec<u16>,
 }
 
 #[event]
@@ -1177,6 +2768,20 @@ pub struct PartialFill {
     pub price_total: u64,
 }
 
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub old_len: u64,
+    pub new_len: u64,
+}
+
+#[event]
+pub struct GameRoleUpdated {
+    pub game: Pubkey,
+    pub member: Pubkey,
+    pub roles: u8,
+}
+
 #[event]
 pub struct GameUpdated {
     pub game: Pubkey,
@@ -1199,6 +2804,9 @@ pub struct KycUpdated {
     pub verified: bool,
     pub provider: Pubkey,
     pub at: i64,
+    pub tier: KycTier,
+    pub expires_at: i64, // 0 = never
+    pub country: [u8; 2],
 }
 
 #[event]
@@ -1666,6 +3274,19 @@ pub mod phantom_paradox {
         cfg.last_state_num_intents = 0;
         cfg.last_state_num_items = 0;
         cfg.last_state_timestamp = 0;
+        cfg.referral_bps = 0; // Referrals disabled until governance sets a share
+        cfg.kyc_providers = [Pubkey::default(); MAX_KYC_PROVIDERS]; // Governance attests until providers are approved
+        cfg.kyc_tier_limits = [KycTierLimits::default(); KYC_TIER_COUNT]; // No tier limits by default
+        cfg.kyc_attestation_ttl_secs = 0;
+        cfg.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS]; // Flat protocol_fee_bps until governance sets tiers
+        cfg.fee_tier_count = 0;
+        cfg.pdox_stake_program = Pubkey::default(); // Staker discounts off until governance sets the program
+        cfg.stake_discount_tiers = [StakeDiscountTier::default(); MAX_STAKE_DISCOUNT_TIERS];
+        cfg.stake_discount_tier_count = 0;
+        cfg.pdox_fee_sink = PdoxFeeSink::Disabled; // Fees in game currency until governance sets a sink
+        cfg.pdox_fee_sink_account = Pubkey::default();
+        cfg.pdox_fee_discount_bps = 0;
+        cfg.fee_router = FeeRouterSplits::default(); // Fees withdrawn to protocol_treasury until governance routes them
 
         emit!(ConfigInitialized {
             admin: cfg.admin,
@@ -1683,6 +3304,14 @@ pub mod phantom_paradox {
     /// - `PgError::FeeTooHigh` if `new_protocol_fee_bps` exceeds `MAX_PROTOCOL_FEE_BPS` or
     ///   if the fee would cause total fees (protocol + max game + max royalty) to exceed 100%
     /// - `PgError::Unauthorized` if attempting to change treasury wallet without proper authorization
+    /// - `PgError::FeeTooHigh` if `new_referral_bps` exceeds `MAX_REFERRAL_BPS`
+    /// - `PgError::InvalidTime` if `new_kyc_attestation_ttl_secs` is negative
+    /// - `PgError::InvalidFeeTiers` if `new_fee_tiers` has too many rows, doesn't start at zero
+    ///   volume or isn't strictly sorted by `min_volume`
+    /// - `PgError::FeeTooHigh` if a tier rate exceeds `MAX_PROTOCOL_FEE_BPS`
+    /// - `PgError::InvalidFeeTiers` if `new_stake_discount_tiers` has too many rows, isn't strictly
+    ///   sorted by `min_stake` or a discount exceeds `MAX_STAKE_DISCOUNT_BPS`
+    /// - `PgError::InvalidFeeRouter` if `new_fee_router` splits don't sum to 100%
     ///
     /// # Note
     /// Although the context accepts `governance` signer, the logic allows either governance
@@ -1748,6 +3377,61 @@ pub mod phantom_paradox {
         if let Some(lp) = params.new_lp_pool {
             cfg.lp_pool = lp;
         }
+        if let Some(rb) = params.new_referral_bps {
+            require!(rb <= MAX_REFERRAL_BPS, PgError::FeeTooHigh);
+            cfg.referral_bps = rb;
+        }
+        if let Some(limits) = params.new_kyc_tier_limits {
+            cfg.kyc_tier_limits = limits;
+        }
+        if let Some(ttl) = params.new_kyc_attestation_ttl_secs {
+            require!(ttl >= 0, PgError::InvalidTime);
+            cfg.kyc_attestation_ttl_secs = ttl;
+        }
+        if let Some(tiers) = params.new_fee_tiers {
+            require!(tiers.len() <= MAX_FEE_TIERS, PgError::InvalidFeeTiers);
+            if let Some(first) = tiers.first() {
+                require!(first.min_volume == 0, PgError::InvalidFeeTiers);
+            }
+            require!(
+                tiers.windows(2).all(|w| w[0].min_volume < w[1].min_volume),
+                PgError::InvalidFeeTiers
+            );
+            for tier in &tiers {
+                require!(
+                    tier.maker_fee_bps <= MAX_PROTOCOL_FEE_BPS
+                        && tier.taker_fee_bps <= MAX_PROTOCOL_FEE_BPS,
+                    PgError::FeeTooHigh
+                );
+            }
+            cfg.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+            cfg.fee_tiers[..tiers.len()].copy_from_slice(&tiers);
+            cfg.fee_tier_count = u8::try_from(tiers.len()).map_err(|_| PgError::InvalidFeeTiers)?;
+        }
+        if let Some(program) = params.new_pdox_stake_program {
+            cfg.pdox_stake_program = program;
+        }
+        if let Some(tiers) = params.new_stake_discount_tiers {
+            require!(tiers.len() <= MAX_STAKE_DISCOUNT_TIERS, PgError::InvalidFeeTiers);
+            require!(
+                tiers.windows(2).all(|w| w[0].min_stake < w[1].min_stake),
+                PgError::InvalidFeeTiers
+            );
+            for tier in &tiers {
+                require!(
+                    tier.discount_bps <= MAX_STAKE_DISCOUNT_BPS,
+                    PgError::InvalidFeeTiers
+                );
+            }
+            cfg.stake_discount_tiers = [StakeDiscountTier::default(); MAX_STAKE_DISCOUNT_TIERS];
+            cfg.stake_discount_tiers[..tiers.len()].copy_from_slice(&tiers);
+            cfg.stake_discount_tier_count =
+                u8::try_from(tiers.len()).map_err(|_| PgError::InvalidFeeTiers)?;
+        }
+        if let Some(splits) = params.new_fee_router {
+            splits.validate()?;
+            cfg.fee_router = splits;
+        }
 
         emit!(ConfigUpdated {
             admin: cfg.admin,
@@ -1799,6 +3483,9 @@ pub mod phantom_paradox {
         game.protocol_fees_accumulated = 0; // CRITICAL: Track protocol fees per-game
         game.payout_wallet = game.owner; // Initialize to owner, can be updated
         game.in_execution = false; // Per-game reentrancy guard
+        game.soft_close = SoftCloseConfig::default(); // Soft-close disabled until configured
+        game.royalty_enforcement = RoyaltyEnforcement::Ignored; // Seller-supplied royalties until configured
+        game.jurisdictions = JurisdictionPolicy::default(); // No country gating until configured
 
         emit!(GameCreated {
             game: game.key(),
@@ -1811,11 +3498,16 @@ pub mod phantom_paradox {
     }
     /// Update game configuration.
     ///
+    /// Besides the owner, governance and admin, holders of the game's `Pauser` role may change
+    /// the pause flags and `ConfigManager`s any other field; the payout wallet stays owner-only.
+    ///
     /// # Errors
-    /// - `PgError::Unauthorized` if caller is not game owner, governance, or admin
+    /// - `PgError::Unauthorized` if caller is not game owner, governance, or admin, nor holds
+    ///   the roles for every field being changed
     /// - `PgError::FeeTooHigh` if `new_fee_bps` exceeds `MAX_GAME_FEE_BPS` or would cause total fees to exceed 100%
     /// - `PgError::CancelPenaltyTooHigh` if `new_cancel_penalty_bps` exceeds `MAX_CANCEL_PENALTY_BPS`
     /// - `PgError::Unauthorized` if attempting to change `currency_mint` (not allowed in v1)
+    /// - `PgError::InvalidCountryCode` if `jurisdictions` lists a malformed country code
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     #[allow(clippy::too_many_arguments)] // Using params struct would break API compatibility
     pub fn update_game(ctx: Context<UpdateGame>, params: GameUpdateParams) -> Result<()> {
@@ -1823,12 +3515,19 @@ pub mod phantom_paradox {
         let cfg = &ctx.accounts.config;
         let game = &ctx.accounts.game;
 
-        // Security: Only game owner or governance can update game
+        // Security: Only game owner, governance, or the game roles covering every field touched
         let caller = ctx.accounts.caller.key();
         let governance = cfg.governance;
         let admin = cfg.admin;
+        let roles = ctx.accounts.roles.as_deref();
+        let has_role =
+            |role| instructions::game_roles::is_owner_or_role(game, roles, &caller, role);
+        let role_update = params.new_payout_wallet.is_none()
+            && (params.touches_pause() || params.touches_config())
+            && (!params.touches_pause() || has_role(GameRole::Pauser))
+            && (!params.touches_config() || has_role(GameRole::ConfigManager));
         require!(
-            caller == game.owner || caller == governance || caller == admin,
+            caller == game.owner || caller == governance || caller == admin || role_update,
             PgError::Unauthorized
         );
 
@@ -1850,6 +3549,12 @@ pub mod phantom_paradox {
         if let Some(p) = params.new_cancel_penalty_bps {
             require!(p <= MAX_CANCEL_PENALTY_BPS, PgError::CancelPenaltyTooHigh);
         }
+        if let Some(sc) = params.soft_close {
+            sc.validate()?;
+        }
+        if let Some(j) = params.jurisdictions {
+            j.validate()?;
+        }
 
         // CRITICAL: Validate mint change attempt BEFORE guard
         if let Some(m) = params.new_currency_mint {
@@ -1888,6 +3593,15 @@ pub mod phantom_paradox {
             );
             game.payout_wallet = pw;
         }
+        if let Some(sc) = params.soft_close {
+            game.soft_close = sc;
+        }
+        if let Some(re) = params.royalty_enforcement {
+            game.royalty_enforcement = re;
+        }
+        if let Some(j) = params.jurisdictions {
+            game.jurisdictions = j;
+        }
 
         emit!(GameUpdated {
             game: game.key(),
@@ -1897,20 +3611,76 @@ pub mod phantom_paradox {
         exit_execution_game(game);
         Ok(())
     }
-    /// Set or update player KYC status.
+    /// Set or update player KYC tier (`KycTier::None` revokes the attestation).
+    ///
+    /// The attestation expires at `expires_at`, or after `kyc_attestation_ttl_secs` when omitted.
+    /// A game `KycOperator` can only revoke or downgrade: it passes a lower tier and no other
+    /// attestation data, and the existing expiry, country and provider are kept.
     ///
     /// # Errors
-    /// - `PgError::Unauthorized` if caller is not the KYC provider or game owner
+    /// - `PgError::Unauthorized` if caller is neither governance, an approved KYC provider nor
+    ///   a `KycOperator` of the game
+    /// - `PgError::Unauthorized` if a `KycOperator` raises the tier or passes attestation data
+    /// - `PgError::InvalidTime` if `expires_at` is not in the future
+    /// - `PgError::InvalidCountryCode` if `country_code` is not an uppercase ISO 3166-1 alpha-2 code
     ///
     /// # Note
-    /// In v1, `governance` acts as the centralized KYC oracle/provider.
+    /// Governance may attest on behalf of `kyc_provider`; an approved provider is always
+    /// recorded as the provider of its own attestations. `country_code` is part of the signed
+    /// attestation and is replaced (or cleared) on every call.
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     pub fn set_player_kyc(
         ctx: Context<SetPlayerKyc>,
-        kyc_verified: bool,
+        kyc_tier: KycTier,
         kyc_provider: Option<Pubkey>,
         kyc_proof_hash: Option<[u8; 32]>,
+        expires_at: Option<i64>,
+        country_code: Option<[u8; 2]>,
     ) -> Result<()> {
+        if let Some(code) = country_code {
+            require!(
+                JurisdictionPolicy::is_valid_country(code),
+                PgError::InvalidCountryCode
+            );
+        }
+        let cfg = &ctx.accounts.config;
+        let attestor = ctx.accounts.attestor.key();
+        let is_governance = attestor == cfg.governance;
+        // Only governance and approved providers attest; a game KycOperator may only lower tiers
+        let can_attest = is_governance || cfg.kyc_providers.contains(&attestor);
+        let is_kyc_operator = ctx
+            .accounts
+            .roles
+            .as_ref()
+            .is_some_and(|roles| roles.has_role(&attestor, GameRole::KycOperator));
+        require!(can_attest || is_kyc_operator, PgError::Unauthorized);
+        if !can_attest {
+            require!(
+                kyc_provider.is_none()
+                    && kyc_proof_hash.is_none()
+                    && expires_at.is_none()
+                    && country_code.is_none(),
+                PgError::Unauthorized
+            );
+        }
+        let kyc_provider = if is_governance {
+            kyc_provider
+        } else {
+            Some(attestor)
+        };
+
+        let now = Clock::get()?.unix_timestamp;
+        let kyc_expires_at = match expires_at {
+            Some(t) => {
+                require!(t > now, PgError::InvalidTime);
+                t
+            }
+            None if cfg.kyc_attestation_ttl_secs > 0 => now
+                .checked_add(cfg.kyc_attestation_ttl_secs)
+                .ok_or(PgError::Overflow)?,
+            None => 0,
+        };
+
         // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
         let game = &mut ctx.accounts.game;
         let ledger = &mut ctx.accounts.player_ledger;
@@ -1937,11 +3707,17 @@ pub mod phantom_paradox {
                 PgError::Unauthorized
             );
 
-            let now = Clock::get()?.unix_timestamp;
-
-            ledger.kyc_verified = kyc_verified;
-            if kyc_verified {
+            let kyc_verified = kyc_tier != KycTier::None;
+            if !can_attest && kyc_verified {
+                // Downgrade: keep the provider's attestation, only lower its tier
+                require!(kyc_tier < ledger.kyc_tier_at(now), PgError::Unauthorized);
+                ledger.kyc_tier = kyc_tier;
+            } else if kyc_verified {
+                ledger.kyc_verified = true;
+                ledger.kyc_tier = kyc_tier;
                 ledger.kyc_verified_at = now;
+                ledger.kyc_expires_at = kyc_expires_at;
+                ledger.kyc_country = country_code.unwrap_or_default();
                 if let Some(provider) = kyc_provider {
                     ledger.kyc_provider = provider;
                 }
@@ -1949,7 +3725,11 @@ pub mod phantom_paradox {
                     ledger.kyc_proof_hash = proof_hash;
                 }
             } else {
+                ledger.kyc_verified = false;
+                ledger.kyc_tier = KycTier::None;
                 ledger.kyc_verified_at = 0;
+                ledger.kyc_expires_at = 0;
+                ledger.kyc_country = [0u8; 2];
                 ledger.kyc_provider = Pubkey::default();
                 ledger.kyc_proof_hash = [0u8; 32];
             }
@@ -1960,6 +3740,9 @@ pub mod phantom_paradox {
                 verified: kyc_verified,
                 provider: ledger.kyc_provider,
                 at: now,
+                tier: kyc_tier,
+                expires_at: ledger.kyc_expires_at,
+                country: ledger.kyc_country,
             });
 
             Ok(())
@@ -1980,6 +3763,7 @@ pub mod phantom_paradox {
     /// - `PgError::CurrencyMintMismatch` if `currency_mint` doesn't match game config
     /// - `PgError::ListingsPaused` if listings are paused
     /// - `PgError::KycRequired` if game requires KYC and player is not verified
+    /// - `PgError::JurisdictionBlocked` if the game blocks the player's KYC-attested country
     /// - `PgError::Overflow` on arithmetic overflow
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     pub fn deposit_credits(ctx: Context<DepositCredits>, amount: u64) -> Result<()> {
@@ -2000,13 +3784,17 @@ pub mod phantom_paradox {
 
         // CRITICAL: Check KYC *BEFORE* guard.
         // player_ledger initialized via init_if_needed
-        // If it's new, kyc_verified is false. If required, this check correctly fails.
+        // If it's new, kyc_verified is false. If required, this check correctly fails (as does an expired attestation).
         if game.kyc_required {
             require!(
-                ctx.accounts.player_ledger.kyc_verified,
+                ctx.accounts
+                    .player_ledger
+                    .has_valid_kyc(Clock::get()?.unix_timestamp),
                 PgError::KycRequired
             );
         }
+        game.jurisdictions
+            .check(&ctx.accounts.player_ledger, Clock::get()?.unix_timestamp)?;
 
         // NOW safe to enter reentrancy guard - all validation passed
         let game = &mut ctx.accounts.game;
@@ -2068,6 +3856,8 @@ pub mod phantom_paradox {
     /// - `PgError::InvalidAmount` if amount is 0
     /// - `PgError::InsufficientCredits` if player doesn't have enough available credits
     /// - `PgError::ListingsPaused` if listings are paused
+    /// - `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired` if the player's
+    ///   spending limits block the withdrawal (self-exclusion never blocks withdrawals)
     /// - `PgError::Overflow` on arithmetic overflow
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     pub fn withdraw_credits(ctx: Context<WithdrawCredits>, amount: u64) -> Result<()> {
@@ -2108,6 +3898,10 @@ pub mod phantom_paradox {
             // during CPI calls. We don't add extra verification in v1 - the token program itself
             // will reject transfers if hooks fail. See verify_token_2022_transfer_hook stub for v2.
 
+            let now = Clock::get()?.unix_timestamp;
+            let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+            ledger.limits.record_spend(amount, now, approver)?;
+
             ledger.available = ledger
                 .available
                 .checked_sub(amount)
@@ -2156,6 +3950,19 @@ pub mod phantom_paradox {
     /// - `PgError::InvalidTime` if duration exceeds `MAX_LISTING_DURATION_SECS`
     /// - `PgError::FeeTooHigh` if total fees (royalty + game + protocol) exceed 100%
     /// - `PgError::InvalidAmount` if `royalty_bps` > 0 but `royalty_recipient` is default
+    /// - `PgError::InvalidAmount` if Dutch curve params are invalid (min > start, zero step, bad decay)
+    /// - `PgError::InvalidTime` if soft-close params exceed `MAX_SOFT_CLOSE_SECS` limits
+    /// - `PgError::InvalidListingKind` if `SecondPrice` is used with a non English/sealed listing
+    /// - `PgError::RoyaltySplitMismatch` if `royalty_split` is for another game or its primary isn't `royalty_recipient`
+    /// - `PgError::MetadataRequired` / `PgError::InvalidMetadata` / `PgError::InvalidCreatorShares` if the
+    ///   game enforces metadata royalties and `item_metadata` is missing or invalid
+    ///
+    /// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` if the seller's KYC tier doesn't
+    ///   allow a trade of `start_price * quantity_total`
+    /// - `PgError::JurisdictionBlocked` if the game blocks the seller's KYC-attested country
+    ///
+    /// `allowed_buyer` / `allowlist_root` make the listing private (OTC): only that wallet, or
+    /// wallets proving membership in the allowlist, can buy or bid.
     #[allow(clippy::too_many_arguments)] // Required for listing creation parameters
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     pub fn create_listing(
@@ -2169,13 +3976,42 @@ pub mod phantom_paradox {
         reserve_price: u64,
         buy_now_price: u64,
         dutch_min_price: u64,
+        dutch_curve: DutchCurve,
+        dutch_step_secs: u32,
+        dutch_decay_bps: u16,
+        soft_close: SoftCloseConfig,
+        settlement_mode: SettlementMode,
         royalty_recipient: Pubkey,
         royalty_bps: u16,
+        allowed_buyer: Option<Pubkey>,
+        allowlist_root: Option<[u8; 32]>,
     ) -> Result<()> {
         require!(quantity_total > 0, PgError::InvalidAmount);
         require!(quantity_total <= MAX_BULK_QTY, PgError::InvalidAmount);
         require!(start_price > 0, PgError::InvalidAmount);
 
+        // Dutch: validate curve so the on-chain price is always well-defined
+        if kind == ListingKind::DutchAuction {
+            require!(dutch_min_price > 0, PgError::InvalidAmount);
+            require!(dutch_min_price <= start_price, PgError::InvalidAmount);
+            if dutch_curve != DutchCurve::Linear {
+                require!(dutch_step_secs > 0, PgError::InvalidAmount);
+            }
+            if dutch_curve == DutchCurve::Exponential {
+                require!(
+                    dutch_decay_bps > 0 && u64::from(dutch_decay_bps) < BPS_DENOM,
+                    PgError::InvalidAmount
+                );
+            }
+        }
+        soft_close.validate()?;
+        if settlement_mode == SettlementMode::SecondPrice {
+            require!(
+                kind == ListingKind::EnglishAuction || kind == ListingKind::SealedBid,
+                PgError::InvalidListingKind
+            );
+        }
+
         let now = Clock::get()?.unix_timestamp;
         require!(end_time > start_time, PgError::InvalidTime);
         let duration = end_time
@@ -2189,6 +4025,25 @@ pub mod phantom_paradox {
         let cfg = &ctx.accounts.config;
         let game = &ctx.accounts.game;
 
+        // KYC: the seller's tier must allow a trade of the listing's full size at its start price
+        let notional = start_price
+            .checked_mul(quantity_total)
+            .ok_or(PgError::Overflow)?;
+        ctx.accounts
+            .seller_ledger
+            .check_kyc_trade(cfg, game.kyc_required, notional, now)?;
+        game.jurisdictions.check(&ctx.accounts.seller_ledger, now)?;
+
+        // Creator royalties from Metaplex metadata override seller input (per-game policy)
+        let (royalty_recipient, royalty_bps) = metadata::resolve_listing_royalty(
+            game.royalty_enforcement,
+            ctx.accounts.item_metadata.as_deref(),
+            &ctx.accounts.item_mint.key(),
+            ctx.accounts.royalty_split.as_deref().map(|split| &**split),
+            royalty_recipient,
+            royalty_bps,
+        )?;
+
         // CRITICAL: Fee sanity check - total fees must not exceed 100%
         let total_fees = u64::from(royalty_bps)
             .checked_add(u64::from(game.fee_bps))
@@ -2205,6 +4060,19 @@ pub mod phantom_paradox {
         if royalty_bps > 0 {
             require!(royalty_bps >= MIN_ROYALTY_BPS, PgError::InvalidAmount);
         }
+        // Multi-recipient royalties: the split's primary recipient is the listing's royalty_recipient
+        let royalty_split = match &ctx.accounts.royalty_split {
+            Some(split) => {
+                require!(split.game == game.key(), PgError::RoyaltySplitMismatch);
+                require!(royalty_bps > 0, PgError::InvalidRoyalty);
+                require!(
+                    split.recipients[0] == royalty_recipient,
+                    PgError::RoyaltySplitMismatch
+                );
+                split.key()
+            }
+            None => Pubkey::default(),
+        };
         // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
         let game = &mut ctx.accounts.game;
         enter_execution_game(game)?;
@@ -2233,9 +4101,21 @@ pub mod phantom_paradox {
             l.has_interest = false; // Will be set to true when first buy/settlement occurs
             l.royalty_recipient = royalty_recipient;
             l.royalty_bps = royalty_bps;
+            l.royalty_split = royalty_split;
             // Initialize bid tracking (no bids yet)
             l.highest_bid = 0;
             l.highest_bidder = Pubkey::default();
+            l.second_highest_bid = 0;
+            l.settlement_mode = settlement_mode;
+            l.dutch_curve = dutch_curve;
+            l.dutch_step_secs = dutch_step_secs;
+            l.dutch_decay_bps = dutch_decay_bps;
+            l.soft_close = soft_close;
+            l.soft_close_extended_secs = 0;
+            l.version = 0;
+            l.keeper_deposit = 0;
+            l.allowed_buyer = allowed_buyer.unwrap_or_default();
+            l.allowlist_root = allowlist_root.unwrap_or_default();
             l.reserved_u16 = 0;
 
             // Escrow items: seller -> escrow
@@ -2297,11 +4177,16 @@ pub mod phantom_paradox {
         let listing = &mut ctx.accounts.listing;
         enter_execution_game(game)?;
 
-        // Only seller, game owner, or server can activate
+        // Only seller, game owner / listing moderator, or server can activate
         let caller = ctx.accounts.caller.key();
         require!(
             caller == listing.seller
-                || caller == game.owner
+                || instructions::game_roles::is_owner_or_role(
+                    game,
+                    ctx.accounts.roles.as_deref(),
+                    &caller,
+                    GameRole::ListingModerator,
+                )
                 || caller == cfg.server_authority
                 || caller == cfg.admin
                 || caller == cfg.governance,
@@ -2349,11 +4234,16 @@ pub mod phantom_paradox {
             PgError::InvalidListingStatus
         );
 
-        // Only seller, game owner, admin, governance or server can cancel
+        // Only seller, game owner / listing moderator, admin, governance or server can cancel
         let caller = ctx.accounts.caller.key();
         let seller = listing.seller;
         let allowed = caller == seller
-            || caller == game.owner
+            || instructions::game_roles::is_owner_or_role(
+                game,
+                ctx.accounts.roles.as_deref(),
+                &caller,
+                GameRole::ListingModerator,
+            )
             || caller == cfg.admin
             || caller == cfg.governance
             || caller == cfg.server_authority;
@@ -2416,35 +4306,167 @@ pub mod phantom_paradox {
                         .checked_div(BPS_DENOM)
                         .ok_or(PgError::Overflow)?;
 
-                    let seller_ledger = &mut ctx.accounts.seller_ledger;
-                    if seller_ledger.available >= penalty_charged {
-                        seller_ledger.available = seller_ledger
-                            .available
-                            .checked_sub(penalty_charged)
-                            .ok_or(PgError::Overflow)?;
-                        // Credit penalty to protocol fees for this game (fair accounting)
-                        game.protocol_fees_accumulated = game
-                            .protocol_fees_accumulated
-                            .checked_add(penalty_charged)
-                            .ok_or(PgError::Overflow)?;
-                        // DEPRECATED: Also update global counter for backward compatibility
-                        let cfg_mut = &mut ctx.accounts.config;
-                        cfg_mut.accumulated_fees = cfg_mut
-                            .accumulated_fees
-                            .checked_add(penalty_charged)
-                            .ok_or(PgError::Overflow)?;
-                    } else {
-                        // If they don't have enough, charge nothing (or you can clamp)
-                        penalty_charged = 0;
-                    }
-                }
+                    let seller_ledger = &mut ctx.accounts.seller_ledger;
+                    if seller_ledger.available >= penalty_charged {
+                        seller_ledger.available = seller_ledger
+                            .available
+                            .checked_sub(penalty_charged)
+                            .ok_or(PgError::Overflow)?;
+                        // Credit penalty to protocol fees for this game (fair accounting)
+                        game.protocol_fees_accumulated = game
+                            .protocol_fees_accumulated
+                            .checked_add(penalty_charged)
+                            .ok_or(PgError::Overflow)?;
+                        // DEPRECATED: Also update global counter for backward compatibility
+                        let cfg_mut = &mut ctx.accounts.config;
+                        cfg_mut.accumulated_fees = cfg_mut
+                            .accumulated_fees
+                            .checked_add(penalty_charged)
+                            .ok_or(PgError::Overflow)?;
+                    } else {
+                        // If they don't have enough, charge nothing (or you can clamp)
+                        penalty_charged = 0;
+                    }
+                }
+            }
+
+            emit!(ListingCancelled {
+                listing: listing.key(),
+                game: game.key(),
+                seller,
+                penalty_charged,
+            });
+
+            Ok(())
+        })();
+
+        exit_execution_game(game);
+        res
+    }
+
+    /// Update prices and/or top up escrowed quantity of an open listing.
+    ///
+    /// Bumps `listing.version`; buyers passing `expected_version` to `buy_fixed`/`place_bid`
+    /// fail instead of trading at the new terms (`buy_fixed` is also bounded by `max_unit_price`).
+    ///
+    /// # Errors
+    /// - `PgError::Unauthorized` if caller is not the seller
+    /// - `PgError::InvalidListingStatus` if listing is not Pending, Active or `PartiallyFilled`
+    /// - `PgError::AuctionHasBids` if an auction already has bids or sealed commitments
+    /// - `PgError::ListingVersionMismatch` if `expected_version` doesn't match
+    /// - `PgError::InvalidAmount` if a price is invalid (a non-zero `buy_now_price` or
+    ///   `reserve_price` below `start_price`) or quantity exceeds `MAX_BULK_QTY`
+    /// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` if a higher start price or
+    ///   added quantity exceeds the seller's KYC tier
+    #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+    pub fn update_listing(
+        ctx: Context<UpdateListing>,
+        start_price: Option<u64>,
+        buy_now_price: Option<u64>,
+        reserve_price: Option<u64>,
+        add_quantity: u64,
+        expected_version: Option<u64>,
+    ) -> Result<()> {
+        // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+        let cfg = &ctx.accounts.config;
+        let game = &ctx.accounts.game;
+        let listing = &ctx.accounts.listing;
+
+        require!(listing.game == game.key(), PgError::Unauthorized);
+        require!(
+            listing.seller == ctx.accounts.seller_signer.key(),
+            PgError::Unauthorized
+        );
+        require!(!cfg.paused_new, PgError::ListingsPaused);
+        require!(!game.paused_new, PgError::ListingsPaused);
+        require!(
+            listing.status == ListingStatus::Pending
+                || listing.status == ListingStatus::Active
+                || listing.status == ListingStatus::PartiallyFilled,
+            PgError::InvalidListingStatus
+        );
+        if let Some(expected) = expected_version {
+            require!(
+                listing.version == expected,
+                PgError::ListingVersionMismatch
+            );
+        }
+
+        // Auction terms are frozen once anyone has bid (or committed a sealed bid)
+        if listing.kind != ListingKind::Fixed {
+            require!(
+                !listing.has_interest && listing.highest_bid == 0,
+                PgError::AuctionHasBids
+            );
+        }
+
+        let (new_start_price, new_buy_now_price, new_reserve_price) =
+            listing.updated_prices(start_price, buy_now_price, reserve_price)?;
+        let new_quantity_total = listing
+            .quantity_total
+            .checked_add(add_quantity)
+            .ok_or(PgError::Overflow)?;
+        require!(
+            new_quantity_total <= MAX_BULK_QTY,
+            PgError::InvalidAmount
+        );
+
+        // KYC: a larger listing must still fit the seller's tier, as checked at create time
+        let now = Clock::get()?.unix_timestamp;
+        if new_start_price > listing.start_price || add_quantity > 0 {
+            let notional = new_start_price
+                .checked_mul(new_quantity_total)
+                .ok_or(PgError::Overflow)?;
+            ctx.accounts
+                .seller_ledger
+                .check_kyc_trade(cfg, game.kyc_required, notional, now)?;
+        }
+
+        // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+        let game = &mut ctx.accounts.game;
+        let listing = &mut ctx.accounts.listing;
+        enter_execution_game(game)?;
+        let res = (|| -> Result<()> {
+            listing.start_price = new_start_price;
+            listing.buy_now_price = new_buy_now_price;
+            listing.reserve_price = new_reserve_price;
+
+            // Top up escrow: seller -> escrow
+            if add_quantity > 0 {
+                let cpi_accounts = token_interface::TransferChecked {
+                    from: ctx.accounts.seller_item_ata.to_account_info(),
+                    mint: ctx.accounts.item_mint.to_account_info(),
+                    to: ctx.accounts.escrow_item_ata.to_account_info(),
+                    authority: ctx.accounts.seller_signer.to_account_info(),
+                };
+                let cpi_ctx =
+                    CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    add_quantity,
+                    ctx.accounts.item_mint.decimals,
+                )?;
+
+                listing.quantity_total = new_quantity_total;
+                listing.quantity_remaining = listing
+                    .quantity_remaining
+                    .checked_add(add_quantity)
+                    .ok_or(PgError::Overflow)?;
             }
 
-            emit!(ListingCancelled {
+            listing.version = listing.version.checked_add(1).ok_or(PgError::Overflow)?;
+            listing.updated_at = now;
+
+            emit!(ListingUpdated {
                 listing: listing.key(),
                 game: game.key(),
-                seller,
-                penalty_charged,
+                seller: listing.seller,
+                start_price: listing.start_price,
+                buy_now_price: listing.buy_now_price,
+                reserve_price: listing.reserve_price,
+                quantity_total: listing.quantity_total,
+                quantity_remaining: listing.quantity_remaining,
+                version: listing.version,
             });
 
             Ok(())
@@ -2461,15 +4483,30 @@ pub mod phantom_paradox {
     /// Place a bid on an active auction.
     ///
     /// Automatically refunds the previous highest bidder if the new bid is higher.
+    /// English bids inside the soft-close window extend `end_time` (see `SoftCloseConfig`).
+    /// Dutch auctions take no bids; they fill atomically at the curve price through `buy_fixed`.
     ///
     /// # Errors
     /// - `PgError::InvalidListingStatus` if listing is not Active
-    /// - `PgError::InvalidListingKind` if listing is not an auction type
+    /// - `PgError::InvalidListingKind` if listing is not an English auction
     /// - `PgError::InvalidAmount` if bid amount is too low
     /// - `PgError::InsufficientCredits` if bidder doesn't have enough credits
+    /// - `PgError::ListingVersionMismatch` if the listing changed since `expected_version`
+    /// - `PgError::BuyerNotAllowed` / `PgError::InvalidMerkleProof` if the listing is private
+    ///   and the bidder isn't `allowed_buyer` or `allowlist_proof` doesn't verify
+    /// - `PgError::SelfExcluded` / `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired`
+    ///   if the bidder's spending limits block the bid (the locked amount counts as spent)
+    /// - `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded` if the bid exceeds
+    ///   the bidder's KYC tier limits
+    /// - `PgError::JurisdictionBlocked` if the game blocks the bidder's KYC-attested country
     /// - `PgError::Overflow` on arithmetic overflow
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
-    pub fn place_bid(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
+    pub fn place_bid(
+        ctx: Context<PlaceBid>,
+        bid_amount: u64,
+        expected_version: Option<u64>,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
         require!(bid_amount > 0, PgError::InvalidAmount);
 
         // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
@@ -2479,6 +4516,12 @@ pub mod phantom_paradox {
 
         // Security: Validate listing belongs to this game and currency matches
         require!(listing.game == game.key(), PgError::Unauthorized);
+        if let Some(expected) = expected_version {
+            require!(
+                listing.version == expected,
+                PgError::ListingVersionMismatch
+            );
+        }
         require!(
             listing.currency_mint == game.currency_mint,
             PgError::CurrencyMintMismatch
@@ -2487,10 +4530,9 @@ pub mod phantom_paradox {
         require!(!cfg.paused_settlements, PgError::SettlementsPaused);
         require!(!game.paused_settlements, PgError::SettlementsPaused);
 
-        // Only English and Dutch auctions accept bids
+        // Only English auctions accept bids (Dutch auctions fill through buy_fixed)
         require!(
-            listing.kind == ListingKind::EnglishAuction
-                || listing.kind == ListingKind::DutchAuction,
+            listing.kind == ListingKind::EnglishAuction,
             PgError::InvalidListingKind
         );
         require!(
@@ -2502,8 +4544,8 @@ pub mod phantom_paradox {
         require!(now >= listing.start_time, PgError::InvalidTime);
         require!(now < listing.end_time, PgError::InvalidTime);
 
-        // Validate bid amount
-        match listing.kind {
+        // Validate bid amount and determine the amount to lock
+        let lock_amount = match listing.kind {
             ListingKind::EnglishAuction => {
                 // English: bid must be higher than current highest (or meet reserve if no bids)
                 if listing.highest_bid > 0 {
@@ -2511,20 +4553,13 @@ pub mod phantom_paradox {
                 } else if listing.reserve_price > 0 {
                     require!(bid_amount >= listing.reserve_price, PgError::InvalidAmount);
                 }
+                bid_amount
             }
-            ListingKind::DutchAuction => {
-                // Dutch: bid must be at or above current price (dutch_min_price <= current <= start_price)
-                // Current price decreases over time, but we don't track it - use start_price as max
-                require!(
-                    bid_amount >= listing.dutch_min_price,
-                    PgError::InvalidAmount
-                );
-                require!(bid_amount <= listing.start_price, PgError::InvalidAmount);
-            }
-            ListingKind::Fixed => {
+            ListingKind::Fixed | ListingKind::DutchAuction | ListingKind::SealedBid => {
+                // Dutch fills use buy_fixed; sealed bids use commit_sealed_bid / reveal_sealed_bid
                 return Err(PgError::InvalidListingKind.into());
             }
-        }
+        };
 
         // Check bidder has enough credits
         let bidder_ledger = &ctx.accounts.bidder_ledger;
@@ -2532,10 +4567,16 @@ pub mod phantom_paradox {
             bidder_ledger.authority == ctx.accounts.bidder.key(),
             PgError::Unauthorized
         );
+        listing.check_buyer(&bidder_ledger.authority, &allowlist_proof)?;
+        game.jurisdictions.check(bidder_ledger, now)?;
         require!(
-            bidder_ledger.available >= bid_amount,
+            bidder_ledger.available >= lock_amount,
             PgError::InsufficientCredits
         );
+        require!(
+            !bidder_ledger.limits.is_excluded(now),
+            PgError::SelfExcluded
+        );
 
         // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
         let game = &mut ctx.accounts.game;
@@ -2543,34 +4584,78 @@ pub mod phantom_paradox {
         let bidder_ledger = &mut ctx.accounts.bidder_ledger;
         enter_execution_game(game)?;
         let res = (|| -> Result<()> {
-            // CRITICAL: Enforce KYC if required
-            if game.kyc_required {
-                require!(bidder_ledger.kyc_verified, PgError::KycRequired);
-            }
+            // CRITICAL: Enforce KYC requirement and tier limits (the bid counts toward daily volume)
+            bidder_ledger.record_kyc_trade(cfg, game.kyc_required, lock_amount, now)?;
+
+            // Spending limits: the locked amount counts as spent
+            let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+            bidder_ledger
+                .limits
+                .record_spend(lock_amount, now, approver)?;
 
             // Store previous highest bidder for refund
             let previous_highest_bid = listing.highest_bid;
             let previous_highest_bidder = listing.highest_bidder;
 
+            // Track runner-up for second-price settlement (a bidder raising their own bid is not a runner-up)
+            if previous_highest_bid > 0 && previous_highest_bidder != ctx.accounts.bidder.key() {
+                listing.second_highest_bid = previous_highest_bid;
+            }
+
             // Update highest bid
-            listing.highest_bid = bid_amount;
+            listing.highest_bid = lock_amount;
             listing.highest_bidder = ctx.accounts.bidder.key();
             listing.has_interest = true; // Mark that auction has activity
             listing.updated_at = now;
 
+            // Anti-sniping: English bids inside the soft-close window extend end_time (capped)
+            if listing.kind == ListingKind::EnglishAuction {
+                let soft_close = if listing.soft_close.window_secs > 0 {
+                    listing.soft_close
+                } else {
+                    game.soft_close
+                };
+                let remaining = listing
+                    .end_time
+                    .checked_sub(now)
+                    .ok_or(PgError::Overflow)?;
+                let extension =
+                    soft_close.extension_for(remaining, listing.soft_close_extended_secs);
+                if extension > 0 {
+                    let previous_end_time = listing.end_time;
+                    listing.end_time = listing
+                        .end_time
+                        .checked_add(i64::from(extension))
+                        .ok_or(PgError::Overflow)?;
+                    listing.soft_close_extended_secs = listing
+                        .soft_close_extended_secs
+                        .checked_add(extension)
+                        .ok_or(PgError::Overflow)?;
+
+                    emit!(AuctionExtended {
+                        listing: listing.key(),
+                        game: game.key(),
+                        bidder: ctx.accounts.bidder.key(),
+                        previous_end_time,
+                        new_end_time: listing.end_time,
+                        total_extended_secs: listing.soft_close_extended_secs,
+                    });
+                }
+            }
+
             // CRITICAL: Lock bid amount (move from available to locked)
             // This ensures funds are locked but can be refunded if outbid
             require!(
-                bidder_ledger.available >= bid_amount,
+                bidder_ledger.available >= lock_amount,
                 PgError::InsufficientCredits
             );
             bidder_ledger.available = bidder_ledger
                 .available
-                .checked_sub(bid_amount)
+                .checked_sub(lock_amount)
                 .ok_or(PgError::Overflow)?;
             bidder_ledger.locked = bidder_ledger
                 .locked
-                .checked_add(bid_amount)
+                .checked_add(lock_amount)
                 .ok_or(PgError::Overflow)?;
 
             // CRITICAL: Refund previous highest bidder if exists
@@ -2614,7 +4699,7 @@ pub mod phantom_paradox {
                 listing: listing.key(),
                 game: game.key(),
                 bidder: ctx.accounts.bidder.key(),
-                bid_amount,
+                bid_amount: lock_amount,
                 previous_highest_bid,
             });
 
@@ -2631,15 +4716,48 @@ pub mod phantom_paradox {
 
     /// Execute a fixed-price purchase.
     ///
+    /// Dutch auctions fill here too, atomically at the current curve price per unit; the buyer
+    /// pays `price * quantity` in this instruction, with no bid or server settlement step.
+    ///
+    /// The protocol fee follows the maker/taker tier of each side's 30-day volume: the maker
+    /// fee comes out of the seller's proceeds, the taker fee is paid on top of the price.
+    /// Passing `seller_stake` / `buyer_stake` (pdox_stake `AgentAccount`s) applies that side's
+    /// PDOX staker discount. Emits `TradeFeeRates` with the effective rates.
+    ///
+    /// Passing `buyer_pdox_account` (with the PDOX mint, price source and its pool reserves)
+    /// pays the whole protocol fee in PDOX at the governance discount instead of in credits;
+    /// referrers earn no cut of a fee paid in PDOX.
+    ///
     /// # Errors
     /// - `PgError::InvalidListingStatus` if listing is not Active
-    /// - `PgError::InvalidListingKind` if listing is not Fixed
+    /// - `PgError::InvalidListingKind` if listing is not Fixed or a Dutch auction
     /// - `PgError::InsufficientQuantity` if requested quantity exceeds available
-    /// - `PgError::InsufficientCredits` if buyer doesn't have enough credits
+    /// - `PgError::InvalidAmount` if the unit price exceeds `max_unit_price`
+    /// - `PgError::InsufficientCredits` if buyer doesn't have enough credits for the price plus taker fee
+    /// - `PgError::ListingVersionMismatch` if the listing changed since `expected_version`
+    /// - `PgError::RoyaltySplitMismatch` if the listing's royalty split account is missing or wrong
+    /// - `PgError::SelfReferral` if the buyer or seller refers their own trade
+    /// - `PgError::BuyerNotAllowed` / `PgError::InvalidMerkleProof` if the listing is private
+    ///   and the buyer isn't `allowed_buyer` or `allowlist_proof` doesn't verify
+    /// - `PgError::SelfExcluded` / `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired`
+    ///   if the buyer's spending limits block the purchase
+    /// - `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded` if the purchase
+    ///   exceeds the buyer's KYC tier limits
+    /// - `PgError::InvalidStakeAccount` if a stake account isn't the side's pdox_stake `AgentAccount`
+    /// - `PgError::PdoxFeesDisabled` / `PgError::InvalidPdoxPayment` / `PgError::InvalidPdoxPriceSource`
+    ///   / `PgError::PdoxLiquidityTooLow` / `PgError::PdoxPriceDeviation` / `PgError::InvalidPdoxFeeSink`
+    ///   if the PDOX fee payment fails
+    /// - `PgError::JurisdictionBlocked` if the game blocks the buyer's KYC-attested country
     /// - `PgError::Overflow` on arithmetic overflow
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     #[allow(clippy::too_many_lines)] // Complex settlement logic requires many lines
-    pub fn buy_fixed(ctx: Context<BuyFixed>, quantity: u64) -> Result<()> {
+    pub fn buy_fixed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyFixed<'info>>,
+        quantity: u64,
+        max_unit_price: u64, // CRITICAL: Buyer's max price per unit (prevents paying updated terms)
+        expected_version: Option<u64>,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
         require!(quantity > 0, PgError::InvalidAmount);
 
         // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
@@ -2649,6 +4767,12 @@ pub mod phantom_paradox {
 
         // Security: Validate listing belongs to this game and currency matches
         require!(listing.game == game.key(), PgError::Unauthorized);
+        if let Some(expected) = expected_version {
+            require!(
+                listing.version == expected,
+                PgError::ListingVersionMismatch
+            );
+        }
         require!(
             listing.currency_mint == game.currency_mint,
             PgError::CurrencyMintMismatch
@@ -2658,7 +4782,7 @@ pub mod phantom_paradox {
         require!(!game.paused_settlements, PgError::SettlementsPaused);
 
         require!(
-            listing.kind == ListingKind::Fixed,
+            listing.kind == ListingKind::Fixed || listing.kind == ListingKind::DutchAuction,
             PgError::InvalidListingKind
         );
         require!(
@@ -2677,12 +4801,18 @@ pub mod phantom_paradox {
 
         let buyer_ledger = &ctx.accounts.buyer_ledger;
         if game.kyc_required {
-            require!(buyer_ledger.kyc_verified, PgError::KycRequired);
+            require!(buyer_ledger.has_valid_kyc(now), PgError::KycRequired);
         }
+        game.jurisdictions.check(buyer_ledger, now)?;
         require!(
             buyer_ledger.authority == ctx.accounts.buyer_signer.key(),
             PgError::Unauthorized
         );
+        listing.check_buyer(&buyer_ledger.authority, &allowlist_proof)?;
+        require!(
+            !buyer_ledger.limits.is_excluded(now),
+            PgError::SelfExcluded
+        );
 
         let seller_ledger = &ctx.accounts.seller_ledger;
         require!(
@@ -2690,6 +4820,59 @@ pub mod phantom_paradox {
             PgError::Unauthorized
         );
 
+        // Multi-recipient royalties: the split must be the one recorded on the listing
+        if listing.royalty_split != Pubkey::default() {
+            let split = ctx
+                .accounts
+                .royalty_split
+                .as_ref()
+                .ok_or(PgError::RoyaltySplitMismatch)?;
+            require!(
+                split.key() == listing.royalty_split,
+                PgError::RoyaltySplitMismatch
+            );
+        }
+
+        if let Some(referral) = &ctx.accounts.referral {
+            referral.check(&game.key(), &buyer_ledger.authority, &listing.seller)?;
+        }
+
+        // Paying the protocol fee in PDOX: opted into by passing a PDOX token account
+        let pdox_payment = match ctx.accounts.buyer_pdox_account.as_deref() {
+            Some(payer_pdox_account) => Some(instructions::pdox_fees::PdoxFeeAccounts {
+                pdox_mint: ctx
+                    .accounts
+                    .pdox_mint
+                    .as_deref()
+                    .ok_or(PgError::InvalidPdoxPayment)?,
+                payer_pdox_account,
+                price_source: ctx
+                    .accounts
+                    .pdox_price_source
+                    .as_deref()
+                    .ok_or(PgError::InvalidPdoxPriceSource)?,
+                pdox_reserve: ctx
+                    .accounts
+                    .pdox_reserve
+                    .as_deref()
+                    .ok_or(PgError::InvalidPdoxPriceSource)?,
+                quote_reserve: ctx
+                    .accounts
+                    .pdox_quote_reserve
+                    .as_deref()
+                    .ok_or(PgError::InvalidPdoxPriceSource)?,
+                sink_account: ctx.accounts.pdox_fee_sink.as_deref(),
+                payer: ctx.accounts.buyer_signer.to_account_info(),
+                token_program: ctx
+                    .accounts
+                    .pdox_token_program
+                    .as_ref()
+                    .ok_or(PgError::InvalidPdoxPayment)?
+                    .to_account_info(),
+            }),
+            None => None,
+        };
+
         // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
         let game = &mut ctx.accounts.game;
         let listing = &mut ctx.accounts.listing;
@@ -2697,10 +4880,15 @@ pub mod phantom_paradox {
         let seller_ledger = &mut ctx.accounts.seller_ledger;
         enter_execution_game(game)?;
         let res = (|| -> Result<()> {
-            // Price - fixed price listings use start_price per unit
-            // Price is fully determined on-chain from listing.start_price * quantity
-            // No user-supplied price to validate - this is safe by design
-            let unit_price = listing.start_price;
+            // Price - fixed price listings use start_price per unit, Dutch auctions the curve price
+            // Price is fully determined on-chain from the unit price * quantity; the buyer's
+            // max_unit_price bounds it in case the seller changed the price in flight
+            let unit_price = if listing.kind == ListingKind::DutchAuction {
+                current_dutch_price(listing, now)?
+            } else {
+                listing.start_price
+            };
+            require!(unit_price <= max_unit_price, PgError::InvalidAmount);
             let total_price = unit_price.checked_mul(quantity).ok_or(PgError::Overflow)?;
 
             // CRITICAL: Enforce maximum price if buy_now_price is set (prevents overcharge attacks)
@@ -2713,10 +4901,46 @@ pub mod phantom_paradox {
                 require!(total_price <= max_total_price, PgError::InvalidAmount);
             }
 
+            // Maker/taker protocol fees from each side's 30-day volume (taker fee is paid on top)
+            let maker_volume_30d = seller_ledger.fee_volume.volume(now)?;
+            let taker_volume_30d = buyer_ledger.fee_volume.volume(now)?;
+            let maker_discount_bps = stake::stake_discount_bps(
+                cfg,
+                ctx.accounts.seller_stake.as_deref(),
+                &seller_ledger.authority,
+            )?;
+            let taker_discount_bps = stake::stake_discount_bps(
+                cfg,
+                ctx.accounts.buyer_stake.as_deref(),
+                &buyer_ledger.authority,
+            )?;
+            let fees = instructions::fee_tiers::quote_trade_fees(
+                cfg,
+                maker_volume_30d,
+                taker_volume_30d,
+                maker_discount_bps,
+                taker_discount_bps,
+                total_price,
+            )?;
+            // A fee paid in PDOX is charged to neither side's credits
+            let total_cost = if pdox_payment.is_some() {
+                total_price
+            } else {
+                total_price
+                    .checked_add(fees.taker_fee)
+                    .ok_or(PgError::Overflow)?
+            };
+
             require!(
-                buyer_ledger.available >= total_price,
+                buyer_ledger.available >= total_cost,
                 PgError::InsufficientCredits
             );
+            let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
+            buyer_ledger
+                .limits
+                .record_spend(total_cost, now, approver)?;
+            // KYC tier limits (trade size + daily volume)
+            buyer_ledger.record_kyc_trade(cfg, game.kyc_required, total_price, now)?;
 
             // Fees
             let game_fee = total_price
@@ -2725,11 +4949,12 @@ pub mod phantom_paradox {
                 .checked_div(BPS_DENOM)
                 .ok_or(PgError::Overflow)?;
 
-            let protocol_fee = total_price
-                .checked_mul(u64::from(cfg.protocol_fee_bps))
-                .ok_or(PgError::Overflow)?
-                .checked_div(BPS_DENOM)
-                .ok_or(PgError::Overflow)?;
+            // Seller-side share of the protocol fee; the taker fee is booked with it below
+            let protocol_fee = if pdox_payment.is_some() {
+                0
+            } else {
+                fees.maker_fee
+            };
 
             let royalty_amount =
                 if listing.royalty_bps > 0 && listing.royalty_recipient != Pubkey::default() {
@@ -2775,7 +5000,7 @@ pub mod phantom_paradox {
             // Move credits
             buyer_ledger.available = buyer_ledger
                 .available
-                .checked_sub(total_price)
+                .checked_sub(total_cost)
                 .ok_or(PgError::Overflow)?;
 
             seller_ledger.available = seller_ledger
@@ -2783,6 +5008,40 @@ pub mod phantom_paradox {
                 .checked_add(seller_amount)
                 .ok_or(PgError::Overflow)?;
 
+            // Both sides accrue volume toward their next fee tier
+            seller_ledger.fee_volume.record(total_price, now)?;
+            buyer_ledger.fee_volume.record(total_price, now)?;
+            emit!(TradeFeeRates {
+                game: game.key(),
+                listing: listing.key(),
+                maker: seller_ledger.authority,
+                taker: buyer_ledger.authority,
+                maker_volume_30d,
+                taker_volume_30d,
+                maker_fee_bps: fees.maker_fee_bps,
+                taker_fee_bps: fees.taker_fee_bps,
+                maker_discount_bps,
+                taker_discount_bps,
+                maker_fee: fees.maker_fee,
+                taker_fee: fees.taker_fee,
+            });
+            let protocol_fee = fees.total()?;
+
+            // Protocol fee paid in PDOX leaves nothing in credits to book or share with a referrer
+            let credit_protocol_fee = match &pdox_payment {
+                Some(accounts) => {
+                    instructions::pdox_fees::pay_protocol_fee_in_pdox(
+                        accounts,
+                        cfg,
+                        game.key(),
+                        game.currency_mint,
+                        protocol_fee,
+                    )?;
+                    0
+                }
+                None => protocol_fee,
+            };
+
             // CRITICAL: Pay royalties to royalty recipient (was missing - money lost bug)
             // CRITICAL: Only process royalties when royalty_amount > 0 (prevents griefing via init_if_needed)
             if royalty_amount > 0 && listing.royalty_recipient != Pubkey::default() {
@@ -2808,10 +5067,19 @@ pub mod phantom_paradox {
                     PgError::Unauthorized
                 );
 
+                // Split royalties: recipient 0 (royalty_recipient) takes its share plus rounding dust
+                let royalty_shares = match &ctx.accounts.royalty_split {
+                    Some(split) if listing.royalty_split != Pubkey::default() => {
+                        Some(split.shares(royalty_amount)?)
+                    }
+                    _ => None,
+                };
+                let primary_royalty = royalty_shares.map_or(royalty_amount, |shares| shares[0]);
+
                 // Credit royalties to recipient
                 royalty_ledger.available = royalty_ledger
                     .available
-                    .checked_add(royalty_amount)
+                    .checked_add(primary_royalty)
                     .ok_or(PgError::Overflow)?;
 
                 // Emit royalty payment event for off-chain tracking
@@ -2819,8 +5087,19 @@ pub mod phantom_paradox {
                     listing: listing.key(),
                     game: game.key(),
                     recipient: listing.royalty_recipient,
-                    amount: royalty_amount,
+                    amount: primary_royalty,
                 });
+
+                if let (Some(split), Some(shares)) = (&ctx.accounts.royalty_split, &royalty_shares) {
+                    instructions::royalty_split::credit_royalty_split(
+                        split,
+                        shares,
+                        listing.key(),
+                        &mut [&mut **buyer_ledger, &mut **seller_ledger],
+                        ctx.remaining_accounts,
+                        ctx.program_id,
+                    )?;
+                }
             } else if listing.royalty_recipient != Pubkey::default() && listing.royalty_bps == 0 {
                 // CRITICAL: Prevent griefing - if royalty_bps == 0, account should not be created
                 // If account exists but shouldn't (edge case), fail the transaction
@@ -2834,11 +5113,28 @@ pub mod phantom_paradox {
                 }
             }
 
+            // Referral: the referrer's cut is carved out of the protocol fee
+            let referral_fee = match ctx.accounts.referral.as_deref_mut() {
+                Some(referral) => instructions::referral::accrue_referral(
+                    referral,
+                    cfg.referral_bps,
+                    credit_protocol_fee,
+                    listing.key(),
+                    buyer_ledger.authority,
+                    total_price,
+                )?,
+                None => 0,
+            };
+
             // CRITICAL: Update Protocol Fees - track per-game, not global
             // This prevents "Robin Hood" risk where fees from Game B are withdrawn from Game A's vault
             game.protocol_fees_accumulated = game
                 .protocol_fees_accumulated
-                .checked_add(protocol_fee)
+                .checked_add(
+                    credit_protocol_fee
+                        .checked_sub(referral_fee)
+                        .ok_or(PgError::Overflow)?,
+                )
                 .ok_or(PgError::Overflow)?;
 
             // DEPRECATED: Keep global counter for backward compatibility (will be removed in v2)
@@ -2945,17 +5241,32 @@ pub mod phantom_paradox {
 
     /// Finalize auction settlement (server-authority + winner co-sign).
     ///
+    /// Maker/taker fees and PDOX staker discounts apply as in `buy_fixed` (seller = maker,
+    /// winner = taker).
+    ///
     /// # Errors
     /// - `PgError::InvalidListingStatus` if listing is not Active or `PartiallyFilled`
     /// - `PgError::InvalidListingKind` if listing is not an auction type
     /// - `PgError::InvalidAmount` if `clearing_price` < `min_expected_price` or quantity > `max_quantity`
+    /// - `PgError::InvalidAmount` if a Dutch auction has no locked bid (new Dutch fills use `buy_fixed`)
+    ///   or its bid price exceeds `clearing_price`
+    /// - `PgError::InvalidTime` if a sealed-bid auction is settled before its reveal window closes
+    ///   or after `SEALED_BID_SETTLEMENT_WINDOW_SECS` more have passed
+    /// - `PgError::InvalidAmount` if the on-chain second price exceeds `clearing_price` (`SecondPrice` mode)
     /// - `PgError::InsufficientQuantity` if quantity exceeds available
-    /// - `PgError::InsufficientCredits` if winner doesn't have enough credits
+    /// - `PgError::InsufficientCredits` if winner doesn't have enough credits (price plus taker fee)
+    /// - `PgError::KycRequired` / `PgError::KycTradeSizeExceeded` / `PgError::KycDailyVolumeExceeded`
+    ///   if the winner's KYC tier doesn't allow the fill
+    /// - `PgError::SelfExcluded` / `PgError::SpendingCapExceeded` / `PgError::GuardianApprovalRequired`
+    ///   if the winner's spending limits don't allow the amount taken from available credits
+    /// - `PgError::RoyaltySplitMismatch` if the listing's royalty split account is missing or wrong
+    /// - `PgError::SelfReferral` if the buyer or seller refers their own trade
+    /// - `PgError::InvalidStakeAccount` if a stake account isn't the side's pdox_stake `AgentAccount`
     /// - `PgError::Overflow` on arithmetic overflow
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     #[allow(clippy::too_many_lines)] // Complex settlement logic requires many lines
-    pub fn finalize_auction_settlement(
-        ctx: Context<FinalizeAuction>,
+    pub fn finalize_auction_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeAuction<'info>>,
         quantity: u64,
         clearing_price: u64,
         min_expected_price: u64, // CRITICAL: Winner's minimum acceptable price (prevents overcharge)
@@ -2983,7 +5294,8 @@ pub mod phantom_paradox {
 
         require!(
             listing.kind == ListingKind::EnglishAuction
-                || listing.kind == ListingKind::DutchAuction,
+                || listing.kind == ListingKind::DutchAuction
+                || listing.kind == ListingKind::SealedBid,
             PgError::InvalidListingKind
         );
 
@@ -3020,19 +5332,45 @@ pub mod phantom_paradox {
             PgError::Unauthorized
         );
 
+        // Multi-recipient royalties: the split must be the one recorded on the listing
+        if listing.royalty_split != Pubkey::default() {
+            let split = ctx
+                .accounts
+                .royalty_split
+                .as_ref()
+                .ok_or(PgError::RoyaltySplitMismatch)?;
+            require!(
+                split.key() == listing.royalty_split,
+                PgError::RoyaltySplitMismatch
+            );
+        }
+
+        if let Some(referral) = &ctx.accounts.referral {
+            referral.check(&game.key(), &winner_ledger.authority, &listing.seller)?;
+        }
+
         // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
         let game = &mut ctx.accounts.game;
         let listing = &mut ctx.accounts.listing;
         let winner_ledger = &mut ctx.accounts.winner_ledger;
         enter_execution_game(game)?;
         let res = (|| -> Result<()> {
-            // CRITICAL: Enforce KYC if required (was missing - security/compliance bug)
-            if game.kyc_required {
-                require!(winner_ledger.kyc_verified, PgError::KycRequired);
-            }
-
             // CRITICAL: Enforce price bounds based on auction type
-            match listing.kind {
+            let clearing_price = match listing.kind {
+                ListingKind::EnglishAuction
+                    if listing.settlement_mode == SettlementMode::SecondPrice =>
+                {
+                    // Vickrey: price computed on-chain from the runner-up bid, never from the server
+                    require!(listing.highest_bid > 0, PgError::InvalidAmount);
+                    require!(
+                        listing.highest_bidder == winner_ledger.authority,
+                        PgError::Unauthorized
+                    );
+                    let vickrey_price = second_price(listing);
+                    // Supplied clearing_price acts as the winner's max
+                    require!(vickrey_price <= clearing_price, PgError::InvalidAmount);
+                    vickrey_price
+                }
                 ListingKind::EnglishAuction => {
                     // English: clearing_price must be >= reserve_price (if set)
                     if listing.reserve_price > 0 {
@@ -3041,41 +5379,86 @@ pub mod phantom_paradox {
                             PgError::InvalidAmount
                         );
                     }
+                    clearing_price
                 }
                 ListingKind::DutchAuction => {
-                    // Dutch: clearing_price must be between dutch_min_price and start_price
+                    // Dutch: only bids locked before Dutch fills moved to buy_fixed settle here,
+                    // at the locked bid price (never a server-chosen price)
+                    require!(listing.highest_bid > 0, PgError::InvalidAmount);
+                    require!(
+                        listing.highest_bidder == winner_ledger.authority,
+                        PgError::Unauthorized
+                    );
                     require!(
-                        clearing_price >= listing.dutch_min_price,
+                        listing.highest_bid <= clearing_price,
                         PgError::InvalidAmount
                     );
+                    listing.highest_bid
+                }
+                ListingKind::SealedBid => {
+                    // Sealed: settle only after the reveal window, at the highest valid reveal,
+                    // and before the winner may reclaim an unsettled deposit
+                    let reveal_end = listing
+                        .end_time
+                        .checked_add(SEALED_BID_REVEAL_WINDOW_SECS)
+                        .ok_or(PgError::Overflow)?;
+                    require!(now >= reveal_end, PgError::InvalidTime);
+                    let settle_deadline = reveal_end
+                        .checked_add(SEALED_BID_SETTLEMENT_WINDOW_SECS)
+                        .ok_or(PgError::Overflow)?;
+                    require!(now < settle_deadline, PgError::InvalidTime);
+                    require!(listing.highest_bid > 0, PgError::InvalidAmount);
+                    require!(
+                        listing.highest_bidder == winner_ledger.authority,
+                        PgError::Unauthorized
+                    );
+                    // Whole lot only - the winner's deposit was sized for quantity_total
                     require!(
-                        clearing_price <= listing.start_price,
+                        quantity == listing.quantity_remaining,
                         PgError::InvalidAmount
                     );
+                    let sealed_price = if listing.settlement_mode == SettlementMode::SecondPrice {
+                        second_price(listing)
+                    } else {
+                        listing.highest_bid
+                    };
+                    require!(sealed_price <= clearing_price, PgError::InvalidAmount);
+                    sealed_price
                 }
                 ListingKind::Fixed => {
                     // Fixed price shouldn't reach here
                     return Err(PgError::InvalidListingKind.into());
                 }
-            }
+            };
 
             // Funds check
             let total_price = clearing_price
                 .checked_mul(quantity)
                 .ok_or(PgError::Overflow)?;
 
+            // CRITICAL: Enforce KYC requirement; the whole fill must fit the winner's tier
+            winner_ledger.check_kyc_trade(cfg, game.kyc_required, total_price, now)?;
+
             // CRITICAL: Handle payment based on whether winner placed a bid
             // If winner is highest_bidder, their bid is locked - use it and adjust
             // If winner is not highest_bidder, deduct from available
             let winner_is_highest_bidder =
                 listing.highest_bidder == winner_ledger.authority && listing.highest_bid > 0;
+            // Spending limits and KYC daily volume: whatever is taken from available credits
+            // (locked bids were already counted when placed)
+            let approver = ctx.accounts.guardian.as_ref().map(Signer::key);
 
             if winner_is_highest_bidder {
                 // Winner has a locked bid - use it and adjust for clearing price
-                let locked_bid_total = listing
-                    .highest_bid
-                    .checked_mul(quantity)
-                    .ok_or(PgError::Overflow)?;
+                // (a Dutch bid locked a single unit price, whatever quantity it settles)
+                let locked_bid_total = if listing.kind == ListingKind::DutchAuction {
+                    listing.highest_bid
+                } else {
+                    listing
+                        .highest_bid
+                        .checked_mul(quantity)
+                        .ok_or(PgError::Overflow)?
+                };
 
                 require!(
                     winner_ledger.locked >= locked_bid_total,
@@ -3098,6 +5481,14 @@ pub mod phantom_paradox {
                         winner_ledger.available >= additional,
                         PgError::InsufficientCredits
                     );
+                    require!(
+                        !winner_ledger.limits.is_excluded(now),
+                        PgError::SelfExcluded
+                    );
+                    winner_ledger
+                        .limits
+                        .record_spend(additional, now, approver)?;
+                    winner_ledger.record_kyc_trade(cfg, game.kyc_required, additional, now)?;
                     winner_ledger.available = winner_ledger
                         .available
                         .checked_sub(additional)
@@ -3119,6 +5510,14 @@ pub mod phantom_paradox {
                     winner_ledger.available >= total_price,
                     PgError::InsufficientCredits
                 );
+                require!(
+                    !winner_ledger.limits.is_excluded(now),
+                    PgError::SelfExcluded
+                );
+                winner_ledger
+                    .limits
+                    .record_spend(total_price, now, approver)?;
+                winner_ledger.record_kyc_trade(cfg, game.kyc_required, total_price, now)?;
                 winner_ledger.available = winner_ledger
                     .available
                     .checked_sub(total_price)
@@ -3143,11 +5542,29 @@ pub mod phantom_paradox {
                 .checked_div(BPS_DENOM)
                 .ok_or(PgError::Overflow)?;
 
-            let protocol_fee = total_price
-                .checked_mul(u64::from(cfg.protocol_fee_bps))
-                .ok_or(PgError::Overflow)?
-                .checked_div(BPS_DENOM)
-                .ok_or(PgError::Overflow)?;
+            // Maker/taker protocol fees from each side's 30-day volume (before this trade)
+            let maker_volume_30d = seller_ledger.fee_volume.volume(now)?;
+            let taker_volume_30d = winner_ledger.fee_volume.volume(now)?;
+            let maker_discount_bps = stake::stake_discount_bps(
+                cfg,
+                ctx.accounts.seller_stake.as_deref(),
+                &seller_ledger.authority,
+            )?;
+            let taker_discount_bps = stake::stake_discount_bps(
+                cfg,
+                ctx.accounts.winner_stake.as_deref(),
+                &winner_ledger.authority,
+            )?;
+            let fees = instructions::fee_tiers::quote_trade_fees(
+                cfg,
+                maker_volume_30d,
+                taker_volume_30d,
+                maker_discount_bps,
+                taker_discount_bps,
+                total_price,
+            )?;
+            // Seller-side share of the protocol fee; the taker fee is booked with it below
+            let protocol_fee = fees.maker_fee;
 
             let royalty_amount =
                 if listing.royalty_bps > 0 && listing.royalty_recipient != Pubkey::default() {
@@ -3190,13 +5607,40 @@ pub mod phantom_paradox {
             // This prevents edge cases where rounding or fee configuration could result in zero/negative amounts
             require!(seller_amount > 0, PgError::InvalidSellerAmount);
 
-            // Credits already moved above (with bid adjustment logic)
+            // Credits already moved above (with bid adjustment logic); the taker fee comes on top
+            require!(
+                winner_ledger.available >= fees.taker_fee,
+                PgError::InsufficientCredits
+            );
+            winner_ledger.available = winner_ledger
+                .available
+                .checked_sub(fees.taker_fee)
+                .ok_or(PgError::Overflow)?;
 
             seller_ledger.available = seller_ledger
                 .available
                 .checked_add(seller_amount)
                 .ok_or(PgError::Overflow)?;
 
+            // Both sides accrue volume toward their next fee tier
+            seller_ledger.fee_volume.record(total_price, now)?;
+            winner_ledger.fee_volume.record(total_price, now)?;
+            emit!(TradeFeeRates {
+                game: game.key(),
+                listing: listing.key(),
+                maker: seller_ledger.authority,
+                taker: winner_ledger.authority,
+                maker_volume_30d,
+                taker_volume_30d,
+                maker_fee_bps: fees.maker_fee_bps,
+                taker_fee_bps: fees.taker_fee_bps,
+                maker_discount_bps,
+                taker_discount_bps,
+                maker_fee: fees.maker_fee,
+                taker_fee: fees.taker_fee,
+            });
+            let protocol_fee = fees.total()?;
+
             // CRITICAL: Pay royalties to royalty recipient (was missing - money lost bug)
             // CRITICAL: Only process royalties when royalty_amount > 0 (prevents griefing via init_if_needed)
             if royalty_amount > 0 && listing.royalty_recipient != Pubkey::default() {
@@ -3222,10 +5666,19 @@ pub mod phantom_paradox {
                     PgError::Unauthorized
                 );
 
+                // Split royalties: recipient 0 (royalty_recipient) takes its share plus rounding dust
+                let royalty_shares = match &ctx.accounts.royalty_split {
+                    Some(split) if listing.royalty_split != Pubkey::default() => {
+                        Some(split.shares(royalty_amount)?)
+                    }
+                    _ => None,
+                };
+                let primary_royalty = royalty_shares.map_or(royalty_amount, |shares| shares[0]);
+
                 // Credit royalties to recipient
                 royalty_ledger.available = royalty_ledger
                     .available
-                    .checked_add(royalty_amount)
+                    .checked_add(primary_royalty)
                     .ok_or(PgError::Overflow)?;
 
                 // Emit royalty payment event for off-chain tracking
@@ -3233,8 +5686,19 @@ pub mod phantom_paradox {
                     listing: listing.key(),
                     game: game.key(),
                     recipient: listing.royalty_recipient,
-                    amount: royalty_amount,
+                    amount: primary_royalty,
                 });
+
+                if let (Some(split), Some(shares)) = (&ctx.accounts.royalty_split, &royalty_shares) {
+                    instructions::royalty_split::credit_royalty_split(
+                        split,
+                        shares,
+                        listing.key(),
+                        &mut [&mut **winner_ledger, &mut **seller_ledger],
+                        ctx.remaining_accounts,
+                        ctx.program_id,
+                    )?;
+                }
             } else if listing.royalty_recipient != Pubkey::default() && listing.royalty_bps == 0 {
                 // CRITICAL: Prevent griefing - if royalty_bps == 0, account should not be created
                 // If account exists but shouldn't (edge case), fail the transaction
@@ -3248,11 +5712,28 @@ pub mod phantom_paradox {
                 }
             }
 
+            // Referral: the referrer's cut is carved out of the protocol fee
+            let referral_fee = match ctx.accounts.referral.as_deref_mut() {
+                Some(referral) => instructions::referral::accrue_referral(
+                    referral,
+                    cfg.referral_bps,
+                    protocol_fee,
+                    listing.key(),
+                    winner_ledger.authority,
+                    total_price,
+                )?,
+                None => 0,
+            };
+
             // CRITICAL: Update Protocol Fees - track per-game, not global
             // This prevents "Robin Hood" risk where fees from Game B are withdrawn from Game A's vault
             game.protocol_fees_accumulated = game
                 .protocol_fees_accumulated
-                .checked_add(protocol_fee)
+                .checked_add(
+                    protocol_fee
+                        .checked_sub(referral_fee)
+                        .ok_or(PgError::Overflow)?,
+                )
                 .ok_or(PgError::Overflow)?;
 
             // DEPRECATED: Keep global counter for backward compatibility (will be removed in v2)
@@ -3366,65 +5847,141 @@ pub mod phantom_paradox {
         Ok(())
     }
 
-    // --------------------------------------------------------------
-    // FEE WITHDRAWAL
-    // --------------------------------------------------------------
+    // --------------------------------------------------------------
+    // FEE WITHDRAWAL
+    // --------------------------------------------------------------
+
+    /// Withdraw accumulated protocol fees to the treasury
+    /// Only governance can call this
+    /// Withdraw accumulated protocol fees.
+    ///
+    /// Disabled while the fee router is on; fees then leave via `distribute_protocol_fees`.
+    ///
+    /// # Errors
+    /// - `PgError::InvalidAmount` if amount is 0
+    /// - `PgError::FeeRouterActive` if governance has configured fee router splits
+    /// - `PgError::Unauthorized` if caller is not protocol treasury
+    /// - `PgError::InsufficientCredits` if accumulated fees are less than amount
+    #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+        require!(amount > 0, PgError::InvalidAmount);
+
+        // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
+        let cfg = &ctx.accounts.config;
+        let game = &ctx.accounts.game;
+        require!(!cfg.fee_router.is_enabled(), PgError::FeeRouterActive);
+
+        // CRITICAL: Use per-game protocol fees, not global (prevents "Robin Hood" risk)
+        require!(
+            game.protocol_fees_accumulated >= amount,
+            PgError::InsufficientCredits
+        );
+
+        // Extract values needed for CPI and events before mutable borrows
+        let protocol_treasury = cfg.protocol_treasury;
+
+        // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
+        let game = &mut ctx.accounts.game;
+        enter_execution_game(game)?;
+        let res = (|| -> Result<()> {
+            // Decrement per-game protocol fees
+            game.protocol_fees_accumulated = game
+                .protocol_fees_accumulated
+                .checked_sub(amount)
+                .ok_or(PgError::Overflow)?;
+
+            // DEPRECATED: Also decrement global counter for backward compatibility
+            let cfg_mut = &mut ctx.accounts.config;
+            cfg_mut.accumulated_fees = cfg_mut
+                .accumulated_fees
+                .checked_sub(amount)
+                .ok_or(PgError::Overflow)?;
+
+            // Extract game values before CPI
+            let game_id = game.game_id;
+            let game_bump = game.bump;
+
+            // Transfer from vault -> treasury
+            let decimals = ctx.accounts.currency_mint.decimals;
+            let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+            let signer = &[seeds];
+
+            let cpi_accounts = token_interface::TransferChecked {
+                from: ctx.accounts.game_vault.to_account_info(),
+                mint: ctx.accounts.currency_mint.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+                authority: game.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+
+            emit!(ProtocolFeesWithdrawn {
+                game: game.key(),
+                treasury: protocol_treasury,
+                amount,
+            });
+
+            Ok(())
+        })();
+
+        exit_execution_game(game);
+        res
+    }
 
-    /// Withdraw accumulated protocol fees to the treasury
-    /// Only governance can call this
-    /// Withdraw accumulated protocol fees.
+    /// Withdraw accumulated game fees to the game owner wallet
+    /// Only game owner or a `FeeWithdrawer` can call this
+    /// Withdraw accumulated game fees (always paid to `payout_wallet`).
     ///
     /// # Errors
     /// - `PgError::InvalidAmount` if amount is 0
-    /// - `PgError::Unauthorized` if caller is not protocol treasury
+    /// - `PgError::Unauthorized` if caller is neither the game owner nor a `FeeWithdrawer`
     /// - `PgError::InsufficientCredits` if accumulated fees are less than amount
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
-    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+    pub fn withdraw_game_fees(ctx: Context<WithdrawGameFees>, amount: u64) -> Result<()> {
         require!(amount > 0, PgError::InvalidAmount);
 
         // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
-        let cfg = &ctx.accounts.config;
         let game = &ctx.accounts.game;
-
-        // CRITICAL: Use per-game protocol fees, not global (prevents "Robin Hood" risk)
         require!(
-            game.protocol_fees_accumulated >= amount,
+            instructions::game_roles::is_owner_or_role(
+                game,
+                ctx.accounts.roles.as_deref(),
+                &ctx.accounts.owner.key(),
+                GameRole::FeeWithdrawer,
+            ),
+            PgError::Unauthorized
+        );
+        require!(
+            game.accumulated_game_fees >= amount,
             PgError::InsufficientCredits
         );
 
-        // Extract values needed for CPI and events before mutable borrows
-        let protocol_treasury = cfg.protocol_treasury;
-
         // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
         let game = &mut ctx.accounts.game;
         enter_execution_game(game)?;
         let res = (|| -> Result<()> {
-            // Decrement per-game protocol fees
-            game.protocol_fees_accumulated = game
-                .protocol_fees_accumulated
-                .checked_sub(amount)
-                .ok_or(PgError::Overflow)?;
-
-            // DEPRECATED: Also decrement global counter for backward compatibility
-            let cfg_mut = &mut ctx.accounts.config;
-            cfg_mut.accumulated_fees = cfg_mut
-                .accumulated_fees
+            // Decrement accumulated game fees
+            game.accumulated_game_fees = game
+                .accumulated_game_fees
                 .checked_sub(amount)
                 .ok_or(PgError::Overflow)?;
 
-            // Extract game values before CPI
-            let game_id = game.game_id;
-            let game_bump = game.bump;
-
-            // Transfer from vault -> treasury
+            // Transfer from vault -> game_owner wallet
             let decimals = ctx.accounts.currency_mint.decimals;
-            let seeds: &[&[u8]] = &[GAME_SEED, &game_id.to_le_bytes(), &[game_bump]];
+            let seeds: &[&[u8]] = &[GAME_SEED, &game.game_id.to_le_bytes(), &[game.bump]];
             let signer = &[seeds];
 
+            // Extract payout_wallet before mutable borrow
+            let payout_wallet = game.payout_wallet;
+
             let cpi_accounts = token_interface::TransferChecked {
                 from: ctx.accounts.game_vault.to_account_info(),
                 mint: ctx.accounts.currency_mint.to_account_info(),
-                to: ctx.accounts.treasury.to_account_info(),
+                to: ctx.accounts.game_owner_wallet.to_account_info(),
                 authority: game.to_account_info(),
             };
             let cpi_ctx = CpiContext::new_with_signer(
@@ -3434,119 +5991,506 @@ pub mod phantom_paradox {
             );
             token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
 
-            emit!(ProtocolFeesWithdrawn {
+            emit!(GameFeesWithdrawn {
                 game: game.key(),
-                treasury: protocol_treasury,
+                payout_wallet,
                 amount,
             });
 
             Ok(())
         })();
 
-        exit_execution_game(game);
-        res
+        exit_execution_game(game);
+        res
+    }
+
+    // --------------------------------------------------------------
+    // ACCOUNT CLEANUP / RENT RECLAMATION
+    // --------------------------------------------------------------
+
+    /// Close a settled or cancelled listing and reclaim rent
+    /// Only seller, game owner, or governance can close
+    /// Close a listing account to reclaim rent.
+    ///
+    /// # Errors
+    /// - `PgError::Unauthorized` if listing.game doesn't match game key
+    /// - `PgError::InvalidAmount` if listing is not Settled or Cancelled, or `quantity_remaining` != 0
+    /// - `PgError::Unauthorized` if caller is not game owner or governance
+    #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
+    pub fn close_listing(ctx: Context<CloseListing>) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        let game = &ctx.accounts.game;
+
+        require!(listing.game == game.key(), PgError::Unauthorized);
+        require!(
+            listing.status == ListingStatus::Settled || listing.status == ListingStatus::Cancelled,
+            PgError::InvalidListingStatus
+        );
+        require!(listing.quantity_remaining == 0, PgError::InvalidAmount);
+
+        // Authority check
+        let caller = ctx.accounts.caller.key();
+        let cfg = &ctx.accounts.config;
+        require!(
+            caller == listing.seller
+                || instructions::game_roles::is_owner_or_role(
+                    game,
+                    ctx.accounts.roles.as_deref(),
+                    &caller,
+                    GameRole::ListingModerator,
+                )
+                || caller == cfg.governance
+                || caller == cfg.admin,
+            PgError::Unauthorized
+        );
+
+        // Account will be closed automatically via #[account(close = recipient)]
+        Ok(())
+    }
+
+    // --------------------------------------------------------------
+    // SEALED-BID (COMMIT-REVEAL) AUCTIONS
+    // --------------------------------------------------------------
+
+    /// Commit a sealed bid: `commitment = keccak(listing || bidder || amount_le || salt)`.
+    pub fn commit_sealed_bid(
+        ctx: Context<CommitSealedBid>,
+        commitment: [u8; 32],
+        deposit: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::sealed_bid::commit_sealed_bid(ctx, commitment, deposit, allowlist_proof)
+    }
+
+    /// Reveal a sealed bid after `end_time` (within the reveal window).
+    pub fn reveal_sealed_bid(
+        ctx: Context<RevealSealedBid>,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::sealed_bid::reveal_sealed_bid(ctx, amount, salt)
+    }
+
+    /// Release a sealed-bid deposit (non-revealers forfeit a share to the seller).
+    pub fn claim_sealed_bid_deposit(ctx: Context<ClaimSealedBidDeposit>) -> Result<()> {
+        instructions::sealed_bid::claim_sealed_bid_deposit(ctx)
+    }
+
+    // --------------------------------------------------------------
+    // COLLECTION / TRAIT OFFERS
+    // --------------------------------------------------------------
+
+    /// Create a standing offer for any item whose mint is in `criteria_root`.
+    pub fn create_collection_offer(
+        ctx: Context<CreateCollectionOffer>,
+        offer_id: u64,
+        scope: OfferScope,
+        criteria_root: [u8; 32],
+        price_per_unit: u64,
+        quantity: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::collection_offer::create_collection_offer(
+            ctx,
+            offer_id,
+            scope,
+            criteria_root,
+            price_per_unit,
+            quantity,
+            expires_at,
+        )
+    }
+
+    /// Sell matching items into a collection offer (Merkle proof of the item mint).
+    /// Royalties are resolved from the accepted item's metadata.
+    pub fn accept_collection_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptCollectionOffer<'info>>,
+        quantity: u64,
+        proof: Vec<[u8; 32]>,
+        royalty_recipient: Pubkey,
+        royalty_bps: u16,
+    ) -> Result<()> {
+        instructions::collection_offer::accept_collection_offer(
+            ctx,
+            quantity,
+            proof,
+            royalty_recipient,
+            royalty_bps,
+        )
+    }
+
+    /// Cancel a collection offer and unlock the remaining credits.
+    pub fn cancel_collection_offer(ctx: Context<CancelCollectionOffer>) -> Result<()> {
+        instructions::collection_offer::cancel_collection_offer(ctx)
+    }
+
+    // --------------------------------------------------------------
+    // BUNDLE LISTINGS
+    // --------------------------------------------------------------
+
+    /// List several item mints as one fixed-price lot (components via `remaining_accounts`).
+    #[allow(clippy::too_many_arguments)] // Required for bundle creation parameters
+    pub fn create_bundle_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateBundleListing<'info>>,
+        bundle_id: u64,
+        quantities: Vec<u64>,
+        price: u64,
+        start_time: i64,
+        end_time: i64,
+        royalty_recipient: Pubkey,
+        royalty_bps: u16,
+    ) -> Result<()> {
+        instructions::bundle::create_bundle_listing(
+            ctx,
+            bundle_id,
+            quantities,
+            price,
+            start_time,
+            end_time,
+            royalty_recipient,
+            royalty_bps,
+        )
+    }
+
+    /// Buy every component of a bundle in one atomic settlement.
+    pub fn buy_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, BuyBundle<'info>>) -> Result<()> {
+        instructions::bundle::buy_bundle(ctx)
+    }
+
+    /// Cancel a bundle and return every component to the seller.
+    pub fn cancel_bundle_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelBundleListing<'info>>,
+    ) -> Result<()> {
+        instructions::bundle::cancel_bundle_listing(ctx)
+    }
+
+    // --------------------------------------------------------------
+    // ITEM SWAPS / BARTER
+    // --------------------------------------------------------------
+
+    /// Offer escrowed items for another player's items (pass `parent_offer` to counter an offer).
+    #[allow(clippy::too_many_arguments)] // Required for swap offer parameters
+    pub fn create_swap_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateSwapOffer<'info>>,
+        offer_id: u64,
+        taker: Pubkey,
+        maker_quantities: Vec<u64>,
+        taker_mints: Vec<Pubkey>,
+        taker_quantities: Vec<u64>,
+        top_up: u64,
+        top_up_payer: SwapParty,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::swap::create_swap_offer(
+            ctx,
+            offer_id,
+            taker,
+            maker_quantities,
+            taker_mints,
+            taker_quantities,
+            top_up,
+            top_up_payer,
+            expires_at,
+        )
+    }
+
+    /// Accept a swap offer: both item legs and the currency top-up settle atomically.
+    /// Accepting a counter-offer also cancels the offer it countered.
+    pub fn accept_swap_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptSwapOffer<'info>>,
+    ) -> Result<()> {
+        instructions::swap::accept_swap_offer(ctx)
+    }
+
+    /// Cancel a swap offer, return the escrowed items and any maker top-up to the maker.
+    pub fn cancel_swap_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelSwapOffer<'info>>,
+    ) -> Result<()> {
+        instructions::swap::cancel_swap_offer(ctx)
+    }
+
+    // --------------------------------------------------------------
+    // ORDER BOOK (FUNGIBLE ITEMS)
+    // --------------------------------------------------------------
+
+    /// Create the order book for one item mint of a game.
+    pub fn create_order_book(ctx: Context<CreateOrderBook>, min_notional: u64) -> Result<()> {
+        instructions::order_book::create_order_book(ctx, min_notional)
+    }
+
+    /// Place a resting bid (credits locked) or ask (items escrowed).
+    pub fn place_order<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PlaceOrder<'info>>,
+        side: OrderSide,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        instructions::order_book::place_order(ctx, side, price, quantity)
+    }
+
+    /// Cancel a resting order and release its credits or items.
+    pub fn cancel_order(ctx: Context<CancelOrder>, side: OrderSide, order_id: u64) -> Result<()> {
+        instructions::order_book::cancel_order(ctx, side, order_id)
+    }
+
+    /// Permissionless crank: match crossed orders by price-time priority.
+    pub fn match_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MatchOrders<'info>>,
+        max_matches: u8,
+    ) -> Result<()> {
+        instructions::order_book::match_orders(ctx, max_matches)
+    }
+
+    // --------------------------------------------------------------
+    // ITEM RENTALS
+    // --------------------------------------------------------------
+
+    /// List items for rent (escrowed until rented).
+    pub fn create_rental_listing(
+        ctx: Context<CreateRentalListing>,
+        rental_id: u64,
+        quantity: u64,
+        rent_price: u64,
+        duration_secs: i64,
+        collateral: u64,
+    ) -> Result<()> {
+        instructions::rental::create_rental_listing(
+            ctx,
+            rental_id,
+            quantity,
+            rent_price,
+            duration_secs,
+            collateral,
+        )
+    }
+
+    /// Rent items: pay rent, lock collateral, receive items under a game-PDA delegate.
+    pub fn rent_item(ctx: Context<RentItem>) -> Result<()> {
+        instructions::rental::rent_item(ctx)
+    }
+
+    /// End a rental (renter any time, anyone after expiry): return items or forfeit collateral.
+    pub fn end_rental(ctx: Context<EndRental>) -> Result<()> {
+        instructions::rental::end_rental(ctx)
+    }
+
+    /// Cancel an available rental listing and return the items to the owner.
+    pub fn cancel_rental_listing(ctx: Context<CancelRentalListing>) -> Result<()> {
+        instructions::rental::cancel_rental_listing(ctx)
+    }
+
+    // --------------------------------------------------------------
+    // ROYALTY SPLITS
+    // --------------------------------------------------------------
+
+    /// Create a reusable multi-recipient royalty split (ledgers via `remaining_accounts`).
+    pub fn create_royalty_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateRoyaltySplit<'info>>,
+        split_id: u64,
+        recipients: Vec<Pubkey>,
+        weights: Vec<u16>,
+    ) -> Result<()> {
+        instructions::royalty_split::create_royalty_split(ctx, split_id, recipients, weights)
+    }
+
+    // --------------------------------------------------------------
+    // REFERRALS
+    // --------------------------------------------------------------
+
+    /// Register the signer as a referrer for a game.
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::referral::register_referrer(ctx)
+    }
+
+    /// Claim accrued referral fees into the referrer's ledger.
+    pub fn claim_referral_fees(ctx: Context<ClaimReferralFees>) -> Result<()> {
+        instructions::referral::claim_referral_fees(ctx)
+    }
+
+    // --------------------------------------------------------------
+    // KEEPER CRANKS
+    // --------------------------------------------------------------
+
+    /// Configure per-game keeper bounties (game owner / admin / governance).
+    pub fn configure_keeper_pool(
+        ctx: Context<ConfigureKeeperPool>,
+        activation_bounty: u64,
+        expiry_bounty: u64,
+    ) -> Result<()> {
+        instructions::keeper::configure_keeper_pool(ctx, activation_bounty, expiry_bounty)
+    }
+
+    /// Fund the keeper pool from ledger credits.
+    pub fn fund_keeper_pool(ctx: Context<FundKeeperPool>, amount: u64) -> Result<()> {
+        instructions::keeper::fund_keeper_pool(ctx, amount)
+    }
+
+    /// Post a seller-funded keeper deposit on a listing.
+    pub fn post_keeper_deposit(ctx: Context<KeeperDeposit>, amount: u64) -> Result<()> {
+        instructions::keeper::post_keeper_deposit(ctx, amount)
+    }
+
+    /// Withdraw the unused keeper deposit of a closed listing.
+    pub fn withdraw_keeper_deposit(ctx: Context<KeeperDeposit>) -> Result<()> {
+        instructions::keeper::withdraw_keeper_deposit(ctx)
+    }
+
+    /// Permissionless: activate a pending listing after `start_time`.
+    pub fn crank_activate_listing(ctx: Context<CrankActivateListing>) -> Result<()> {
+        instructions::keeper::crank_activate_listing(ctx)
+    }
+
+    /// Permissionless: return unsold escrow of an expired listing to the seller.
+    pub fn crank_expire_listing(ctx: Context<CrankExpireListing>) -> Result<()> {
+        instructions::keeper::crank_expire_listing(ctx)
+    }
+
+    // --------------------------------------------------------------
+    // SWEEP BUY
+    // --------------------------------------------------------------
+
+    /// Buy from several fixed-price listings at once (all-or-nothing or best-effort).
+    pub fn sweep_buy<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepBuy<'info>>,
+        items: Vec<SweepItem>,
+        all_or_nothing: bool,
+    ) -> Result<()> {
+        instructions::sweep::sweep_buy(ctx, items, all_or_nothing)
+    }
+
+    // --------------------------------------------------------------
+    // LAZY MINT
+    // --------------------------------------------------------------
+
+    /// Enable/disable minting items on first trade from metadata-hash auction leaves.
+    pub fn configure_lazy_mint(
+        ctx: Context<ConfigureLazyMint>,
+        enabled: bool,
+        decimals: u8,
+    ) -> Result<()> {
+        instructions::lazy_mint::configure_lazy_mint(ctx, enabled, decimals)
+    }
+
+    /// Server authority mints a lazy item to the buyer of its first off-chain-settled trade.
+    pub fn settle_lazy_item(
+        ctx: Context<SettleLazyItem>,
+        metadata: instructions::lazy_mint::LazyMintMetadata,
+        metadata_hash: [u8; 32],
+        quantity: u64,
+    ) -> Result<()> {
+        instructions::lazy_mint::settle_lazy_item(ctx, metadata, metadata_hash, quantity)
+    }
+
+    // --------------------------------------------------------------
+    // SPENDING LIMITS
+    // --------------------------------------------------------------
+
+    /// Set rolling daily/weekly spend caps and a guardian (guardian must co-sign once set).
+    pub fn set_spending_limits(
+        ctx: Context<SetSpendingLimits>,
+        daily_cap: u64,
+        weekly_cap: u64,
+        guardian: Pubkey,
+        guardian_threshold: u64,
+    ) -> Result<()> {
+        instructions::spending_limits::set_spending_limits(
+            ctx,
+            daily_cap,
+            weekly_cap,
+            guardian,
+            guardian_threshold,
+        )
+    }
+
+    /// Start or extend a self-exclusion cool-off (buying and bidding blocked).
+    pub fn self_exclude(ctx: Context<SelfExclude>, duration_secs: i64) -> Result<()> {
+        instructions::spending_limits::self_exclude(ctx, duration_secs)
     }
 
-    /// Withdraw accumulated game fees to the game owner wallet
-    /// Only game owner can call this
-    /// Withdraw accumulated game fees.
-    ///
-    /// # Errors
-    /// - `PgError::InvalidAmount` if amount is 0
-    /// - `PgError::Unauthorized` if caller is not game payout wallet
-    /// - `PgError::InsufficientCredits` if accumulated fees are less than amount
-    #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
-    pub fn withdraw_game_fees(ctx: Context<WithdrawGameFees>, amount: u64) -> Result<()> {
-        require!(amount > 0, PgError::InvalidAmount);
+    // --------------------------------------------------------------
+    // KYC PROVIDERS
+    // --------------------------------------------------------------
 
-        // CRITICAL: Do ALL validation BEFORE entering reentrancy guard to prevent bricking
-        let game = &ctx.accounts.game;
-        require!(
-            game.accumulated_game_fees >= amount,
-            PgError::InsufficientCredits
-        );
+    /// Approve or remove a KYC provider allowed to attest player tiers (governance only).
+    pub fn set_kyc_provider(
+        ctx: Context<SetKycProvider>,
+        provider: Pubkey,
+        approved: bool,
+    ) -> Result<()> {
+        instructions::kyc::set_kyc_provider(ctx, provider, approved)
+    }
 
-        // CRITICAL: Use RAII pattern to ensure guard is always released, even on error
-        let game = &mut ctx.accounts.game;
-        enter_execution_game(game)?;
-        let res = (|| -> Result<()> {
-            // Decrement accumulated game fees
-            game.accumulated_game_fees = game
-                .accumulated_game_fees
-                .checked_sub(amount)
-                .ok_or(PgError::Overflow)?;
+    // --------------------------------------------------------------
+    // PDOX FEE PAYMENTS
+    // --------------------------------------------------------------
 
-            // Transfer from vault -> game_owner wallet
-            let decimals = ctx.accounts.currency_mint.decimals;
-            let seeds: &[&[u8]] = &[GAME_SEED, &game.game_id.to_le_bytes(), &[game.bump]];
-            let signer = &[seeds];
+    /// Set the sink and discount for protocol fees paid in PDOX (governance only).
+    pub fn set_pdox_fee_config(
+        ctx: Context<SetPdoxFeeConfig>,
+        sink: PdoxFeeSink,
+        discount_bps: u16,
+    ) -> Result<()> {
+        instructions::pdox_fees::set_pdox_fee_config(ctx, sink, discount_bps)
+    }
 
-            // Extract payout_wallet before mutable borrow
-            let payout_wallet = game.payout_wallet;
+    /// Set the PDOX/currency pool used to price PDOX fee payments and the reference price it
+    /// must stay near (governance only).
+    pub fn set_pdox_price_source(
+        ctx: Context<SetPdoxPriceSource>,
+        min_quote_liquidity: u64,
+        reference_price: u64,
+        max_deviation_bps: u16,
+    ) -> Result<()> {
+        instructions::pdox_fees::set_pdox_price_source(
+            ctx,
+            min_quote_liquidity,
+            reference_price,
+            max_deviation_bps,
+        )
+    }
 
-            let cpi_accounts = token_interface::TransferChecked {
-                from: ctx.accounts.game_vault.to_account_info(),
-                mint: ctx.accounts.currency_mint.to_account_info(),
-                to: ctx.accounts.game_owner_wallet.to_account_info(),
-                authority: game.to_account_info(),
-            };
-            let cpi_ctx = CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                cpi_accounts,
-                signer,
-            );
-            token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+    // --------------------------------------------------------------
+    // PROTOCOL FEE ROUTER
+    // --------------------------------------------------------------
 
-            emit!(GameFeesWithdrawn {
-                game: game.key(),
-                payout_wallet,
-                amount,
-            });
+    /// Sweep a game's protocol fees into the DAO treasury, LP growth fee account and burn
+    /// along the governance splits (permissionless).
+    pub fn distribute_protocol_fees(ctx: Context<DistributeProtocolFees>) -> Result<()> {
+        instructions::fee_router::distribute_protocol_fees(ctx)
+    }
 
-            Ok(())
-        })();
+    // --------------------------------------------------------------
+    // GAME ROLES
+    // --------------------------------------------------------------
 
-        exit_execution_game(game);
-        res
+    /// Grant or revoke a member's game roles (Pauser, FeeWithdrawer, KycOperator,
+    /// ListingModerator, ConfigManager). Only the game owner can call this.
+    pub fn set_game_role(ctx: Context<SetGameRole>, member: Pubkey, roles: u8) -> Result<()> {
+        instructions::game_roles::set_game_role(ctx, member, roles)
     }
 
     // --------------------------------------------------------------
-    // ACCOUNT CLEANUP / RENT RECLAMATION
+    // ACCOUNT MIGRATIONS
     // --------------------------------------------------------------
 
-    /// Close a settled or cancelled listing and reclaim rent
-    /// Only seller, game owner, or governance can close
-    /// Close a listing account to reclaim rent.
-    ///
-    /// # Errors
-    /// - `PgError::Unauthorized` if listing.game doesn't match game key
-    /// - `PgError::InvalidAmount` if listing is not Settled or Cancelled, or `quantity_remaining` != 0
-    /// - `PgError::Unauthorized` if caller is not game owner or governance
-    #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
-    pub fn close_listing(ctx: Context<CloseListing>) -> Result<()> {
-        let listing = &ctx.accounts.listing;
-        let game = &ctx.accounts.game;
+    /// Resize the CONFIG PDA to the current `GlobalConfig` layout (governance only).
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate::migrate_config(ctx)
+    }
 
-        require!(listing.game == game.key(), PgError::Unauthorized);
-        require!(
-            listing.status == ListingStatus::Settled || listing.status == ListingStatus::Cancelled,
-            PgError::InvalidListingStatus
-        );
-        require!(listing.quantity_remaining == 0, PgError::InvalidAmount);
+    /// Resize a game to the current `GameConfig` layout (permissionless).
+    pub fn migrate_game_config(ctx: Context<MigrateGameConfig>) -> Result<()> {
+        instructions::migrate::migrate_game_config(ctx)
+    }
 
-        // Authority check
-        let caller = ctx.accounts.caller.key();
-        let cfg = &ctx.accounts.config;
-        require!(
-            caller == listing.seller
-                || caller == game.owner
-                || caller == cfg.governance
-                || caller == cfg.admin,
-            PgError::Unauthorized
-        );
+    /// Resize a listing to the current `Listing` layout (permissionless).
+    pub fn migrate_listing(ctx: Context<MigrateListing>) -> Result<()> {
+        instructions::migrate::migrate_listing(ctx)
+    }
 
-        // Account will be closed automatically via #[account(close = recipient)]
-        Ok(())
+    /// Resize a player ledger to the current `PlayerLedger` layout (permissionless).
+    pub fn migrate_player_ledger(ctx: Context<MigratePlayerLedger>) -> Result<()> {
+        instructions::migrate::migrate_player_ledger(ctx)
     }
 
     /// Close an empty player ledger and reclaim rent
@@ -3556,6 +6500,7 @@ pub mod phantom_paradox {
     /// # Errors
     /// - `PgError::Unauthorized` if bidder is not the caller
     /// - `PgError::InvalidAmount` if no locked funds to refund
+    /// - `PgError::InvalidListingKind` if listing is a sealed-bid auction (use `claim_sealed_bid_deposit`)
     #[allow(clippy::needless_pass_by_value)] // Anchor requires Context by value
     pub fn claim_bid_refund(ctx: Context<ClaimBidRefund>, listing_id: u64) -> Result<()> {
         // CRITICAL: Do ALL validation BEFORE entering reentrancy guard
@@ -3565,6 +6510,11 @@ pub mod phantom_paradox {
 
         require!(listing.game == game.key(), PgError::Unauthorized);
         require!(listing.listing_id == listing_id, PgError::Unauthorized);
+        // Sealed-bid deposits are released per commitment via claim_sealed_bid_deposit
+        require!(
+            listing.kind != ListingKind::SealedBid,
+            PgError::InvalidListingKind
+        );
         require!(
             bidder_ledger.authority == ctx.accounts.bidder.key(),
             PgError::Unauthorized
@@ -3843,8 +6793,8 @@ pub mod phantom_paradox {
 
     #[cfg(feature = "compression")]
     #[allow(clippy::too_many_arguments)]
-    pub fn buy_compressed_listing(
-        ctx: Context<BuyCompressedListing>,
+    pub fn buy_compressed_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyCompressedListing<'info>>,
         root: [u8; 32],
         data_hash: [u8; 32],
         _creator_hash: [u8; 32], // Used in Bubblegum, here we can use it for extra verification or just pass [0;32]
@@ -3895,6 +6845,15 @@ pub mod phantom_paradox {
         let leaf_hash = calculated_hash;
         verify_merkle_proof(&leaf_hash, &proof, &root)?;
 
+        // KYC tier limits apply to wallet-paid compressed buys too (trade size + daily volume)
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.buyer_ledger.record_kyc_trade(
+            &ctx.accounts.config,
+            game.kyc_required,
+            price,
+            now,
+        )?;
+
         // 3. Verify and Replace Leaf in Merkle Tree
         // Replaces 'Active' listing leaf with 'Sold' leaf (or just a nullified state).
         let new_leaf = [0u8; 32];
@@ -3986,10 +6945,34 @@ pub mod phantom_paradox {
         // This prevents edge cases where rounding or fee configuration could result in zero/negative amounts
         require!(seller_amount > 0, PgError::InvalidSellerAmount);
 
+        // Referral: the referrer's cut of the protocol fee is paid into the game vault and
+        // accrues as claimable credits on the referral account
+        let referral_fee = match ctx.accounts.referral.as_deref_mut() {
+            Some(referral) => {
+                referral.check(
+                    &game.key(),
+                    &ctx.accounts.buyer.key(),
+                    &ctx.accounts.seller.key(),
+                )?;
+                instructions::referral::accrue_referral(
+                    referral,
+                    config.referral_bps,
+                    protocol_fee,
+                    ctx.accounts.merkle_tree.key(), // Compressed listings have no account
+                    ctx.accounts.buyer.key(),
+                    price,
+                )?
+            }
+            None => 0,
+        };
+        let treasury_fee = protocol_fee
+            .checked_sub(referral_fee)
+            .ok_or(PgError::Overflow)?;
+
         // 5. Execute Transfers
 
         // A1. Protocol Fee
-        if protocol_fee > 0 {
+        if treasury_fee > 0 {
             let cpi_accounts = anchor_spl::token_interface::TransferChecked {
                 from: ctx.accounts.buyer_token_account.to_account_info(),
                 mint: ctx.accounts.currency_mint.to_account_info(),
@@ -4003,45 +6986,101 @@ pub mod phantom_paradox {
                 CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
             anchor_spl::token_interface::transfer_checked(
                 cpi_ctx,
-                protocol_fee,
+                treasury_fee,
                 ctx.accounts.currency_mint.decimals,
             )?;
         }
 
-        // A2. Game Fee
-        if game_fee > 0 {
+        // A1b. Referral Fee (vault-backed, claimed via claim_referral_fees)
+        if referral_fee > 0 {
+            let game_vault = ctx
+                .accounts
+                .game_vault
+                .as_ref()
+                .ok_or(PgError::Unauthorized)?;
             let cpi_accounts = anchor_spl::token_interface::TransferChecked {
                 from: ctx.accounts.buyer_token_account.to_account_info(),
                 mint: ctx.accounts.currency_mint.to_account_info(),
-                to: ctx.accounts.game_owner_token_account.to_account_info(),
+                to: game_vault.to_account_info(),
                 authority: ctx.accounts.buyer.to_account_info(),
             };
             let cpi_ctx =
                 CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
             anchor_spl::token_interface::transfer_checked(
                 cpi_ctx,
-                game_fee,
+                referral_fee,
                 ctx.accounts.currency_mint.decimals,
             )?;
         }
 
-        // A3. Creator Royalty
-        if royalty_fee > 0 {
+        // A2. Game Fee
+        if game_fee > 0 {
             let cpi_accounts = anchor_spl::token_interface::TransferChecked {
                 from: ctx.accounts.buyer_token_account.to_account_info(),
                 mint: ctx.accounts.currency_mint.to_account_info(),
-                to: ctx.accounts.creator_token_account.to_account_info(),
+                to: ctx.accounts.game_owner_token_account.to_account_info(),
                 authority: ctx.accounts.buyer.to_account_info(),
             };
             let cpi_ctx =
                 CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
             anchor_spl::token_interface::transfer_checked(
                 cpi_ctx,
-                royalty_fee,
+                game_fee,
                 ctx.accounts.currency_mint.decimals,
             )?;
         }
 
+        // A3. Creator Royalty (`creator` may be a RoyaltySplit shared by several recipients)
+        if royalty_fee > 0 {
+            if let Some(split) = &ctx.accounts.royalty_split {
+                require!(
+                    split.key() == creator && split.game == game.key(),
+                    PgError::RoyaltySplitMismatch
+                );
+                let shares = split.shares(royalty_fee)?;
+                instructions::royalty_split::transfer_royalty_split(
+                    split,
+                    &shares,
+                    &ctx.accounts.buyer_token_account.to_account_info(),
+                    &ctx.accounts.buyer.to_account_info(),
+                    &ctx.accounts.creator_token_account.to_account_info(),
+                    &ctx.accounts.currency_mint,
+                    &ctx.accounts.token_program,
+                    ctx.remaining_accounts,
+                )?;
+            } else {
+                // Without a split the royalty must land in `creator`'s own token account
+                // (a split PDA as `creator` can't pass this, so its royalty can't be diverted)
+                {
+                    let creator_account = ctx.accounts.creator_token_account.to_account_info();
+                    require!(
+                        creator_account.owner == &ctx.accounts.token_program.key(),
+                        PgError::Unauthorized
+                    );
+                    let data = creator_account.try_borrow_data()?;
+                    let token_account = TokenAccount::try_deserialize(&mut &data[..])?;
+                    require!(token_account.owner == creator, PgError::Unauthorized);
+                    require!(
+                        token_account.mint == ctx.accounts.currency_mint.key(),
+                        PgError::CurrencyMintMismatch
+                    );
+                }
+                let cpi_accounts = anchor_spl::token_interface::TransferChecked {
+                    from: ctx.accounts.buyer_token_account.to_account_info(),
+                    mint: ctx.accounts.currency_mint.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                let cpi_ctx =
+                    CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                anchor_spl::token_interface::transfer_checked(
+                    cpi_ctx,
+                    royalty_fee,
+                    ctx.accounts.currency_mint.decimals,
+                )?;
+            }
+        }
+
         // A4. Seller Payment
         if seller_amount > 0 {
             let transfer_payment_accounts = anchor_spl::token_interface::TransferChecked {
@@ -4515,8 +7554,8 @@ pub mod phantom_paradox {
 
     #[cfg(feature = "compression")]
     #[allow(clippy::too_many_arguments)]
-    pub fn verify_and_settle_auction(
-        ctx: Context<VerifyAndSettleAuction>,
+    pub fn verify_and_settle_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyAndSettleAuction<'info>>,
         auction_id: u64,
         batch_id: u64,
         leaf_index: u64,
@@ -4537,6 +7576,8 @@ pub mod phantom_paradox {
         // Settlement parameters
         winner: Pubkey,
         settlement_price: u64,
+        // Set for lazy-minted items: `asset_mint` is then `lazy_metadata.hash()`
+        lazy_metadata: Option<instructions::lazy_mint::LazyMintMetadata>,
     ) -> Result<()> {
         require!(
             !ctx.accounts.config.paused_settlements,
@@ -4714,20 +7755,39 @@ pub mod phantom_paradox {
             )?;
         }
 
+        // Creator royalty (`creator` may be a RoyaltySplit shared by several recipients)
         if royalty_fee > 0 {
-            let cpi_accounts = anchor_spl::token_interface::TransferChecked {
-                from: ctx.accounts.winner_token_account.to_account_info(),
-                mint: ctx.accounts.currency_mint.to_account_info(),
-                to: ctx.accounts.creator_token_account.to_account_info(),
-                authority: ctx.accounts.winner.to_account_info(),
-            };
-            let cpi_ctx =
-                CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-            anchor_spl::token_interface::transfer_checked(
-                cpi_ctx,
-                royalty_fee,
-                ctx.accounts.currency_mint.decimals,
-            )?;
+            if let Some(split) = &ctx.accounts.royalty_split {
+                require!(
+                    split.key() == creator && split.game == game.key(),
+                    PgError::RoyaltySplitMismatch
+                );
+                let shares = split.shares(royalty_fee)?;
+                instructions::royalty_split::transfer_royalty_split(
+                    split,
+                    &shares,
+                    &ctx.accounts.winner_token_account.to_account_info(),
+                    &ctx.accounts.winner.to_account_info(),
+                    &ctx.accounts.creator_token_account.to_account_info(),
+                    &ctx.accounts.currency_mint,
+                    &ctx.accounts.token_program,
+                    ctx.remaining_accounts,
+                )?;
+            } else {
+                let cpi_accounts = anchor_spl::token_interface::TransferChecked {
+                    from: ctx.accounts.winner_token_account.to_account_info(),
+                    mint: ctx.accounts.currency_mint.to_account_info(),
+                    to: ctx.accounts.creator_token_account.to_account_info(),
+                    authority: ctx.accounts.winner.to_account_info(),
+                };
+                let cpi_ctx =
+                    CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                anchor_spl::token_interface::transfer_checked(
+                    cpi_ctx,
+                    royalty_fee,
+                    ctx.accounts.currency_mint.decimals,
+                )?;
+            }
         }
 
         if seller_amount > 0 {
@@ -4746,21 +7806,85 @@ pub mod phantom_paradox {
             )?;
         }
 
-        // Transfer asset from seller to winner (delegated transfer via game PDA)
-        let transfer_item_accounts = anchor_spl::token_interface::TransferChecked {
-            from: ctx.accounts.seller_asset_account.to_account_info(),
-            mint: ctx.accounts.asset_mint.to_account_info(),
-            to: ctx.accounts.winner_asset_account.to_account_info(),
-            authority: game.to_account_info(),
-        };
-        let cpi_program_item = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx_item =
-            CpiContext::new_with_signer(cpi_program_item, transfer_item_accounts, signer_seeds);
-        anchor_spl::token_interface::transfer_checked(
-            cpi_ctx_item,
-            quantity,
-            ctx.accounts.asset_mint.decimals,
-        )?;
+        if let Some(metadata) = &lazy_metadata {
+            // Lazy-minted item: first trade mints it (Token-2022 + metadata) straight to the winner
+            let lazy_accounts = instructions::lazy_mint::LazyMintAccounts {
+                game: game.to_account_info(),
+                mint: ctx
+                    .accounts
+                    .lazy_mint
+                    .as_ref()
+                    .ok_or(PgError::LazyMintMismatch)?
+                    .to_account_info(),
+                recipient: ctx.accounts.winner.to_account_info(),
+                recipient_token_account: ctx
+                    .accounts
+                    .winner_lazy_asset_account
+                    .as_ref()
+                    .ok_or(PgError::LazyMintMismatch)?
+                    .to_account_info(),
+                payer: ctx.accounts.winner.to_account_info(),
+                token_program: ctx
+                    .accounts
+                    .token_2022_program
+                    .as_ref()
+                    .ok_or(PgError::LazyMintMismatch)?
+                    .to_account_info(),
+                associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            };
+            let collection = ctx
+                .accounts
+                .lazy_collection
+                .as_deref_mut()
+                .ok_or(PgError::LazyMintDisabled)?;
+            instructions::lazy_mint::mint_lazy_item(
+                &lazy_accounts,
+                collection,
+                game,
+                game.key(),
+                metadata,
+                asset_mint.to_bytes(),
+                quantity,
+                ctx.program_id,
+            )?;
+        } else {
+            let asset_mint_account = ctx
+                .accounts
+                .asset_mint
+                .as_ref()
+                .ok_or(PgError::ItemMintMismatch)?;
+            require!(
+                asset_mint_account.key() == asset_mint,
+                PgError::ItemMintMismatch
+            );
+            let seller_asset_account = ctx
+                .accounts
+                .seller_asset_account
+                .as_ref()
+                .ok_or(PgError::ItemMintMismatch)?;
+            let winner_asset_account = ctx
+                .accounts
+                .winner_asset_account
+                .as_ref()
+                .ok_or(PgError::ItemMintMismatch)?;
+
+            // Transfer asset from seller to winner (delegated transfer via game PDA)
+            let transfer_item_accounts = anchor_spl::token_interface::TransferChecked {
+                from: seller_asset_account.to_account_info(),
+                mint: asset_mint_account.to_account_info(),
+                to: winner_asset_account.to_account_info(),
+                authority: game.to_account_info(),
+            };
+            let cpi_program_item = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx_item =
+                CpiContext::new_with_signer(cpi_program_item, transfer_item_accounts, signer_seeds);
+            anchor_spl::token_interface::transfer_checked(
+                cpi_ctx_item,
+                quantity,
+                asset_mint_account.decimals,
+            )?;
+        }
 
         // Update leaf in tree to mark as settled (replace with settled leaf)
         let mut settled_leaf = auction_leaf.clone();
@@ -4971,10 +8095,16 @@ pub mod phantom_paradox {
         royalty_bps: u16,
         reason_code: u8,
     ) -> Result<()> {
-        // Verify admin authority
+        // Verify admin authority (or an explicitly granted listing moderator)
+        let admin_authority = ctx.accounts.admin_authority.key();
         require!(
-            ctx.accounts.admin_authority.key() == ctx.accounts.config.admin
-                || ctx.accounts.admin_authority.key() == ctx.accounts.config.governance,
+            admin_authority == ctx.accounts.config.admin
+                || admin_authority == ctx.accounts.config.governance
+                || ctx
+                    .accounts
+                    .roles
+                    .as_deref()
+                    .is_some_and(|r| r.has_role(&admin_authority, GameRole::ListingModerator)),
             PgError::AdminAuthorityRequired
         );
 
@@ -5235,6 +8365,9 @@ pub mod phantom_paradox {
     /// Royalty Distribution: Vec of (agent_id, trade_volume) pairs.
     /// The off-chain engine must calculate trade volume per agent and pass it here.
     /// We calculate the 0.3% fee and creator share (5 bps) on-chain.
+    ///
+    /// Batches settled through this v1 entrypoint charge no maker/taker fees; use
+    /// `settle_net_batch_v2` to pass per-wallet trade volumes.
     pub fn settle_net_batch(
         ctx: Context<SettleNetBatch>,
         batch_id: u64,
@@ -5243,6 +8376,33 @@ pub mod phantom_paradox {
         cash_deltas: Vec<NetDeltaData>,
         royalty_distribution: Vec<RoyaltyDistributionData>, // agent_id and trade_volume for fee calculation
         pi_fee: u64, // π-Standard protocol fee
+    ) -> Result<()> {
+        settle_net_batch_v2(
+            ctx,
+            batch_id,
+            batch_hash,
+            items,
+            cash_deltas,
+            royalty_distribution,
+            pi_fee,
+            Vec::new(),
+        )
+    }
+
+    /// Settle net batch with maker/taker fees (same as `settle_net_batch` otherwise).
+    ///
+    /// `trade_volumes` carries each wallet's maker and taker volume per game; the tiered
+    /// protocol fee is debited from that ledger on top of `cash_deltas` and booked to the
+    /// game's `protocol_fees_accumulated`. Emits `NetBatchFeesCharged` per entry.
+    pub fn settle_net_batch_v2(
+        ctx: Context<SettleNetBatch>,
+        batch_id: u64,
+        batch_hash: [u8; 32], // Hash of batch for auditability (computed off-chain)
+        items: Vec<SettledItemData>,
+        cash_deltas: Vec<NetDeltaData>,
+        royalty_distribution: Vec<RoyaltyDistributionData>, // agent_id and trade_volume for fee calculation
+        pi_fee: u64, // π-Standard protocol fee
+        trade_volumes: Vec<NetTradeVolumeData>, // Per-wallet maker/taker volume for tiered fees
     ) -> Result<()> {
         require!(
             !ctx.accounts.config.paused_settlements,
@@ -5348,7 +8508,8 @@ pub mod phantom_paradox {
         let estimated_accounts = 3 // Base accounts (config, authority, clock)
             + (items.len() * 5) // Items (listing, game, escrow, ata, mint per item)
             + cash_deltas.len() // Cash deltas (ledger per delta)
-            + royalty_distribution.len(); // Agent registries
+            + royalty_distribution.len() // Agent registries
+            + trade_volumes.len(); // Ledgers for tiered fees (games/ledgers usually overlap the above)
         require!(
             estimated_accounts <= MAX_ACCOUNTS_PER_TX,
             PgError::InvalidAmount
@@ -5653,6 +8814,19 @@ pub mod phantom_paradox {
             ledger.try_serialize(&mut writer)?;
         }
 
+        // ======================================================================
+        // MAKER / TAKER FEES (tiered by each wallet's 30-day volume)
+        // ======================================================================
+        // Charged on top of the netted deltas and booked per game (see charge_net_batch_fees)
+        instructions::fee_tiers::charge_net_batch_fees(
+            &ctx.accounts.config,
+            &trade_volumes,
+            ctx.remaining_accounts,
+            batch_id,
+            now,
+            ctx.program_id,
+        )?;
+
         // ======================================================================
         // AGENT MARKETPLACE FEE DISTRIBUTION - 0.3% Rule
         // ======================================================================
@@ -6503,6 +9677,159 @@ fn exit_execution_game(game: &mut GameConfig) {
     game.in_execution = false;
 }
 
+/// System instructions that create the PDA `account` with `space` bytes and `lamports` rent,
+/// owned by `owner`. A predictable PDA may already hold lamports (sent by anyone), which makes
+/// `create_account` fail, so a funded address is topped up, allocated and assigned instead,
+/// as Anchor's `init` does.
+fn create_pda_instructions(
+    payer: &Pubkey,
+    account: &Pubkey,
+    current_lamports: u64,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+) -> Vec<anchor_lang::solana_program::instruction::Instruction> {
+    use anchor_lang::solana_program::system_instruction;
+
+    if current_lamports == 0 {
+        return vec![system_instruction::create_account(
+            payer, account, lamports, space, owner,
+        )];
+    }
+    let mut instructions = Vec::with_capacity(3);
+    let top_up = lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        instructions.push(system_instruction::transfer(payer, account, top_up));
+    }
+    instructions.push(system_instruction::allocate(account, space));
+    instructions.push(system_instruction::assign(account, owner));
+    instructions
+}
+
+/// Create the PDA `account` (see `create_pda_instructions`), signing with its `seeds`.
+/// `payer` funds the rent.
+///
+/// # Errors
+/// - `PgError::Unauthorized` if the address is already owned by a program
+fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    require!(
+        account.owner == &anchor_lang::system_program::ID,
+        PgError::Unauthorized
+    );
+    let account_infos = [payer.clone(), account.clone(), system_program.clone()];
+    for instruction in create_pda_instructions(
+        payer.key,
+        account.key,
+        account.lamports(),
+        lamports,
+        space,
+        owner,
+    ) {
+        anchor_lang::solana_program::program::invoke_signed(
+            &instruction,
+            &account_infos,
+            &[seeds],
+        )?;
+    }
+    Ok(())
+}
+
+/// Vickrey clearing price: the runner-up bid, floored at `max(reserve_price, start_price)` and
+/// capped at the winning bid. A lone bidder pays the reserve or opening price, not their own bid.
+fn second_price(listing: &Listing) -> u64 {
+    let floor = listing.reserve_price.max(listing.start_price);
+    listing
+        .second_highest_bid
+        .max(floor)
+        .min(listing.highest_bid)
+}
+
+/// Fixed-point scale for exponential Dutch decay (1e12)
+const DUTCH_DECAY_SCALE: u128 = 1_000_000_000_000;
+
+/// Compute the current Dutch auction unit price from the listing's decay curve
+/// Price falls from `start_price` at `start_time` towards `dutch_min_price` at `end_time`
+/// and never drops below `dutch_min_price`
+fn current_dutch_price(listing: &Listing, now: i64) -> Result<u64> {
+    let start = listing.start_price;
+    let floor = listing.dutch_min_price;
+    if now <= listing.start_time || start <= floor {
+        return Ok(start);
+    }
+
+    let duration = listing
+        .end_time
+        .checked_sub(listing.start_time)
+        .ok_or(PgError::Overflow)?;
+    let elapsed = now
+        .checked_sub(listing.start_time)
+        .ok_or(PgError::Overflow)?
+        .min(duration);
+    let duration = u128::try_from(duration).map_err(|_| PgError::InvalidTime)?;
+    let elapsed = u128::try_from(elapsed).map_err(|_| PgError::InvalidTime)?;
+    let range = u128::from(start.checked_sub(floor).ok_or(PgError::Overflow)?);
+
+    let price = match listing.dutch_curve {
+        DutchCurve::Linear => {
+            let decay = range
+                .checked_mul(elapsed)
+                .ok_or(PgError::Overflow)?
+                .checked_div(duration)
+                .ok_or(PgError::InvalidTime)?;
+            u128::from(start).checked_sub(decay).ok_or(PgError::Overflow)?
+        }
+        DutchCurve::Stepped => {
+            let step = u128::from(listing.dutch_step_secs);
+            require!(step > 0, PgError::InvalidAmount);
+            let total_steps = duration / step;
+            if total_steps == 0 {
+                u128::from(start)
+            } else {
+                let decay = range
+                    .checked_mul(elapsed / step)
+                    .ok_or(PgError::Overflow)?
+                    .checked_div(total_steps)
+                    .ok_or(PgError::Overflow)?;
+                u128::from(start).checked_sub(decay).ok_or(PgError::Overflow)?
+            }
+        }
+        DutchCurve::Exponential => {
+            let step = u128::from(listing.dutch_step_secs);
+            require!(step > 0, PgError::InvalidAmount);
+            let keep_bps = u128::from(BPS_DENOM)
+                .checked_sub(u128::from(listing.dutch_decay_bps))
+                .ok_or(PgError::InvalidAmount)?;
+
+            // factor = (keep_bps / BPS_DENOM) ^ steps, via square-and-multiply in fixed point
+            let mut base = keep_bps * DUTCH_DECAY_SCALE / u128::from(BPS_DENOM);
+            let mut factor = DUTCH_DECAY_SCALE;
+            let mut steps = elapsed / step;
+            while steps > 0 && factor > 0 {
+                if steps & 1 == 1 {
+                    factor = factor * base / DUTCH_DECAY_SCALE;
+                }
+                base = base * base / DUTCH_DECAY_SCALE;
+                steps >>= 1;
+            }
+            u128::from(start)
+                .checked_mul(factor)
+                .ok_or(PgError::Overflow)?
+                / DUTCH_DECAY_SCALE
+        }
+    };
+
+    let price = u64::try_from(price).map_err(|_| PgError::Overflow)?;
+    Ok(price.max(floor))
+}
+
 /// Check if a feature flag is enabled by bit index (Legacy support)
 /// NOTE: Use is_feature_enabled(features, flag) for bitmask checks instead
 #[allow(dead_code)]
@@ -6603,6 +9930,7 @@ pub fn verify_meta_tx_signature(
 /// Verify Merkle proof for state-compressed account
 ///
 /// Verify a Merkle proof for compressed state settlement
+/// (also used for collection offer item-mint criteria roots)
 /// 
 /// This function verifies that a leaf node is part of a Merkle tree with the given root.
 /// The proof path is provided as an array of sibling hashes.
@@ -6615,7 +9943,6 @@ pub fn verify_meta_tx_signature(
 /// # Returns
 /// * `Ok(())` if the proof is valid
 /// * `Err(PgError::InvalidMerkleProof)` if the proof is invalid
-#[cfg(feature = "compression")]
 fn verify_merkle_proof(leaf: &[u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> Result<()> {
     let mut current_hash = *leaf;
     
@@ -6629,7 +9956,7 @@ fn verify_merkle_proof(leaf: &[u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) ->
         };
         
         // Use Solana's native SHA256 hashv (takes slice of slices)
-        let hash_result = anchor_lang::solana_program::hash::hashv(&[&combined]);
+        let hash_result = solana_program::hash::hashv(&[&combined]);
         current_hash = hash_result.to_bytes();
     }
 
@@ -6722,6 +10049,13 @@ pub struct UpdateGame<'info> {
 
     #[account(mut)]
     pub caller: Signer<'info>,
+
+    /// Game roles (required when the caller acts through a `Pauser` / `ConfigManager` role)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
 }
 
 #[derive(Accounts)]
@@ -6729,11 +10063,11 @@ pub struct SetPlayerKyc<'info> {
     #[account(
         mut,
         seeds = [CONFIG_SEED],
-        bump,
-        has_one = governance @ PgError::Unauthorized
+        bump
     )]
-    pub config: Account<'info, GlobalConfig>,
-    pub governance: Signer<'info>,
+    pub config: Box<Account<'info, GlobalConfig>>,
+    /// Governance, an approved KYC provider or a game `KycOperator` (checked in handler)
+    pub attestor: Signer<'info>,
 
     #[account(
         mut,
@@ -6742,6 +10076,13 @@ pub struct SetPlayerKyc<'info> {
     )]
     pub game: Account<'info, GameConfig>,
 
+    /// Game roles (required when the attestor is a `KycOperator`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
     /// CHECK: Player whose KYC status is being set (must sign)
     #[account(mut)]
     pub player_signer: Signer<'info>,
@@ -6859,6 +10200,9 @@ pub struct WithdrawCredits<'info> {
     #[account(mut)]
     pub player_signer: Signer<'info>,
 
+    /// Guardian co-signature for spends above the ledger's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -6920,6 +10264,12 @@ pub struct CreateListing<'info> {
     #[account(mut)]
     pub seller_signer: Signer<'info>,
 
+    /// Optional multi-recipient royalty split (recipient 0 must be `royalty_recipient`)
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    /// CHECK: Metaplex metadata PDA of `item_mint`; address, owner and layout verified in handler
+    pub item_metadata: Option<UncheckedAccount<'info>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -6946,6 +10296,67 @@ pub struct ActivateListing<'info> {
 
     #[account(mut)]
     pub caller: Signer<'info>,
+
+    /// Game roles (required when the caller acts as a `ListingModerator`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [LISTING_SEED, game.key().as_ref(), &listing.listing_id.to_le_bytes()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+
+    #[account(address = listing.item_mint)]
+    pub item_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = item_mint,
+        associated_token::authority = seller_signer
+    )]
+    pub seller_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_SEED, listing.key().as_ref()],
+        bump,
+        token::mint = item_mint,
+        token::authority = game
+    )]
+    pub escrow_item_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Seller's ledger, for KYC tier limits on the updated terms
+    #[account(
+        seeds = [LEDGER_SEED, game.key().as_ref(), seller_signer.key().as_ref()],
+        bump
+    )]
+    pub seller_ledger: Box<Account<'info, PlayerLedger>>,
+
+    pub seller_signer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 #[derive(Accounts)]
@@ -6996,6 +10407,13 @@ pub struct CancelListing<'info> {
     #[account(mut)]
     pub caller: Signer<'info>,
 
+    /// Game roles (required when the caller acts as a `ListingModerator`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -7025,7 +10443,7 @@ pub struct PlaceBid<'info> {
         seeds = [LEDGER_SEED, game.key().as_ref(), bidder.key().as_ref()],
         bump
     )]
-    pub bidder_ledger: Account<'info, PlayerLedger>,
+    pub bidder_ledger: Box<Account<'info, PlayerLedger>>,
 
     /// CHECK: Previous highest bidder's ledger (for automatic refund)
     /// If listing has a previous highest bidder, this must match that bidder
@@ -7035,7 +10453,7 @@ pub struct PlaceBid<'info> {
         seeds = [LEDGER_SEED, game.key().as_ref(), previous_bidder.key().as_ref()],
         bump
     )]
-    pub previous_bidder_ledger: Account<'info, PlayerLedger>,
+    pub previous_bidder_ledger: Box<Account<'info, PlayerLedger>>,
 
     /// CHECK: Previous highest bidder (for validation)
     /// If listing has a previous highest bidder, this must match
@@ -7046,6 +10464,9 @@ pub struct PlaceBid<'info> {
     #[account(mut)]
     pub bidder: Signer<'info>,
 
+    /// Guardian co-signature for spends above the ledger's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -7150,9 +10571,53 @@ pub struct BuyFixed<'info> {
     )]
     pub royalty_recipient_ledger: Box<Account<'info, PlayerLedger>>,
 
+    /// Required when `listing.royalty_split` is set; other recipients' ledgers go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    /// Optional referrer (front-end) earning `config.referral_bps` of the protocol fee
+    #[account(mut)]
+    pub referral: Option<Box<Account<'info, ReferralAccount>>>,
+
+    /// CHECK: Optional pdox_stake `AgentAccount` PDA of the seller (maker fee discount); owner,
+    /// address and layout verified in handler
+    pub seller_stake: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional pdox_stake `AgentAccount` PDA of the buyer (taker fee discount); owner,
+    /// address and layout verified in handler
+    pub buyer_stake: Option<UncheckedAccount<'info>>,
+
+    /// Buyer's PDOX token account; passing it pays the protocol fee in PDOX
+    #[account(mut)]
+    pub buyer_pdox_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// PDOX mint (writable for the `Burn` sink)
+    #[account(mut)]
+    pub pdox_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    /// PDOX price source of the game currency
+    #[account(
+        seeds = [PDOX_PRICE_SEED, game.currency_mint.as_ref()],
+        bump = pdox_price_source.bump
+    )]
+    pub pdox_price_source: Option<Box<Account<'info, PdoxPriceSource>>>,
+
+    /// Pool reserves named by `pdox_price_source`
+    pub pdox_reserve: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    pub pdox_quote_reserve: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// `config.pdox_fee_sink_account` (omit when the sink is `Burn`)
+    #[account(mut)]
+    pub pdox_fee_sink: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Token program of the PDOX mint (Token-2022)
+    pub pdox_token_program: Option<Interface<'info, TokenInterface>>,
+
     #[account(mut)]
     pub buyer_signer: Signer<'info>,
 
+    /// Guardian co-signature for spends above the ledger's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -7222,9 +10687,27 @@ pub struct FinalizeAuction<'info> {
     )]
     pub royalty_recipient_ledger: Box<Account<'info, PlayerLedger>>,
 
+    /// Required when `listing.royalty_split` is set; other recipients' ledgers go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    /// Optional referrer (front-end) earning `config.referral_bps` of the protocol fee
+    #[account(mut)]
+    pub referral: Option<Box<Account<'info, ReferralAccount>>>,
+
+    /// CHECK: Optional pdox_stake `AgentAccount` PDA of the seller (maker fee discount); owner,
+    /// address and layout verified in handler
+    pub seller_stake: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional pdox_stake `AgentAccount` PDA of the winner (taker fee discount); owner,
+    /// address and layout verified in handler
+    pub winner_stake: Option<UncheckedAccount<'info>>,
+
     #[account(mut)]
     pub winner_signer: Signer<'info>,
 
+    /// Guardian co-signature for spends above the winner's `guardian_threshold`
+    pub guardian: Option<Signer<'info>>,
+
     #[account(mut)]
     pub server_signer: Signer<'info>,
 
@@ -7304,12 +10787,19 @@ pub struct WithdrawGameFees<'info> {
     #[account(
         mut,
         seeds = [GAME_SEED, &game.game_id.to_le_bytes()],
-        bump = game.bump,
-        has_one = owner @ PgError::Unauthorized
+        bump = game.bump
     )]
     pub game: Account<'info, GameConfig>,
+    /// Game owner or a `FeeWithdrawer` (checked in handler)
     pub owner: Signer<'info>,
 
+    /// Game roles (required when the owner signer is a `FeeWithdrawer`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
     #[account(
         mut,
         seeds = [VAULT_SEED, game.key().as_ref()],
@@ -7362,8 +10852,15 @@ pub struct CloseListing<'info> {
     #[account(mut)]
     pub recipient: UncheckedAccount<'info>,
 
-    /// CHECK: Caller must be seller, game owner, or governance
+    /// CHECK: Caller must be seller, game owner / listing moderator, or governance
     pub caller: Signer<'info>,
+
+    /// Game roles (required when the caller acts as a `ListingModerator`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
 }
 
 #[derive(Accounts)]
@@ -7491,6 +10988,14 @@ pub struct BuyCompressedListing<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
 
+    /// Buyer's ledger, for KYC tier limits (payment comes from `buyer_token_account`)
+    #[account(
+        mut,
+        seeds = [LEDGER_SEED, game.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_ledger: Box<Account<'info, PlayerLedger>>,
+
     /// CHECK: Seller address verified via data hash reconstruction
     #[account(mut)]
     pub seller: UncheckedAccount<'info>,
@@ -7552,10 +11057,29 @@ pub struct BuyCompressedListing<'info> {
     )]
     pub game_owner_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// CHECK: Creator wallet for royalties (verified via data hash reconstruction)
+    /// CHECK: Creator token account for royalties; without `royalty_split` its owner must be
+    /// `creator` (verified via data hash reconstruction), with it the split's recipient 0
     #[account(mut)]
     pub creator_token_account: UncheckedAccount<'info>,
 
+    /// Set when `creator` is a royalty split; `creator_token_account` then belongs to
+    /// recipient 0 and the other recipients' token accounts go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
+    /// Optional referrer (front-end) earning `config.referral_bps` of the protocol fee
+    #[account(mut)]
+    pub referral: Option<Box<Account<'info, ReferralAccount>>>,
+
+    /// Required with `referral`: the referral fee is held in the game vault until claimed
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, game.key().as_ref()],
+        bump,
+        token::mint = currency_mint,
+        token::authority = game
+    )]
+    pub game_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
     /// CHECK: Secure via CPI to spl-account-compression.
     #[account(mut)]
     pub merkle_tree: UncheckedAccount<'info>,
@@ -7865,7 +11389,8 @@ pub struct VerifyAndSettleAuction<'info> {
     pub seller: UncheckedAccount<'info>,
 
     pub currency_mint: InterfaceAccount<'info, Mint>,
-    pub asset_mint: InterfaceAccount<'info, Mint>,
+    /// Omitted for lazy-minted items (the mint doesn't exist until settlement)
+    pub asset_mint: Option<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -7887,7 +11412,7 @@ pub struct VerifyAndSettleAuction<'info> {
         associated_token::mint = asset_mint,
         associated_token::authority = seller
     )]
-    pub seller_asset_account: InterfaceAccount<'info, TokenAccount>,
+    pub seller_asset_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
@@ -7895,7 +11420,25 @@ pub struct VerifyAndSettleAuction<'info> {
         associated_token::mint = asset_mint,
         associated_token::authority = winner
     )]
-    pub winner_asset_account: InterfaceAccount<'info, TokenAccount>,
+    pub winner_asset_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Lazy-minted items only: the game's lazy-mint collection
+    #[account(
+        mut,
+        seeds = [LAZY_MINT_SEED, game.key().as_ref()],
+        bump = lazy_collection.bump
+    )]
+    pub lazy_collection: Option<Box<Account<'info, LazyMintCollection>>>,
+
+    /// CHECK: Lazy-minted items only: `[LAZY_ITEM_SEED, game, metadata_hash]` mint PDA, created on settlement
+    #[account(mut)]
+    pub lazy_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Lazy-minted items only: winner's Token-2022 ATA for `lazy_mint`, created on settlement
+    #[account(mut)]
+    pub winner_lazy_asset_account: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, anchor_spl::token_2022::Token2022>>,
 
     /// CHECK: Protocol treasury
     #[account(mut, constraint = protocol_treasury.key() == config.protocol_treasury)]
@@ -7925,6 +11468,10 @@ pub struct VerifyAndSettleAuction<'info> {
     #[account(mut)]
     pub creator_token_account: UncheckedAccount<'info>,
 
+    /// Set when `creator` is a royalty split; `creator_token_account` then belongs to
+    /// recipient 0 and the other recipients' token accounts go in `remaining_accounts`
+    pub royalty_split: Option<Box<Account<'info, RoyaltySplit>>>,
+
     /// CHECK: Auction tree config
     #[account(
         seeds = [AUCTION_TREE_SEED, game.key().as_ref()],
@@ -8031,9 +11578,16 @@ pub struct AdminCancelListing<'info> {
     )]
     pub game: Account<'info, GameConfig>,
 
-    /// CHECK: Admin authority (admin or governance)
+    /// CHECK: Admin authority (admin, governance or listing moderator)
     pub admin_authority: Signer<'info>,
 
+    /// Game roles (required when the authority acts as a `ListingModerator`)
+    #[account(
+        seeds = [GAME_ROLES_SEED, game.key().as_ref()],
+        bump = roles.bump
+    )]
+    pub roles: Option<Box<Account<'info, GameRoles>>>,
+
     /// CHECK: Seller (verified via leaf data)
     #[account(mut)]
     pub seller: UncheckedAccount<'info>,
@@ -8376,3 +11930,638 @@ pub struct CancelIntegrationUpdate<'info> {
     )]
     pub config: Account<'info, IntegrationConfig>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All-zero account (every field at its default), as `init` leaves it before the handler runs
+    pub(crate) fn zeroed<T: AccountDeserialize>() -> T {
+        let data = vec![0u8; 8 + size_of::<T>()];
+        T::try_deserialize_unchecked(&mut &data[..]).unwrap()
+    }
+
+    /// Account living for the whole test, as `remaining_accounts` entries do
+    pub(crate) fn leaked_account(
+        key: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+        owner: Pubkey,
+    ) -> AccountInfo<'static> {
+        AccountInfo::new(
+            Box::leak(Box::new(key)),
+            false,
+            true,
+            Box::leak(Box::new(lamports)),
+            Box::leak(data.into_boxed_slice()),
+            Box::leak(Box::new(owner)),
+            false,
+            0,
+        )
+    }
+
+    fn dutch_listing(curve: DutchCurve) -> Listing {
+        let mut listing = zeroed::<Listing>();
+        listing.kind = ListingKind::DutchAuction;
+        listing.start_price = 1_000;
+        listing.dutch_min_price = 200;
+        listing.start_time = 1_000;
+        listing.end_time = 1_100;
+        listing.dutch_curve = curve;
+        listing
+    }
+
+    #[test]
+    fn dutch_linear_decays_to_floor() {
+        let listing = dutch_listing(DutchCurve::Linear);
+        assert_eq!(current_dutch_price(&listing, 900).unwrap(), 1_000);
+        assert_eq!(current_dutch_price(&listing, 1_000).unwrap(), 1_000);
+        assert_eq!(current_dutch_price(&listing, 1_050).unwrap(), 600);
+        assert_eq!(current_dutch_price(&listing, 1_100).unwrap(), 200);
+        assert_eq!(current_dutch_price(&listing, 5_000).unwrap(), 200);
+    }
+
+    #[test]
+    fn dutch_stepped_drops_once_per_step() {
+        let mut listing = dutch_listing(DutchCurve::Stepped);
+        listing.dutch_step_secs = 25;
+        assert_eq!(current_dutch_price(&listing, 1_024).unwrap(), 1_000);
+        assert_eq!(current_dutch_price(&listing, 1_025).unwrap(), 800);
+        assert_eq!(current_dutch_price(&listing, 1_060).unwrap(), 600);
+        assert_eq!(current_dutch_price(&listing, 1_100).unwrap(), 200);
+
+        listing.dutch_step_secs = 0;
+        assert_eq!(
+            current_dutch_price(&listing, 1_050),
+            Err(error!(PgError::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn dutch_exponential_compounds_and_respects_floor() {
+        let mut listing = dutch_listing(DutchCurve::Exponential);
+        listing.dutch_step_secs = 10;
+        listing.dutch_decay_bps = 1_000;
+        assert_eq!(current_dutch_price(&listing, 1_009).unwrap(), 1_000);
+        assert_eq!(current_dutch_price(&listing, 1_010).unwrap(), 900);
+        assert_eq!(current_dutch_price(&listing, 1_020).unwrap(), 810);
+        // 0.9^10 * 1000 = 348
+        assert_eq!(current_dutch_price(&listing, 1_100).unwrap(), 348);
+
+        listing.dutch_decay_bps = 9_000;
+        assert_eq!(current_dutch_price(&listing, 1_030).unwrap(), 200);
+    }
+
+    #[test]
+    fn soft_close_validate_bounds() {
+        assert!(SoftCloseConfig::default().validate().is_ok());
+        let ok = SoftCloseConfig {
+            window_secs: 300,
+            extension_secs: 120,
+            max_extension_secs: 600,
+        };
+        assert!(ok.validate().is_ok());
+        for bad in [
+            SoftCloseConfig {
+                window_secs: MAX_SOFT_CLOSE_SECS + 1,
+                ..ok
+            },
+            SoftCloseConfig {
+                extension_secs: 0,
+                ..ok
+            },
+            SoftCloseConfig {
+                extension_secs: MAX_SOFT_CLOSE_SECS + 1,
+                ..ok
+            },
+            SoftCloseConfig {
+                max_extension_secs: MAX_SOFT_CLOSE_TOTAL_EXTENSION_SECS + 1,
+                ..ok
+            },
+        ] {
+            assert_eq!(bad.validate(), Err(error!(PgError::InvalidTime)));
+        }
+    }
+
+    #[test]
+    fn soft_close_extension_only_inside_window_and_capped() {
+        let sc = SoftCloseConfig {
+            window_secs: 300,
+            extension_secs: 120,
+            max_extension_secs: 300,
+        };
+        assert_eq!(sc.extension_for(301, 0), 0);
+        assert_eq!(sc.extension_for(300, 0), 120);
+        assert_eq!(sc.extension_for(0, 120), 120);
+        // Only 60s of the 300s budget left
+        assert_eq!(sc.extension_for(10, 240), 60);
+        assert_eq!(sc.extension_for(10, 300), 0);
+        assert_eq!(SoftCloseConfig::default().extension_for(0, 0), 0);
+    }
+
+    #[test]
+    fn second_price_uses_runner_up_within_floor_and_winning_bid() {
+        let mut listing = zeroed::<Listing>();
+        listing.start_price = 100;
+        listing.highest_bid = 500;
+        listing.second_highest_bid = 300;
+        assert_eq!(second_price(&listing), 300);
+
+        // Lone bidder pays the opening price, or the reserve when it is higher
+        listing.second_highest_bid = 0;
+        assert_eq!(second_price(&listing), 100);
+        listing.reserve_price = 400;
+        assert_eq!(second_price(&listing), 400);
+
+        // Never above the winner's own bid
+        listing.reserve_price = 600;
+        assert_eq!(second_price(&listing), 500);
+    }
+
+    #[test]
+    fn create_pda_instructions_adopt_a_pre_funded_address() {
+        use anchor_lang::solana_program::system_instruction;
+
+        let (payer, pda, owner) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        assert_eq!(
+            create_pda_instructions(&payer, &pda, 0, 2_000, 165, &owner),
+            vec![system_instruction::create_account(
+                &payer, &pda, 2_000, 165, &owner
+            )]
+        );
+        // Someone sent 1 lamport to the PDA first: top up the rest instead of create_account
+        assert_eq!(
+            create_pda_instructions(&payer, &pda, 1, 2_000, 165, &owner),
+            vec![
+                system_instruction::transfer(&payer, &pda, 1_999),
+                system_instruction::allocate(&pda, 165),
+                system_instruction::assign(&pda, &owner),
+            ]
+        );
+        // Already rent-exempt: no transfer needed
+        assert_eq!(
+            create_pda_instructions(&payer, &pda, 5_000, 2_000, 165, &owner),
+            vec![
+                system_instruction::allocate(&pda, 165),
+                system_instruction::assign(&pda, &owner),
+            ]
+        );
+    }
+
+    #[test]
+    fn create_pda_account_rejects_program_owned_address() {
+        let (key, payer_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let owner = crate::ID;
+        let system = anchor_lang::system_program::ID;
+        let (mut lamports, mut payer_lamports, mut system_lamports) = (1_000u64, 0u64, 0u64);
+        let (mut data, mut payer_data, mut system_data) = (vec![0u8; 8], vec![], vec![]);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut payer_data,
+            &system,
+            false,
+            0,
+        );
+        let system_program = AccountInfo::new(
+            &system,
+            false,
+            false,
+            &mut system_lamports,
+            &mut system_data,
+            &system,
+            true,
+            0,
+        );
+        assert_eq!(
+            create_pda_account(&account, &payer, &system_program, 2_000, 8, &owner, &[]),
+            Err(error!(PgError::Unauthorized))
+        );
+    }
+
+    fn book_order(owner: Pubkey, order_id: u64, price: u64) -> BookOrder {
+        BookOrder {
+            owner,
+            order_id,
+            price,
+            quantity: 1,
+            placed_at: 0,
+        }
+    }
+
+    #[test]
+    fn order_book_ranks_by_price_then_time() {
+        let owner = Pubkey::new_unique();
+        let mut book = zeroed::<OrderBook>();
+        assert_eq!(book.best(OrderSide::Bid), None);
+
+        book.bids[1] = book_order(owner, 1, 100);
+        book.bids[3] = book_order(owner, 2, 120);
+        book.bids[4] = book_order(owner, 3, 120);
+        book.bids[6] = book_order(owner, 4, 90);
+        assert_eq!(book.best(OrderSide::Bid), Some(3));
+        assert_eq!(book.worst(OrderSide::Bid), Some(6));
+
+        book.asks[0] = book_order(owner, 5, 150);
+        book.asks[2] = book_order(owner, 6, 130);
+        book.asks[5] = book_order(owner, 7, 150);
+        assert_eq!(book.best(OrderSide::Ask), Some(2));
+        assert_eq!(book.worst(OrderSide::Ask), Some(5));
+
+        // Filled slots are ignored
+        book.asks[2].quantity = 0;
+        assert_eq!(book.best(OrderSide::Ask), Some(0));
+    }
+
+    #[test]
+    fn order_book_detects_self_crossing() {
+        let (owner, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut book = zeroed::<OrderBook>();
+        book.asks[0] = book_order(owner, 1, 100);
+        book.bids[0] = book_order(other, 2, 80);
+
+        assert!(book.crosses_own(OrderSide::Bid, &owner, 100));
+        assert!(!book.crosses_own(OrderSide::Bid, &owner, 99));
+        assert!(!book.crosses_own(OrderSide::Ask, &owner, 50));
+        assert!(book.crosses_own(OrderSide::Ask, &other, 80));
+        assert!(!book.crosses_own(OrderSide::Bid, &other, 500));
+    }
+
+    #[test]
+    fn updated_prices_keep_buy_now_and_reserve_above_start() {
+        let mut listing = zeroed::<Listing>();
+        listing.start_price = 100;
+        listing.buy_now_price = 500;
+        listing.reserve_price = 200;
+
+        assert_eq!(
+            listing.updated_prices(None, None, None).unwrap(),
+            (100, 500, 200)
+        );
+        assert_eq!(
+            listing.updated_prices(Some(150), Some(0), None).unwrap(),
+            (150, 0, 200)
+        );
+        for (start, buy_now, reserve) in [
+            (Some(0), None, None),
+            (Some(300), None, None), // Reserve would sit below the new start
+            (None, Some(99), None),
+            (None, None, Some(50)),
+        ] {
+            assert_eq!(
+                listing.updated_prices(start, buy_now, reserve),
+                Err(error!(PgError::InvalidAmount))
+            );
+        }
+
+        // A Dutch auction can't start below its floor
+        listing.kind = ListingKind::DutchAuction;
+        listing.dutch_min_price = 80;
+        listing.reserve_price = 0;
+        assert!(listing.updated_prices(Some(80), None, None).is_ok());
+        assert_eq!(
+            listing.updated_prices(Some(79), None, None),
+            Err(error!(PgError::InvalidAmount))
+        );
+    }
+
+    #[test]
+    fn royalty_split_shares_roll_dust_into_recipient_zero() {
+        let mut split = zeroed::<RoyaltySplit>();
+        split.recipient_count = 3;
+        split.weights[..3].copy_from_slice(&[5_000, 3_333, 1_667]);
+
+        let shares = split.shares(1_000).unwrap();
+        assert_eq!(shares[..3], [501, 333, 166]);
+        assert_eq!(shares[..3].iter().sum::<u64>(), 1_000);
+        // 1 unit: both secondary shares round to 0, recipient 0 takes it all
+        assert_eq!(split.shares(1).unwrap()[..3], [1, 0, 0]);
+        // Unused slots never receive anything
+        assert!(shares[3..].iter().all(|s| *s == 0));
+        assert_eq!(split.shares(0).unwrap()[..3], [0, 0, 0]);
+    }
+
+    #[test]
+    fn check_buyer_allows_named_buyer_or_allowlist_proof() {
+        let (named, listed, outsider) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut listing = zeroed::<Listing>();
+        assert!(!listing.is_private());
+        assert!(listing.check_buyer(&outsider, &[]).is_ok());
+
+        listing.allowed_buyer = named;
+        assert!(listing.is_private());
+        assert!(listing.check_buyer(&named, &[]).is_ok());
+        assert_eq!(
+            listing.check_buyer(&outsider, &[]),
+            Err(error!(PgError::BuyerNotAllowed))
+        );
+
+        // Two-wallet allowlist: each leaf proves with the other as sibling
+        let (a, b) = (
+            Listing::allowlist_leaf(&listed),
+            Listing::allowlist_leaf(&named),
+        );
+        let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+        listing.allowlist_root = solana_program::hash::hashv(&[&[lo, hi].concat()]).to_bytes();
+        assert!(listing.check_buyer(&listed, &[b]).is_ok());
+        assert!(listing.check_buyer(&named, &[]).is_ok());
+        assert_eq!(
+            listing.check_buyer(&outsider, &[b]),
+            Err(error!(PgError::InvalidMerkleProof))
+        );
+    }
+
+    #[test]
+    fn roll_buckets_zeroes_only_stale_buckets() {
+        let mut buckets = [1u64, 2, 3, 4];
+        let mut last_slot = 3;
+        roll_buckets(&mut buckets, &mut last_slot, 5);
+        assert_eq!(buckets, [0, 0, 3, 4]);
+        assert_eq!(last_slot, 5);
+
+        // Older slots never rewind the ring
+        roll_buckets(&mut buckets, &mut last_slot, 4);
+        assert_eq!(buckets, [0, 0, 3, 4]);
+        assert_eq!(last_slot, 5);
+
+        // A full window later everything is stale
+        roll_buckets(&mut buckets, &mut last_slot, 100);
+        assert_eq!(buckets, [0; 4]);
+    }
+
+    #[test]
+    fn record_spend_enforces_rolling_caps() {
+        let mut limits = SpendingLimits {
+            daily_cap: 100,
+            weekly_cap: 250,
+            ..SpendingLimits::default()
+        };
+        let day = SPEND_WEEK_BUCKET_SECS;
+        assert!(limits.record_spend(60, day, None).is_ok());
+        assert_eq!(
+            limits.record_spend(50, day + 60, None),
+            Err(error!(PgError::SpendingCapExceeded))
+        );
+        assert!(limits.record_spend(40, day + 60, None).is_ok());
+
+        // The 24h window has rolled, the weekly total has not
+        assert!(limits.record_spend(100, 2 * day + 60, None).is_ok());
+        assert_eq!(
+            limits.record_spend(60, 3 * day + 60, None),
+            Err(error!(PgError::SpendingCapExceeded))
+        );
+        assert!(limits.record_spend(50, 3 * day + 60, None).is_ok());
+
+        // A week after the first spends they no longer count
+        assert!(limits.record_spend(100, 8 * day + 60, None).is_ok());
+    }
+
+    #[test]
+    fn record_spend_requires_guardian_above_threshold() {
+        let guardian = Pubkey::new_unique();
+        let mut limits = SpendingLimits {
+            guardian,
+            guardian_threshold: 50,
+            ..SpendingLimits::default()
+        };
+        assert!(limits.record_spend(50, 0, None).is_ok());
+        assert_eq!(
+            limits.record_spend(51, 0, None),
+            Err(error!(PgError::GuardianApprovalRequired))
+        );
+        assert_eq!(
+            limits.record_spend(51, 0, Some(Pubkey::new_unique())),
+            Err(error!(PgError::GuardianApprovalRequired))
+        );
+        assert!(limits.record_spend(51, 0, Some(guardian)).is_ok());
+    }
+
+    fn kyc_config() -> GlobalConfig {
+        let mut cfg = zeroed::<GlobalConfig>();
+        cfg.kyc_tier_limits[KycTier::None as usize] = KycTierLimits {
+            max_trade_size: 100,
+            daily_volume: 150,
+        };
+        cfg.kyc_tier_limits[KycTier::Basic as usize] = KycTierLimits {
+            max_trade_size: 1_000,
+            daily_volume: 0,
+        };
+        cfg
+    }
+
+    fn verified_ledger(tier: KycTier, expires_at: i64) -> PlayerLedger {
+        let mut ledger = zeroed::<PlayerLedger>();
+        ledger.kyc_verified = true;
+        ledger.kyc_tier = tier;
+        ledger.kyc_expires_at = expires_at;
+        ledger
+    }
+
+    #[test]
+    fn check_kyc_trade_applies_tier_limits_and_expiry() {
+        let cfg = kyc_config();
+        let unverified = zeroed::<PlayerLedger>();
+        assert!(unverified.check_kyc_trade(&cfg, false, 100, 0).is_ok());
+        assert_eq!(
+            unverified.check_kyc_trade(&cfg, false, 101, 0),
+            Err(error!(PgError::KycTradeSizeExceeded))
+        );
+        assert_eq!(
+            unverified.check_kyc_trade(&cfg, true, 1, 0),
+            Err(error!(PgError::KycRequired))
+        );
+
+        // Legacy verified ledgers (tier None) count as Basic
+        let legacy = verified_ledger(KycTier::None, 0);
+        assert!(legacy.check_kyc_trade(&cfg, true, 1_000, 0).is_ok());
+
+        let basic = verified_ledger(KycTier::Basic, 500);
+        assert!(basic.check_kyc_trade(&cfg, true, 1_000, 499).is_ok());
+        assert_eq!(
+            basic.check_kyc_trade(&cfg, true, 1, 500),
+            Err(error!(PgError::KycRequired))
+        );
+        assert_eq!(
+            basic.check_kyc_trade(&cfg, false, 1_000, 500),
+            Err(error!(PgError::KycTradeSizeExceeded))
+        );
+    }
+
+    #[test]
+    fn record_kyc_trade_resets_daily_volume_each_day() {
+        let cfg = kyc_config();
+        let mut ledger = zeroed::<PlayerLedger>();
+        assert!(ledger.record_kyc_trade(&cfg, false, 100, 10).is_ok());
+        assert_eq!(
+            ledger.record_kyc_trade(&cfg, false, 51, 20),
+            Err(error!(PgError::KycDailyVolumeExceeded))
+        );
+        assert!(ledger.record_kyc_trade(&cfg, false, 50, 20).is_ok());
+        assert!(ledger
+            .record_kyc_trade(&cfg, false, 100, KYC_VOLUME_DAY_SECS)
+            .is_ok());
+        assert_eq!(ledger.kyc_day_volume, 100);
+    }
+
+    #[test]
+    fn record_trade_limits_records_nothing_when_either_limit_fails() {
+        let cfg = kyc_config();
+        let mut ledger = zeroed::<PlayerLedger>();
+        ledger.limits.daily_cap = 120;
+
+        // KYC trade size fails after the spend would have fit
+        assert_eq!(
+            ledger.record_trade_limits(&cfg, false, 101, 10, None),
+            Err(error!(PgError::KycTradeSizeExceeded))
+        );
+        assert_eq!(ledger.limits.day_buckets, [0; 6]);
+        assert!(ledger
+            .record_trade_limits(&cfg, false, 100, 10, None)
+            .is_ok());
+
+        // Spending cap fails before the KYC volume would have fit
+        assert_eq!(
+            ledger.record_trade_limits(&cfg, false, 30, 20, None),
+            Err(error!(PgError::SpendingCapExceeded))
+        );
+        assert_eq!(ledger.kyc_day_volume, 100);
+        assert!(ledger
+            .record_trade_limits(&cfg, false, 20, 20, None)
+            .is_ok());
+        assert_eq!(ledger.kyc_day_volume, 120);
+    }
+
+    #[test]
+    fn jurisdiction_check_matches_attested_country() {
+        let mut policy = JurisdictionPolicy::default();
+        let mut ledger = verified_ledger(KycTier::Full, 1_000);
+        ledger.kyc_country = *b"DE";
+
+        // Disabled gating never blocks, even without KYC
+        assert!(policy.check(&zeroed::<PlayerLedger>(), 0).is_ok());
+
+        policy.countries[3] = *b"DE";
+        policy.mode = JurisdictionMode::AllowList;
+        assert!(policy.check(&ledger, 0).is_ok());
+        policy.mode = JurisdictionMode::DenyList;
+        assert_eq!(
+            policy.check(&ledger, 0),
+            Err(error!(PgError::JurisdictionBlocked))
+        );
+
+        ledger.kyc_country = *b"FR";
+        assert!(policy.check(&ledger, 0).is_ok());
+        // Expired attestation or no attested country
+        assert_eq!(
+            policy.check(&ledger, 1_000),
+            Err(error!(PgError::JurisdictionBlocked))
+        );
+        ledger.kyc_country = [0; 2];
+        assert_eq!(
+            policy.check(&ledger, 0),
+            Err(error!(PgError::JurisdictionBlocked))
+        );
+    }
+
+    #[test]
+    fn fee_rates_pick_the_highest_tier_reached_per_side() {
+        let mut cfg = zeroed::<GlobalConfig>();
+        cfg.protocol_fee_bps = 250;
+        assert_eq!(cfg.fee_rates(0, u64::MAX), (250, 0));
+
+        cfg.fee_tiers[0] = FeeTier {
+            min_volume: 0,
+            maker_fee_bps: 200,
+            taker_fee_bps: 300,
+        };
+        cfg.fee_tiers[1] = FeeTier {
+            min_volume: 1_000,
+            maker_fee_bps: 100,
+            taker_fee_bps: 200,
+        };
+        cfg.fee_tier_count = 2;
+        assert_eq!(cfg.fee_rates(999, 1_000), (200, 200));
+        assert_eq!(cfg.fee_rates(5_000, 0), (100, 300));
+    }
+
+    #[test]
+    fn stake_discount_uses_configured_tiers_only() {
+        let mut cfg = zeroed::<GlobalConfig>();
+        cfg.stake_discount_tiers[0] = StakeDiscountTier {
+            min_stake: 100,
+            discount_bps: 1_000,
+        };
+        cfg.stake_discount_tiers[1] = StakeDiscountTier {
+            min_stake: 1_000,
+            discount_bps: 2_500,
+        };
+        assert_eq!(cfg.stake_discount_bps(5_000), 0);
+
+        cfg.stake_discount_tier_count = 2;
+        assert_eq!(cfg.stake_discount_bps(99), 0);
+        assert_eq!(cfg.stake_discount_bps(100), 1_000);
+        assert_eq!(cfg.stake_discount_bps(5_000), 2_500);
+    }
+
+    #[test]
+    fn fee_router_splits_are_off_or_sum_to_one_hundred_percent() {
+        assert!(FeeRouterSplits::default().validate().is_ok());
+        let splits = FeeRouterSplits {
+            treasury_bps: 5_000,
+            lp_growth_bps: 3_000,
+            burn_bps: 2_000,
+        };
+        assert!(splits.validate().is_ok());
+        let short = FeeRouterSplits {
+            burn_bps: 1_999,
+            ..splits
+        };
+        assert_eq!(short.validate(), Err(error!(PgError::InvalidFeeRouter)));
+    }
+
+    #[test]
+    fn game_update_params_split_pause_from_config() {
+        let none = GameUpdateParams::default();
+        assert!(!none.touches_pause() && !none.touches_config());
+
+        let pause = GameUpdateParams {
+            paused_settlements: Some(true),
+            ..GameUpdateParams::default()
+        };
+        assert!(pause.touches_pause() && !pause.touches_config());
+
+        // The payout wallet is owner-only, neither pause nor config
+        let payout = GameUpdateParams {
+            new_payout_wallet: Some(Pubkey::new_unique()),
+            ..GameUpdateParams::default()
+        };
+        assert!(!payout.touches_pause() && !payout.touches_config());
+
+        let config = GameUpdateParams {
+            new_fee_bps: Some(100),
+            paused_new: Some(false),
+            ..GameUpdateParams::default()
+        };
+        assert!(config.touches_pause() && config.touches_config());
+    }
+}
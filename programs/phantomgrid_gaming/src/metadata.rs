@@ -0,0 +1,330 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    PgError, RoyaltyEnforcement, RoyaltySplit, MAX_ROYALTY_BPS, MAX_ROYALTY_RECIPIENTS,
+    MIN_ROYALTY_BPS,
+};
+
+// ============================================================================
+// METAPLEX TOKEN METADATA - Creator Royalties
+// ============================================================================
+//
+// Only the prefix of the Metadata account needed for royalties is parsed (Borsh):
+//   key: u8 | update_authority: Pubkey | mint: Pubkey
+//   name: String | symbol: String | uri: String
+//   seller_fee_basis_points: u16
+//   creators: Option<Vec<{ address: Pubkey, verified: bool, share: u8 }>>
+//
+// PDA Seeds:
+// - Metadata: ["metadata", TOKEN_METADATA_PROGRAM_ID, mint] (owned by Token Metadata)
+// ============================================================================
+
+/// Metaplex Token Metadata program
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+/// `Key::MetadataV1` discriminator
+const METADATA_V1_KEY: u8 = 4;
+
+/// Creator shares are whole percentages summing to 100
+const CREATOR_SHARE_TOTAL: u16 = 100;
+
+/// Metadata PDA for an item mint
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            METADATA_SEED,
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Royalty terms read from a Metadata account
+pub struct MetadataRoyalties {
+    pub seller_fee_basis_points: u16,
+    /// Creators with a non-zero share, in metadata order: (address, share %)
+    pub creators: Vec<(Pubkey, u8)>,
+}
+
+/// Bounds-checked Borsh cursor over raw account data
+struct MetadataReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> MetadataReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .ok_or(PgError::InvalidMetadata)?;
+        let bytes = self
+            .data
+            .get(self.offset..end)
+            .ok_or(PgError::InvalidMetadata)?;
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        let bytes = self.take(32)?;
+        Ok(Pubkey::new_from_array(
+            bytes.try_into().map_err(|_| PgError::InvalidMetadata)?,
+        ))
+    }
+
+    fn skip_string(&mut self) -> Result<()> {
+        let len = usize::try_from(self.u32()?).map_err(|_| PgError::InvalidMetadata)?;
+        self.take(len)?;
+        Ok(())
+    }
+}
+
+/// Parse creators and `seller_fee_basis_points` from Metadata account data for `mint`.
+///
+/// # Errors
+/// - `PgError::InvalidMetadata` if the data is not a `MetadataV1` account for `mint` or is truncated
+/// - `PgError::InvalidCreatorShares` if creator shares don't sum to 100
+pub fn parse_metadata_royalties(data: &[u8], mint: &Pubkey) -> Result<MetadataRoyalties> {
+    let mut reader = MetadataReader { data, offset: 0 };
+    require!(reader.u8()? == METADATA_V1_KEY, PgError::InvalidMetadata);
+    let _update_authority = reader.pubkey()?;
+    require!(reader.pubkey()? == *mint, PgError::InvalidMetadata);
+    reader.skip_string()?; // name
+    reader.skip_string()?; // symbol
+    reader.skip_string()?; // uri
+    let seller_fee_basis_points = reader.u16()?;
+
+    let mut creators = Vec::new();
+    if reader.u8()? == 1 {
+        let count = usize::try_from(reader.u32()?).map_err(|_| PgError::InvalidMetadata)?;
+        require!(
+            count <= MAX_ROYALTY_RECIPIENTS,
+            PgError::InvalidCreatorShares
+        );
+        let mut total_share = 0u16;
+        for _ in 0..count {
+            let address = reader.pubkey()?;
+            let _verified = reader.u8()?;
+            let share = reader.u8()?;
+            total_share = total_share
+                .checked_add(u16::from(share))
+                .ok_or(PgError::Overflow)?;
+            if share > 0 {
+                creators.push((address, share));
+            }
+        }
+        require!(
+            total_share == CREATOR_SHARE_TOTAL,
+            PgError::InvalidCreatorShares
+        );
+    }
+
+    Ok(MetadataRoyalties {
+        seller_fee_basis_points,
+        creators,
+    })
+}
+
+/// Resolve the royalty a listing must carry under the game's `RoyaltyEnforcement` policy.
+///
+/// When metadata applies, its creators and `seller_fee_basis_points` replace the seller's
+/// `royalty_recipient`/`royalty_bps`. Multiple creators must be passed as a `RoyaltySplit`
+/// whose recipients and weights mirror the creators exactly. Metadata fees below
+/// `MIN_ROYALTY_BPS` are dropped (dust) and fees above `MAX_ROYALTY_BPS` are capped so
+/// listings stay settleable.
+///
+/// # Errors
+/// - `PgError::MetadataRequired` if royalties are enforced and no metadata account is passed
+/// - `PgError::InvalidMetadata` if the account is not the mint's Metadata PDA or fails to parse
+/// - `PgError::InvalidCreatorShares` if creator shares don't sum to 100
+/// - `PgError::RoyaltySplitMismatch` if the royalty split doesn't mirror the metadata creators
+pub fn resolve_listing_royalty(
+    enforcement: RoyaltyEnforcement,
+    metadata: Option<&AccountInfo>,
+    mint: &Pubkey,
+    royalty_split: Option<&RoyaltySplit>,
+    royalty_recipient: Pubkey,
+    royalty_bps: u16,
+) -> Result<(Pubkey, u16)> {
+    let metadata = match (enforcement, metadata) {
+        (RoyaltyEnforcement::Ignored, _) | (RoyaltyEnforcement::Optional, None) => {
+            return Ok((royalty_recipient, royalty_bps));
+        }
+        (RoyaltyEnforcement::Enforced, None) => return err!(PgError::MetadataRequired),
+        (_, Some(metadata)) => metadata,
+    };
+    require!(
+        metadata.key() == metadata_address(mint),
+        PgError::InvalidMetadata
+    );
+    // Mint has no Metaplex metadata: nothing to enforce
+    if metadata.data_is_empty() {
+        return Ok((royalty_recipient, royalty_bps));
+    }
+    require!(
+        metadata.owner == &TOKEN_METADATA_PROGRAM_ID,
+        PgError::InvalidMetadata
+    );
+
+    let royalties = parse_metadata_royalties(&metadata.try_borrow_data()?, mint)?;
+    let bps = royalties.seller_fee_basis_points.min(MAX_ROYALTY_BPS);
+    if bps < MIN_ROYALTY_BPS || royalties.creators.is_empty() {
+        require!(royalty_split.is_none(), PgError::RoyaltySplitMismatch);
+        return Ok((Pubkey::default(), 0));
+    }
+
+    if royalties.creators.len() == 1 {
+        require!(royalty_split.is_none(), PgError::RoyaltySplitMismatch);
+    } else {
+        let split = royalty_split.ok_or(PgError::RoyaltySplitMismatch)?;
+        require!(
+            usize::from(split.recipient_count) == royalties.creators.len(),
+            PgError::RoyaltySplitMismatch
+        );
+        for ((address, share), (recipient, weight)) in royalties
+            .creators
+            .iter()
+            .zip(split.recipients.iter().zip(split.weights.iter()))
+        {
+            require!(recipient == address, PgError::RoyaltySplitMismatch);
+            // Creator share is a percentage; split weights are bps of the royalty
+            require!(
+                *weight == u16::from(*share) * 100,
+                PgError::RoyaltySplitMismatch
+            );
+        }
+    }
+
+    Ok((royalties.creators[0].0, bps))
+}
+
+/// Resolve the one royalty carried by a sale of several item mints (bundles, swap top-ups).
+///
+/// Each `(mint, metadata)` pair goes through `resolve_listing_royalty`. Items that resolve to
+/// no royalty are skipped; all others must resolve to the same recipient and bps, which
+/// becomes the sale's royalty.
+///
+/// # Errors
+/// - Any error of `resolve_listing_royalty`
+/// - `PgError::MixedItemRoyalties` if items resolve to different royalties
+pub fn resolve_items_royalty(
+    enforcement: RoyaltyEnforcement,
+    items: &[(Pubkey, Option<&AccountInfo>)],
+    royalty_split: Option<&RoyaltySplit>,
+    royalty_recipient: Pubkey,
+    royalty_bps: u16,
+) -> Result<(Pubkey, u16)> {
+    let mut resolved = (Pubkey::default(), 0u16);
+    for (mint, metadata) in items {
+        let royalty = resolve_listing_royalty(
+            enforcement,
+            *metadata,
+            mint,
+            royalty_split,
+            royalty_recipient,
+            royalty_bps,
+        )?;
+        if royalty.1 == 0 {
+            continue;
+        }
+        require!(
+            resolved.1 == 0 || resolved == royalty,
+            PgError::MixedItemRoyalties
+        );
+        resolved = royalty;
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `MetadataV1` account prefix for `mint` (see the layout at the top of this file)
+    fn metadata_data(
+        mint: &Pubkey,
+        seller_fee_bps: u16,
+        creators: Option<&[(Pubkey, u8)]>,
+    ) -> Vec<u8> {
+        let mut data = vec![METADATA_V1_KEY];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(mint.as_ref());
+        for field in ["Sword", "SWD", "https://example.com/sword.json"] {
+            data.extend_from_slice(&u32::try_from(field.len()).unwrap().to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data.extend_from_slice(&seller_fee_bps.to_le_bytes());
+        match creators {
+            Some(creators) => {
+                data.push(1);
+                data.extend_from_slice(&u32::try_from(creators.len()).unwrap().to_le_bytes());
+                for (address, share) in creators {
+                    data.extend_from_slice(address.as_ref());
+                    data.push(1);
+                    data.push(*share);
+                }
+            }
+            None => data.push(0),
+        }
+        data
+    }
+
+    #[test]
+    fn parse_metadata_royalties_reads_fee_and_paid_creators() {
+        let mint = Pubkey::new_unique();
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let data = metadata_data(&mint, 500, Some(&[(a, 70), (b, 0), (c, 30)]));
+        let royalties = parse_metadata_royalties(&data, &mint).unwrap();
+        assert_eq!(royalties.seller_fee_basis_points, 500);
+        assert_eq!(royalties.creators, vec![(a, 70), (c, 30)]);
+
+        let data = metadata_data(&mint, 250, None);
+        let royalties = parse_metadata_royalties(&data, &mint).unwrap();
+        assert_eq!(royalties.seller_fee_basis_points, 250);
+        assert!(royalties.creators.is_empty());
+    }
+
+    #[test]
+    fn parse_metadata_royalties_rejects_bad_accounts() {
+        let mint = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let data = metadata_data(&mint, 500, Some(&[(creator, 100)]));
+
+        let wrong_mint = parse_metadata_royalties(&data, &Pubkey::new_unique());
+        assert_eq!(wrong_mint.err(), Some(error!(PgError::InvalidMetadata)));
+        let truncated = parse_metadata_royalties(&data[..data.len() - 1], &mint);
+        assert_eq!(truncated.err(), Some(error!(PgError::InvalidMetadata)));
+        let mut wrong_key = data.clone();
+        wrong_key[0] = 0;
+        let wrong_key = parse_metadata_royalties(&wrong_key, &mint);
+        assert_eq!(wrong_key.err(), Some(error!(PgError::InvalidMetadata)));
+
+        let data = metadata_data(&mint, 500, Some(&[(creator, 60), (creator, 30)]));
+        let shares = parse_metadata_royalties(&data, &mint);
+        assert_eq!(shares.err(), Some(error!(PgError::InvalidCreatorShares)));
+    }
+}
@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use solana_program::hash::hashv;
+
+use crate::{GlobalConfig, PgError};
+
+// ============================================================================
+// PDOX STAKE - Protocol Fee Discounts
+// ============================================================================
+//
+// Stakers of the `pdox_stake` program get a tiered discount on the protocol fee.
+// Only the prefix of its `AgentAccount` needed here is parsed (Anchor/Borsh):
+//   discriminator: [u8; 8] | owner: Pubkey | stake: u64 | reputation: u8
+//   jobs_completed: u64 | jobs_failed: u64 | created_at: i64 | last_active: i64
+//   is_active: bool
+//
+// PDA Seeds:
+// - AgentAccount: ["agent", staker] (owned by `GlobalConfig.pdox_stake_program`)
+// ============================================================================
+
+pub const PDOX_STAKE_AGENT_SEED: &[u8] = b"agent";
+
+/// Byte offsets into `AgentAccount` data
+const AGENT_OWNER_OFFSET: usize = 8;
+const AGENT_STAKE_OFFSET: usize = 40;
+const AGENT_IS_ACTIVE_OFFSET: usize = 81;
+
+/// `AgentAccount` PDA of `staker` in the stake program
+pub fn agent_account_address(staker: &Pubkey, stake_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PDOX_STAKE_AGENT_SEED, staker.as_ref()], stake_program).0
+}
+
+/// Active PDOX stake of `staker` read from its `pdox_stake` `AgentAccount` (0 if inactive).
+///
+/// # Errors
+/// - `PgError::InvalidStakeAccount` if no stake program is configured, or the account is not
+///   `staker`'s `AgentAccount` PDA owned by it, or its data doesn't parse
+pub fn staked_amount(
+    stake_account: &AccountInfo,
+    staker: &Pubkey,
+    stake_program: &Pubkey,
+) -> Result<u64> {
+    require!(
+        *stake_program != Pubkey::default(),
+        PgError::InvalidStakeAccount
+    );
+    require!(
+        stake_account.owner == stake_program,
+        PgError::InvalidStakeAccount
+    );
+    require!(
+        stake_account.key() == agent_account_address(staker, stake_program),
+        PgError::InvalidStakeAccount
+    );
+
+    let data = stake_account.try_borrow_data()?;
+    require!(
+        data.len() > AGENT_IS_ACTIVE_OFFSET,
+        PgError::InvalidStakeAccount
+    );
+    let discriminator = hashv(&[b"account:AgentAccount"]).to_bytes();
+    require!(
+        data[..8] == discriminator[..8],
+        PgError::InvalidStakeAccount
+    );
+    require!(
+        data[AGENT_OWNER_OFFSET..AGENT_STAKE_OFFSET] == staker.to_bytes(),
+        PgError::InvalidStakeAccount
+    );
+    if data[AGENT_IS_ACTIVE_OFFSET] == 0 {
+        return Ok(0);
+    }
+    let stake = data[AGENT_STAKE_OFFSET..AGENT_STAKE_OFFSET + 8]
+        .try_into()
+        .map_err(|_| PgError::InvalidStakeAccount)?;
+    Ok(u64::from_le_bytes(stake))
+}
+
+/// Protocol fee discount (bps of the fee) for an optional stake account of `staker`.
+///
+/// # Errors
+/// - Any `staked_amount` error if a stake account is passed
+pub fn stake_discount_bps(
+    cfg: &GlobalConfig,
+    stake_account: Option<&AccountInfo>,
+    staker: &Pubkey,
+) -> Result<u16> {
+    match stake_account {
+        Some(account) => {
+            Ok(cfg.stake_discount_bps(staked_amount(account, staker, &cfg.pdox_stake_program)?))
+        }
+        None => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::zeroed;
+    use crate::StakeDiscountTier;
+
+    /// `AgentAccount` data for `staker` (see the layout at the top of this file)
+    fn agent_data(staker: &Pubkey, stake: u64, is_active: bool) -> Vec<u8> {
+        let mut data = vec![0u8; AGENT_IS_ACTIVE_OFFSET + 1];
+        data[..8].copy_from_slice(&hashv(&[b"account:AgentAccount"]).to_bytes()[..8]);
+        data[AGENT_OWNER_OFFSET..AGENT_STAKE_OFFSET].copy_from_slice(staker.as_ref());
+        data[AGENT_STAKE_OFFSET..AGENT_STAKE_OFFSET + 8].copy_from_slice(&stake.to_le_bytes());
+        data[AGENT_IS_ACTIVE_OFFSET] = u8::from(is_active);
+        data
+    }
+
+    fn read_stake(
+        key: &Pubkey,
+        owner: &Pubkey,
+        data: &mut [u8],
+        staker: &Pubkey,
+        stake_program: &Pubkey,
+    ) -> Result<u64> {
+        let mut lamports = 0;
+        let info = AccountInfo::new(key, false, false, &mut lamports, data, owner, false, 0);
+        staked_amount(&info, staker, stake_program)
+    }
+
+    #[test]
+    fn staked_amount_reads_active_stake_from_the_agent_pda() {
+        let program = Pubkey::new_unique();
+        let staker = Pubkey::new_unique();
+        let agent = agent_account_address(&staker, &program);
+
+        let mut data = agent_data(&staker, 5_000, true);
+        assert_eq!(
+            read_stake(&agent, &program, &mut data, &staker, &program),
+            Ok(5_000)
+        );
+        let mut data = agent_data(&staker, 5_000, false);
+        assert_eq!(
+            read_stake(&agent, &program, &mut data, &staker, &program),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn staked_amount_rejects_foreign_or_malformed_accounts() {
+        let program = Pubkey::new_unique();
+        let staker = Pubkey::new_unique();
+        let agent = agent_account_address(&staker, &program);
+        let invalid = Err(error!(PgError::InvalidStakeAccount));
+
+        let mut data = agent_data(&staker, 5_000, true);
+        // No stake program configured
+        assert_eq!(
+            read_stake(&agent, &program, &mut data, &staker, &Pubkey::default()),
+            invalid
+        );
+        // Not owned by the stake program
+        let other = Pubkey::new_unique();
+        assert_eq!(
+            read_stake(&agent, &other, &mut data, &staker, &program),
+            invalid
+        );
+        // Not the staker's agent PDA
+        assert_eq!(
+            read_stake(&other, &program, &mut data, &staker, &program),
+            invalid
+        );
+        // Agent of another wallet stored at the staker's address
+        let mut data = agent_data(&other, 5_000, true);
+        assert_eq!(
+            read_stake(&agent, &program, &mut data, &staker, &program),
+            invalid
+        );
+        // Truncated
+        let mut data = agent_data(&staker, 5_000, true);
+        assert_eq!(
+            read_stake(
+                &agent,
+                &program,
+                &mut data[..AGENT_IS_ACTIVE_OFFSET],
+                &staker,
+                &program
+            ),
+            invalid
+        );
+    }
+
+    #[test]
+    fn stake_discount_bps_is_zero_without_a_stake_account() {
+        let program = Pubkey::new_unique();
+        let staker = Pubkey::new_unique();
+        let agent = agent_account_address(&staker, &program);
+        let mut cfg = zeroed::<GlobalConfig>();
+        cfg.pdox_stake_program = program;
+        cfg.stake_discount_tiers[0] = StakeDiscountTier {
+            min_stake: 1_000,
+            discount_bps: 2_000,
+        };
+        cfg.stake_discount_tier_count = 1;
+
+        assert_eq!(stake_discount_bps(&cfg, None, &staker), Ok(0));
+
+        let mut lamports = 0;
+        let mut data = agent_data(&staker, 1_000, true);
+        let info = AccountInfo::new(
+            &agent,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program,
+            false,
+            0,
+        );
+        assert_eq!(stake_discount_bps(&cfg, Some(&info), &staker), Ok(2_000));
+    }
+}